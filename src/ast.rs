@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt;
 
 #[derive(Debug)]
 pub enum Stmt {
@@ -7,7 +8,7 @@ pub enum Stmt {
     },
     VarDeclaration {
         constant: bool,
-        identifier: String,
+        pattern: Pattern,
         value: Option<Expr>,
         var_type: Type,
     },
@@ -19,12 +20,40 @@ pub enum Stmt {
 
     FunctionDeclaration {
         name: String,
-        parameters: Vec<String>,
+        parameters: Vec<Pattern>,
         param_types: Vec<Type>,
+        param_defaults: Vec<Option<Expr>>,
+        has_rest_parameter: bool,
         return_type: Type,
         body: Vec<Stmt>,
         is_async: bool,
     },
+    ClassDeclaration {
+        name: String,
+        parent: Option<String>,
+        members: Vec<ClassMember>,
+    },
+    InterfaceDeclaration {
+        name: String,
+        members: Vec<InterfaceMember>,
+    },
+    ImplDeclaration {
+        interface: String,
+        target: String,
+        methods: Vec<Stmt>,
+    },
+    Import {
+        names: Vec<String>,
+        source: String,
+    },
+    Export {
+        declaration: Box<Stmt>,
+    },
+    /// External JS value, the declaration is a body-less function, a const
+    /// without value or, for `declare object`, an interface naming the object.
+    Declare {
+        declaration: Box<Stmt>,
+    },
     Expression(Expr),
     IfStatement {
         condition: Expr,
@@ -43,14 +72,14 @@ pub enum Stmt {
 
     TryCatchFinally {
         try_branch: Vec<Stmt>,
-        catch_branch: Vec<Stmt>,
+        catch_clauses: Vec<CatchClause>,
         finally_branch: Option<Vec<Stmt>>,
     },
 
     SwitchStatement {
         condition: Expr,
-        case_branches: Vec<caseBranch>,
-        default_branch: Vec<Stmt>,
+        case_branches: Vec<CaseBranch>,
+        default_branch: Option<Vec<Stmt>>,
     },
 
     ForLoopStatement {
@@ -72,12 +101,40 @@ pub enum Stmt {
         iterable: Option<Expr>,
         body: Vec<Stmt>,
     },
+    ThrowStatement {
+        value: Expr,
+    },
     BreakStatement,
     ContinueStatement,
+    /// `#[allow(rule, ...)]` in front of a statement, `#![...]` for the whole
+    /// file. Only the lint pass looks at them.
+    Attribute {
+        name: String,
+        arguments: Vec<String>,
+        applies_to_file: bool,
+    },
+}
+
+/// Identifies an expression among the ones its parser produced. Unlike the
+/// address of the node it survives moving and cloning the program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExprId(pub u32);
+
+#[derive(Clone)]
+pub struct Expr {
+    pub id: ExprId,
+    pub kind: ExprKind,
+}
+
+// ids would only clutter printed programs and error messages
+impl fmt::Debug for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind.fmt(f)
+    }
 }
 
 #[derive(Debug, Clone)]
-pub enum Expr {
+pub enum ExprKind {
     BooleanLiteral(bool),
     NullLiteral,
     CompoundAssignment {
@@ -95,6 +152,19 @@ pub enum Expr {
         value: Box<Expr>,
     },
 
+    Spread {
+        value: Box<Expr>,
+    },
+
+    NamedArgument {
+        name: String,
+        value: Box<Expr>,
+    },
+
+    Propagate {
+        value: Box<Expr>,
+    },
+
     Increment {
         identifier: Box<Expr>,
         prefix: bool,
@@ -132,24 +202,140 @@ pub enum Type {
     Array(Box<Type>),
     Object(HashMap<String, Type>),
     Option(Box<Type>),
+    Result(Box<Type>, Box<Type>),
+    Map(Box<Type>, Box<Type>),
+    Set(Box<Type>),
+    /// what `keys()`, `values()` and `entries()` of maps and sets return
+    Iterator(Box<Type>),
+    Promise(Box<Type>),
     Null,
     Any,
     AliasedType(String),
     Void,
+    StringLiteral(String),
+    NumberLiteral(f64),
+    BooleanLiteral(bool),
+    Union(Vec<Type>),
+}
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Identifier(String),
+    Array {
+        elements: Vec<PatternElement>,
+        rest: Option<Box<Pattern>>,
+    },
+    Object {
+        properties: Vec<PatternProperty>,
+        rest: Option<String>,
+    },
 }
+
+#[derive(Debug, Clone)]
+pub struct PatternElement {
+    pub pattern: Pattern,
+    pub default_value: Option<Expr>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PatternProperty {
+    pub key: String,
+    pub pattern: Pattern,
+    pub default_value: Option<Expr>,
+}
+
+/// Methods and the constructor are stored as `Stmt::FunctionDeclaration`,
+/// the constructor under the name `constructor`.
+#[derive(Debug)]
+pub enum ClassMember {
+    Field {
+        name: String,
+        field_type: Type,
+        value: Option<Expr>,
+        is_static: bool,
+    },
+    Method {
+        declaration: Stmt,
+        is_static: bool,
+    },
+}
+
+/// Methods are stored as `Stmt::FunctionDeclaration` without a body.
+#[derive(Debug)]
+pub enum InterfaceMember {
+    Field { name: String, field_type: Type },
+    Method { declaration: Stmt },
+}
+
 #[derive(Debug)]
 pub struct ElseIfBranch {
     pub condition: Expr,
     pub body: Vec<Stmt>,
 }
 #[derive(Debug)]
-pub struct caseBranch {
+pub struct CatchClause {
+    pub binding: Option<String>,
+    pub error_type: Option<Type>,
+    pub body: Vec<Stmt>,
+}
+#[derive(Debug)]
+pub struct CaseBranch {
     pub condition: Expr,
     pub body: Vec<Stmt>,
 }
 
+/// Spread entries (`...other`) have an empty key and an `ExprKind::Spread` value.
 #[derive(Clone, Debug)]
 pub struct Property {
     pub key: String,
     pub value: Option<Expr>,
 }
+
+/// `column` is the byte offset within the line, both start at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub line: u32,
+    pub column: usize,
+}
+
+/// Where the parser found the statements, expressions and names of a
+/// program. Statements and names are keyed by the address of the node or of
+/// the name's text, so they are only valid as long as the program they were
+/// recorded for is alive and not moved out of its block.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    statement_lines: HashMap<*const Stmt, u32>,
+    names: HashMap<*const u8, Position>,
+    // where the first token of each expression is
+    expressions: HashMap<ExprId, Position>,
+}
+
+impl SourceMap {
+    pub fn record_statements(&mut self, stmts: &[Stmt], lines: &[u32]) {
+        for (stmt, line) in stmts.iter().zip(lines) {
+            self.statement_lines.insert(stmt as *const Stmt, *line);
+        }
+    }
+
+    /// Line of a statement parsed as part of a block.
+    pub fn line_of(&self, stmt: &Stmt) -> Option<u32> {
+        self.statement_lines.get(&(stmt as *const Stmt)).copied()
+    }
+
+    pub fn record_name(&mut self, name: &str, position: Position) {
+        self.names.insert(name.as_ptr(), position);
+    }
+
+    /// Position of the name whose text starts at `address`, see
+    /// `str::as_ptr`.
+    pub fn name_position(&self, address: *const u8) -> Option<Position> {
+        self.names.get(&address).copied()
+    }
+
+    pub fn record_expression(&mut self, id: ExprId, position: Position) {
+        self.expressions.insert(id, position);
+    }
+
+    pub fn expression_position(&self, id: ExprId) -> Option<Position> {
+        self.expressions.get(&id).copied()
+    }
+}
@@ -0,0 +1,137 @@
+use clap::{Parser, Subcommand};
+use lenniscript::fix;
+use lenniscript::lint::Linter;
+use lenniscript::modules::{CheckError, ModuleGraph};
+use std::fs;
+use std::path::Path;
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Typecheckt eine Datei samt Imports und prüft sie mit den Lint-Regeln
+    Lint {
+        file_path: String,
+        /// Konfigurationsdatei statt der nächstgelegenen .lslint
+        #[arg(long)]
+        config: Option<String>,
+        /// Meldet jeden Typ, der nur aus Unkenntnis Any ist
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Behebt Tippfehler in Namen, auf die der Typechecker einen Vorschlag hat
+    Fix { file_path: String },
+}
+
+// eine Korrektur kann keinen neuen Tippfehler einführen, die Grenze schützt
+// nur vor Fehlern in der Korrektur selbst
+const MAX_FIXES: usize = 100;
+
+fn main() {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Lint {
+            file_path,
+            config,
+            strict,
+        } => lint(&file_path, config.as_deref(), strict),
+        Command::Fix { file_path } => fix(&file_path),
+    }
+}
+
+fn load_graph(file_path: &Path) -> ModuleGraph {
+    if !file_path.exists() {
+        eprintln!(
+            "Fehler: Die angegebene Datei existiert nicht: {}",
+            file_path.display()
+        );
+        std::process::exit(1);
+    }
+    match ModuleGraph::load(file_path) {
+        Ok(graph) => graph,
+        Err(e) => {
+            eprintln!("Fehler: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// Der Typechecker bricht beim ersten Fehler ab, deshalb wird eine Korrektur
+// nach der anderen angewendet.
+fn fix(file_path: &str) {
+    let file_path = Path::new(file_path);
+    for _ in 0..MAX_FIXES {
+        let graph = load_graph(file_path);
+        let (path, edit) = match graph.check_fixable() {
+            Ok(()) => {
+                println!("Keine Typfehler");
+                return;
+            }
+            Err(CheckError { fix: Some(fix), .. }) => fix,
+            Err(CheckError { message, .. }) => {
+                eprintln!("Typfehler: {}", message);
+                std::process::exit(1);
+            }
+        };
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Fehler beim Lesen von {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        };
+        if let Err(e) = fs::write(&path, fix::apply(&source, &edit)) {
+            eprintln!("Fehler beim Schreiben von {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+        println!(
+            "{}:{}:{}: durch '{}' ersetzt",
+            path.display(),
+            edit.span.line,
+            edit.span.column,
+            edit.replacement
+        );
+    }
+    eprintln!("Fehler: mehr als {} Korrekturen, abgebrochen", MAX_FIXES);
+    std::process::exit(1);
+}
+
+fn lint(file_path: &str, config: Option<&str>, strict: bool) {
+    let file_path = Path::new(file_path);
+    let mut graph = load_graph(file_path);
+    graph.set_strict(strict);
+
+    let mut linter = Linter::new();
+    let config_path = match config {
+        Some(config) => Some(Path::new(config).to_path_buf()),
+        None => Linter::find_config(file_path),
+    };
+    if let Some(config_path) = config_path {
+        if let Err(e) = linter.load_config(&config_path) {
+            eprintln!("Fehler: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let (warnings, errors) = match graph.lint(&linter) {
+        Ok(diagnostics) => diagnostics,
+        Err(e) => {
+            eprintln!("Typfehler: {}", e);
+            std::process::exit(1);
+        }
+    };
+    for warning in &warnings {
+        eprintln!("Warnung: {}", warning);
+    }
+    for error in &errors {
+        eprintln!("Fehler: {}", error);
+    }
+    if !errors.is_empty() {
+        std::process::exit(1);
+    }
+}
@@ -1,15 +1,18 @@
 use clap::Parser;
+use lenniscript::modules::ModuleGraph;
 use std::fs;
 use std::io::Write;
 use std::path::Path;
 use std::process::Command;
-use lenniscript::compiler::Compiler;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
     #[arg(required = true)]
     file_path: String,
+    /// Meldet jeden Typ, der nur aus Unkenntnis Any ist
+    #[arg(long)]
+    strict: bool,
 }
 
 fn main() {
@@ -30,33 +33,45 @@ fn main() {
         std::process::exit(1);
     }
 
-    // Lesen des Inhalts der .ls-Datei
-    let source = match fs::read_to_string(file_path) {
-        Ok(content) => content,
+    // Einlesen der Datei samt aller importierten Module
+    let mut graph = match ModuleGraph::load(file_path) {
+        Ok(graph) => graph,
         Err(e) => {
-            eprintln!("Fehler beim Lesen der Datei: {}", e);
+            eprintln!("Fehler: {}", e);
             std::process::exit(1);
         }
     };
 
-    // Importieren und Ausführen der benötigten Module aus Ihrem Projekt
-    let mut parser = lenniscript::parser::Parser::new();
-    let ast = parser.produceAst(&source);
-    println!("Geparster Code: {:?}", &ast);
-
-    // Compiler initialisieren
-    let mut compiler = Compiler::new();
+    graph.set_strict(cli.strict);
+    match graph.check() {
+        Ok(warnings) => {
+            for warning in warnings {
+                eprintln!("Warnung: {}", warning);
+            }
+        }
+        Err(e) => {
+            eprintln!("Typfehler: {}", e);
+            std::process::exit(1);
+        }
+    }
 
-    // AST kompilieren
-    let compiled_output = compiler.compile_programm(&ast);
+    let outputs = match graph.compile() {
+        Ok(outputs) => outputs,
+        Err(e) => {
+            eprintln!("Fehler beim Kompilieren: {}", e);
+            std::process::exit(1);
+        }
+    };
 
-    // Speichern der kompilierten Ausgabe in eine JavaScript-Datei
-    let js_output_path = "temp_output.js";
-    let mut output_file =
-        fs::File::create(js_output_path).expect("Konnte temporäre Ausgabedatei nicht erstellen");
-    output_file
-        .write_all(compiled_output.as_bytes())
-        .expect("Konnte nicht in temporäre Ausgabedatei schreiben");
+    // Jedes Modul wird als ES-Modul neben seiner Quelldatei gespeichert
+    for (output_path, compiled_output) in &outputs {
+        let mut output_file =
+            fs::File::create(output_path).expect("Konnte temporäre Ausgabedatei nicht erstellen");
+        output_file
+            .write_all(compiled_output.as_bytes())
+            .expect("Konnte nicht in temporäre Ausgabedatei schreiben");
+    }
+    let js_output_path = &outputs.last().expect("Einstiegsmodul fehlt").0;
 
     // Ausführen des generierten JavaScript-Codes mit Node.js - wichtige Änderung hier:
     println!("\nAusgabe des Programms:");
@@ -69,8 +84,10 @@ fn main() {
         .wait()
         .expect("Fehler beim Ausführen des JavaScript-Codes");
 
-    // Löschen der temporären Datei
-    fs::remove_file(js_output_path).ok();
+    // Löschen der temporären Dateien
+    for (output_path, _) in &outputs {
+        fs::remove_file(output_path).ok();
+    }
 
     if !status.success() {
         eprintln!(
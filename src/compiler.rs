@@ -1,9 +1,46 @@
-use crate::ast::{Expr, Stmt};
-use crate::typechecker::TypeChecker;
+use crate::ast::{CatchClause, ClassMember, Expr, ExprKind, Pattern, Stmt, Type};
+use crate::typechecker::ModuleExports;
+use std::collections::{HashMap, HashSet};
+
+const PROPAGATION_RUNTIME: &str = "class __Propagation {
+    constructor(result) {
+        this.result = result
+    }
+}
+function __unwrap(result) {
+    if (!result.ok) throw new __Propagation(result)
+    return result.value
+}
+";
 
 pub struct Compiler {
     pub output: String,
     pub indent_level: usize,
+    // parameter names of the functions visible in each enclosing block,
+    // inner blocks last
+    function_params: Vec<HashMap<String, Vec<Option<String>>>>,
+    // calls to these are instantiations and need `new`
+    class_names: HashSet<String>,
+    // declarations of imported modules, keyed by the path used in the import
+    dependencies: HashMap<String, Compiler>,
+    external_sources: HashSet<String>,
+    type_names: HashSet<String>,
+    // `?` throws its failed Result as a sentinel, the function it is in
+    // catches it and returns it
+    propagations: usize,
+    uses_propagation: bool,
+    // scripts have no top-level await, they get wrapped in an async function
+    es_module: bool,
+    function_depth: usize,
+    uses_top_level_await: bool,
+    // first call that cannot be compiled, expressions have no way to fail
+    error: Option<String>,
+}
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Compiler {
@@ -25,21 +62,251 @@ impl Compiler {
         Compiler {
             output: String::new(),
             indent_level: 0,
+            function_params: vec![HashMap::new()],
+            class_names: HashSet::new(),
+            dependencies: HashMap::new(),
+            external_sources: HashSet::new(),
+            type_names: HashSet::new(),
+            propagations: 0,
+            uses_propagation: false,
+            es_module: false,
+            function_depth: 0,
+            uses_top_level_await: false,
+            error: None,
         }
     }
 
+    /// ES modules keep top-level `await` as it is.
+    pub fn set_es_module(&mut self, es_module: bool) {
+        self.es_module = es_module;
+    }
+
     pub fn compile_programm(&mut self, program: &Stmt) -> Result<String, String> {
         if let Stmt::Program { body } = program {
+            self.collect_function_params(body);
             for stmt in body {
                 let stmt = self.compile_stmt(stmt);
+                // declarations only the checker needs leave no empty line
+                if stmt.is_empty() {
+                    continue;
+                }
                 self.output.push_str(&stmt);
                 self.output.push('\n');
             }
         }
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        if self.uses_top_level_await && !self.es_module {
+            self.output = format!("(async () => {{\n{}}})();\n", self.output);
+        }
+        if self.uses_propagation {
+            self.output.insert_str(0, PROPAGATION_RUNTIME);
+        }
 
         Ok(self.output.clone())
     }
 
+    // Named arguments are reordered into positional ones, so every call needs
+    // the parameter names of its callee.
+    /// Makes the declarations of an imported module known, so imported
+    /// classes get `new` and named arguments work across modules.
+    pub fn register_dependency(&mut self, source: &str, program: &Stmt) {
+        let mut dependency = Compiler::new();
+        if let Stmt::Program { body } = program {
+            dependency.collect_function_params(body);
+        }
+        self.dependencies.insert(source.to_string(), dependency);
+    }
+
+    /// JS module described by typings, imported under its own path.
+    pub fn register_external_dependency(&mut self, source: &str, declarations: &ModuleExports) {
+        let mut dependency = Compiler::new();
+        dependency.function_params = vec![declarations.function_param_names()];
+        dependency.type_names = declarations.type_names().into_iter().collect();
+        self.dependencies.insert(source.to_string(), dependency);
+        self.external_sources.insert(source.to_string());
+    }
+
+    /// Rewrites `./math.ls` to the path of the emitted module.
+    pub fn output_path(source: &str) -> String {
+        match source.strip_suffix(".ls") {
+            Some(stem) => format!("{}.mjs", stem),
+            None => format!("{}.mjs", source),
+        }
+    }
+
+    fn compile_import(&mut self, stmt: &Stmt) -> String {
+        if let Stmt::Import { names, source } = stmt {
+            let dependency = self
+                .dependencies
+                .get(source)
+                .unwrap_or_else(|| panic!("Modul '{}' wurde nicht registriert", source));
+
+            // types only exist for the typechecker
+            let mut value_names = Vec::new();
+            for name in names {
+                if dependency.type_names.contains(name) {
+                    continue;
+                }
+                if dependency.class_names.contains(name) {
+                    self.class_names.insert(name.clone());
+                }
+                if let Some(param_names) = dependency.param_names_of(name) {
+                    // imports are top-level, `dependencies` is borrowed
+                    self.function_params[0].insert(name.clone(), param_names.clone());
+                }
+                value_names.push(name.clone());
+            }
+
+            if value_names.is_empty() {
+                return String::new();
+            }
+            let output_path = if self.external_sources.contains(source) {
+                source.clone()
+            } else {
+                Self::output_path(source)
+            };
+            format!(
+                "import {{ {} }} from \"{}\"",
+                value_names.join(", "),
+                output_path
+            )
+        } else {
+            panic!("Import expected")
+        }
+    }
+
+    fn compile_export(&mut self, stmt: &Stmt) -> String {
+        if let Stmt::Export { declaration } = stmt {
+            match declaration.as_ref() {
+                Stmt::TypeAlias { .. } | Stmt::InterfaceDeclaration { .. } => String::new(),
+                _ => format!("export {}", self.compile_stmt(declaration)),
+            }
+        } else {
+            panic!("Export expected")
+        }
+    }
+
+    fn param_names(declaration: &Stmt) -> Vec<Option<String>> {
+        if let Stmt::FunctionDeclaration {
+            parameters,
+            has_rest_parameter,
+            ..
+        } = declaration
+        {
+            let fixed_count = if *has_rest_parameter {
+                parameters.len() - 1
+            } else {
+                parameters.len()
+            };
+            parameters[..fixed_count]
+                .iter()
+                .map(|parameter| match parameter {
+                    Pattern::Identifier(param_name) => Some(param_name.clone()),
+                    _ => None,
+                })
+                .collect()
+        } else {
+            panic!("Function declaration expected")
+        }
+    }
+
+    // Only the declarations of this block, nested blocks collect their own
+    // when they are compiled.
+    fn collect_function_params(&mut self, body: &[Stmt]) {
+        for stmt in body {
+            match stmt {
+                Stmt::FunctionDeclaration { name, .. } => {
+                    self.declare_function_params(name, Self::param_names(stmt));
+                }
+                Stmt::Export { declaration } => {
+                    self.collect_function_params(std::slice::from_ref(declaration))
+                }
+                Stmt::Declare { declaration } => {
+                    if let Stmt::FunctionDeclaration { name, .. } = declaration.as_ref() {
+                        self.declare_function_params(name, Self::param_names(declaration));
+                    }
+                }
+                Stmt::TypeAlias { name, .. } | Stmt::InterfaceDeclaration { name, .. } => {
+                    self.type_names.insert(name.clone());
+                }
+                Stmt::ClassDeclaration {
+                    name,
+                    parent,
+                    members,
+                } => {
+                    self.class_names.insert(name.clone());
+                    let inherited_params = parent
+                        .as_ref()
+                        .and_then(|parent| self.param_names_of(parent).cloned())
+                        .unwrap_or_default();
+                    self.declare_function_params(name, inherited_params);
+                    for member in members {
+                        if let ClassMember::Method {
+                            declaration:
+                                declaration @ Stmt::FunctionDeclaration {
+                                    name: method_name, ..
+                                },
+                            ..
+                        } = member
+                        {
+                            if method_name == "constructor" {
+                                self.declare_function_params(name, Self::param_names(declaration));
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn declare_function_params(&mut self, name: &str, param_names: Vec<Option<String>>) {
+        self.function_params
+            .last_mut()
+            .expect("Kein Block offen")
+            .insert(name.to_string(), param_names);
+    }
+
+    fn param_names_of(&self, name: &str) -> Option<&Vec<Option<String>>> {
+        self.function_params
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+    }
+
+    // Compiles the statements of a block, one per line, each followed by
+    // `terminator`.
+    fn compile_block(&mut self, body: &[Stmt], terminator: &str) -> String {
+        self.increase_indent();
+        let compiled_block = self.compile_block_lines(body, terminator);
+        self.decrease_indent();
+        compiled_block
+    }
+
+    // Like `compile_block`, at the current indentation.
+    fn compile_block_lines(&mut self, body: &[Stmt], terminator: &str) -> String {
+        self.function_params.push(HashMap::new());
+        self.collect_function_params(body);
+        let mut compiled_block = String::new();
+        for stmt in body {
+            let compiled_stmt = self.compile_stmt(stmt);
+            if compiled_stmt.is_empty() {
+                continue;
+            }
+            compiled_block.push_str(&format!(
+                "{}{}{}\n",
+                self.get_indent(),
+                compiled_stmt,
+                terminator
+            ));
+        }
+        self.function_params.pop();
+        compiled_block
+    }
+
     fn compile_stmt(&mut self, stmt: &Stmt) -> String {
         match stmt {
             Stmt::VarDeclaration { .. } => self.compile_var_declaration(stmt),
@@ -47,10 +314,18 @@ impl Compiler {
             Stmt::IfStatement { .. } => self.compile_if_stmt(stmt),
             Stmt::WhileStatement { .. } => self.compile_while_stmt(stmt),
             Stmt::FunctionDeclaration { .. } => self.compile_fun_declaration(stmt),
+            Stmt::ClassDeclaration { .. } => self.compile_class_declaration(stmt),
+            Stmt::InterfaceDeclaration { .. } | Stmt::Declare { .. } | Stmt::Attribute { .. } => {
+                String::new()
+            }
+            Stmt::Import { .. } => self.compile_import(stmt),
+            Stmt::Export { .. } => self.compile_export(stmt),
+            Stmt::ImplDeclaration { .. } => self.compile_impl_declaration(stmt),
             Stmt::ForLoopStatement { .. } => self.compile_for_loop(stmt),
             Stmt::ForInLoopStatement { .. } => self.compile_for_in_loop(stmt),
             Stmt::ForLoopIterated { .. } => self.compile_for_iter_loop(stmt),
             Stmt::TryCatchFinally { .. } => self.compile_try_catch_stmt(stmt),
+            Stmt::ThrowStatement { value } => format!("throw {}", self.compile_expr(value)),
             Stmt::SwitchStatement { .. } => self.compile_switch_stmt(stmt),
             Stmt::BreakStatement => self.compile_break_stmt(stmt),
             Stmt::TypeAlias { .. } => String::new(),
@@ -65,19 +340,22 @@ impl Compiler {
     fn compile_var_declaration(&mut self, stmt: &Stmt) -> String {
         if let Stmt::VarDeclaration {
             constant,
-            identifier,
+            pattern,
             value,
             ..
         } = stmt
         {
             let mut vardecl = String::new();
-            if *constant {
+            // JavaScript constants need their value right away, the checker
+            // already made sure a deferred one is assigned only once
+            if *constant && value.is_some() {
                 vardecl.push_str("const ");
             } else {
                 vardecl.push_str("let ");
             }
 
-            vardecl.push_str(identifier);
+            let compiled_pattern = self.compile_pattern(pattern);
+            vardecl.push_str(&compiled_pattern);
 
             let compiled_value = if let Some(val) = value {
                 vardecl.push_str(" = ");
@@ -94,6 +372,45 @@ impl Compiler {
         }
     }
 
+    fn compile_pattern(&mut self, pattern: &Pattern) -> String {
+        match pattern {
+            Pattern::Identifier(name) => name.clone(),
+            Pattern::Array { elements, rest } => {
+                let mut compiled_elements = Vec::new();
+                for element in elements {
+                    let mut compiled_element = self.compile_pattern(&element.pattern);
+                    if let Some(default_value) = &element.default_value {
+                        compiled_element
+                            .push_str(&format!(" = {}", self.compile_expr(default_value)));
+                    }
+                    compiled_elements.push(compiled_element);
+                }
+                if let Some(rest) = rest {
+                    compiled_elements.push(format!("...{}", self.compile_pattern(rest)));
+                }
+                format!("[{}]", compiled_elements.join(", "))
+            }
+            Pattern::Object { properties, rest } => {
+                let mut compiled_properties = Vec::new();
+                for property in properties {
+                    let mut compiled_property = match &property.pattern {
+                        Pattern::Identifier(name) if *name == property.key => name.clone(),
+                        pattern => format!("{}: {}", property.key, self.compile_pattern(pattern)),
+                    };
+                    if let Some(default_value) = &property.default_value {
+                        compiled_property
+                            .push_str(&format!(" = {}", self.compile_expr(default_value)));
+                    }
+                    compiled_properties.push(compiled_property);
+                }
+                if let Some(rest) = rest {
+                    compiled_properties.push(format!("...{}", rest));
+                }
+                format!("{{ {} }}", compiled_properties.join(", "))
+            }
+        }
+    }
+
     fn compile_return_stmt(&mut self, stmt: &Stmt) -> String {
         if let Stmt::ReturnStatement { value } = stmt {
             if let Some(expr) = value {
@@ -120,14 +437,7 @@ impl Compiler {
             compiled_if_stmt.push_str(&format!("if ({})", compiled_condition));
 
             compiled_if_stmt.push_str(" {\n");
-            self.increase_indent();
-
-            for stmt in then_branch {
-                let stmt = self.compile_stmt(stmt);
-                compiled_if_stmt.push_str(&format!("{}{}\n", self.get_indent(), stmt));
-            }
-
-            self.decrease_indent();
+            compiled_if_stmt.push_str(&self.compile_block(then_branch, ""));
             compiled_if_stmt.push_str(&format!("{}}}", self.get_indent()));
 
             if let Some(else_if_branches) = else_if_branches {
@@ -135,26 +445,16 @@ impl Compiler {
                     let compiled_else_if_cond = self.compile_expr(&else_if_branch.condition);
                     compiled_if_stmt.push_str(&format!(" else if({}) {{\n", compiled_else_if_cond));
 
-                    self.increase_indent();
-                    for stmt in &else_if_branch.body {
-                        let stmt = self.compile_stmt(&stmt);
-                        compiled_if_stmt.push_str(&format!("{}{}\n", self.get_indent(), stmt));
-                    }
-                    self.decrease_indent();
+                    compiled_if_stmt.push_str(&self.compile_block(&else_if_branch.body, ""));
 
                     compiled_if_stmt.push_str(&format!("{}}}", self.get_indent()));
                 }
             }
 
             if let Some(else_branch) = else_branch {
-                compiled_if_stmt.push_str(&format!(" else {{\n"));
+                compiled_if_stmt.push_str(" else {\n");
 
-                self.increase_indent();
-                for stmt in else_branch {
-                    let stmt = self.compile_stmt(&stmt);
-                    compiled_if_stmt.push_str(&format!("{}{}\n", self.get_indent(), stmt));
-                }
-                self.decrease_indent();
+                compiled_if_stmt.push_str(&self.compile_block(else_branch, ""));
 
                 compiled_if_stmt.push_str(&format!("{}}}", self.get_indent()));
             }
@@ -168,16 +468,11 @@ impl Compiler {
     fn compile_while_stmt(&mut self, stmt: &Stmt) -> String {
         if let Stmt::WhileStatement { condition, body } = stmt {
             let mut compiled_while_stmt: String = String::new();
-            let condition: String = self.compile_expr(&condition);
+            let condition: String = self.compile_expr(condition);
             compiled_while_stmt.push_str(&format!("while ({})", condition)); // kein get_indent() hier
             compiled_while_stmt.push_str(" {\n");
 
-            self.increase_indent();
-            for stmt in body {
-                let stmt = self.compile_stmt(&stmt);
-                compiled_while_stmt.push_str(&format!("{}{}\n", self.get_indent(), stmt));
-            }
-            self.decrease_indent();
+            compiled_while_stmt.push_str(&self.compile_block(body, ""));
 
             compiled_while_stmt.push_str(&format!("{}}}", self.get_indent()));
 
@@ -188,40 +483,246 @@ impl Compiler {
     }
 
     fn compile_fun_declaration(&mut self, stmt: &Stmt) -> String {
+        if let Stmt::FunctionDeclaration { name, is_async, .. } = stmt {
+            let mut compiled_function: String = String::new();
+            if *is_async {
+                compiled_function.push_str("async ");
+            }
+            compiled_function.push_str(&format!("function {}", name));
+            compiled_function.push_str(&self.compile_function_tail(stmt, &[]));
+            compiled_function
+        } else {
+            panic!("Function declaration expected")
+        }
+    }
+
+    // Parameter list and body. `field_initializers` are placed right after the
+    // `super(...)` call of a constructor, or at the start of the body.
+    fn compile_function_tail(&mut self, stmt: &Stmt, field_initializers: &[String]) -> String {
         if let Stmt::FunctionDeclaration {
-            name,
             parameters,
+            param_defaults,
             body,
-            is_async,
+            has_rest_parameter,
             ..
         } = stmt
         {
-            let mut compiled_function: String = String::new();
-            if *is_async {
-                compiled_function.push_str("async ");
+            self.function_depth += 1;
+            // nested functions are only known inside their function
+            self.function_params.push(HashMap::new());
+            self.collect_function_params(body);
+            let mut compiled_function = String::from("(");
+            let mut compiled_parameters = Vec::new();
+            for (parameter, default_value) in parameters.iter().zip(param_defaults.iter()) {
+                let mut compiled_parameter = self.compile_pattern(parameter);
+                if let Some(default_value) = default_value {
+                    compiled_parameter
+                        .push_str(&format!(" = {}", self.compile_expr(default_value)));
+                }
+                compiled_parameters.push(compiled_parameter);
             }
-            compiled_function.push_str(&format!("function {}(", name));
-
-            if !parameters.is_empty() {
-                compiled_function.push_str(&parameters.join(", "));
+            if *has_rest_parameter {
+                if let Some(rest_parameter) = compiled_parameters.last_mut() {
+                    rest_parameter.insert_str(0, "...");
+                }
             }
+            compiled_function.push_str(&compiled_parameters.join(", "));
 
             compiled_function.push_str(") {\n");
             self.increase_indent();
+            let outer_propagations = std::mem::replace(&mut self.propagations, 0);
 
-            for stmt in body {
+            let mut compiled_body = String::new();
+            let super_call_position = body.iter().position(Self::is_super_call);
+            if super_call_position.is_none() {
+                for initializer in field_initializers {
+                    compiled_body.push_str(&format!("{}{}\n", self.get_indent(), initializer));
+                }
+            }
+            for (idx, stmt) in body.iter().enumerate() {
                 let compiled_stmt = self.compile_stmt(stmt);
-                compiled_function.push_str(&format!("{}{}\n", self.get_indent(), compiled_stmt));
+                if !compiled_stmt.is_empty() {
+                    compiled_body.push_str(&format!("{}{}\n", self.get_indent(), compiled_stmt));
+                }
+                if super_call_position == Some(idx) {
+                    for initializer in field_initializers {
+                        compiled_body.push_str(&format!("{}{}\n", self.get_indent(), initializer));
+                    }
+                }
             }
 
+            if self.propagations > 0 {
+                let indent = self.get_indent();
+                compiled_function.push_str(&format!("{}try {{\n", indent));
+                compiled_function.push_str(&Self::indent_lines(&compiled_body));
+                compiled_function.push_str(&format!(
+                    "{0}}} catch (__error) {{\n\
+                     {0}    if (__error instanceof __Propagation) return __error.result\n\
+                     {0}    throw __error\n\
+                     {0}}}\n",
+                    indent
+                ));
+            } else {
+                compiled_function.push_str(&compiled_body);
+            }
+            self.propagations = outer_propagations;
+
             self.decrease_indent();
             compiled_function.push_str(&format!("{}}}", self.get_indent()));
+            self.function_params.pop();
+            self.function_depth -= 1;
             compiled_function
         } else {
             panic!("Function declaration expected")
         }
     }
 
+    // JS strings cannot span lines, so every line break is between tokens.
+    fn indent_lines(code: &str) -> String {
+        code.lines()
+            .map(|line| {
+                if line.is_empty() {
+                    "\n".to_string()
+                } else {
+                    format!("    {}\n", line)
+                }
+            })
+            .collect()
+    }
+
+    fn is_super_call(stmt: &Stmt) -> bool {
+        matches!(stmt, Stmt::Expression(Expr { kind: ExprKind::Call { caller, .. }, .. })
+            if matches!(&caller.kind, ExprKind::Identifier(callee) if callee == "super"))
+    }
+
+    // Fields become assignments in the constructor and static fields
+    // assignments after the class, ES2015 has no field declarations.
+    fn compile_class_declaration(&mut self, stmt: &Stmt) -> String {
+        if let Stmt::ClassDeclaration {
+            name,
+            parent,
+            members,
+        } = stmt
+        {
+            let mut compiled_class = format!("class {}", name);
+            if let Some(parent) = parent {
+                compiled_class.push_str(&format!(" extends {}", parent));
+            }
+            compiled_class.push_str(" {\n");
+            self.increase_indent();
+
+            let mut field_initializers = Vec::new();
+            let mut static_initializers = Vec::new();
+            for member in members {
+                if let ClassMember::Field {
+                    name: field_name,
+                    value: Some(value),
+                    is_static,
+                    ..
+                } = member
+                {
+                    let compiled_value = self.compile_expr(value);
+                    if *is_static {
+                        static_initializers
+                            .push(format!("{}.{} = {}", name, field_name, compiled_value));
+                    } else {
+                        field_initializers
+                            .push(format!("this.{} = {}", field_name, compiled_value));
+                    }
+                }
+            }
+
+            let has_constructor = members.iter().any(|member| {
+                matches!(member, ClassMember::Method {
+                    declaration: Stmt::FunctionDeclaration { name, .. },
+                    ..
+                } if name == "constructor")
+            });
+            if !has_constructor && !field_initializers.is_empty() {
+                let indent = self.get_indent();
+                self.increase_indent();
+                let body_indent = self.get_indent();
+                self.decrease_indent();
+                if parent.is_some() {
+                    compiled_class.push_str(&format!(
+                        "{}constructor(...args) {{\n{}super(...args)\n",
+                        indent, body_indent
+                    ));
+                } else {
+                    compiled_class.push_str(&format!("{}constructor() {{\n", indent));
+                }
+                for initializer in &field_initializers {
+                    compiled_class.push_str(&format!("{}{}\n", body_indent, initializer));
+                }
+                compiled_class.push_str(&format!("{}}}\n", indent));
+            }
+
+            for member in members {
+                if let ClassMember::Method {
+                    declaration,
+                    is_static,
+                } = member
+                {
+                    if let Stmt::FunctionDeclaration {
+                        name: method_name,
+                        is_async,
+                        ..
+                    } = declaration
+                    {
+                        compiled_class.push_str(&self.get_indent());
+                        if *is_static {
+                            compiled_class.push_str("static ");
+                        }
+                        if *is_async {
+                            compiled_class.push_str("async ");
+                        }
+                        compiled_class.push_str(method_name);
+                        let initializers = if method_name == "constructor" {
+                            field_initializers.as_slice()
+                        } else {
+                            &[]
+                        };
+                        compiled_class
+                            .push_str(&self.compile_function_tail(declaration, initializers));
+                        compiled_class.push('\n');
+                    }
+                }
+            }
+
+            self.decrease_indent();
+            compiled_class.push_str(&format!("{}}}", self.get_indent()));
+            for initializer in static_initializers {
+                compiled_class.push_str(&format!("\n{}{}", self.get_indent(), initializer));
+            }
+            compiled_class
+        } else {
+            panic!("Class declaration expected")
+        }
+    }
+
+    fn compile_impl_declaration(&mut self, stmt: &Stmt) -> String {
+        if let Stmt::ImplDeclaration {
+            target, methods, ..
+        } = stmt
+        {
+            let mut compiled_methods = Vec::new();
+            for declaration in methods {
+                if let Stmt::FunctionDeclaration { name, is_async, .. } = declaration {
+                    compiled_methods.push(format!(
+                        "{}.prototype.{} = {}function {}",
+                        target,
+                        name,
+                        if *is_async { "async " } else { "" },
+                        self.compile_function_tail(declaration, &[])
+                    ));
+                }
+            }
+            compiled_methods.join(&format!("\n{}", self.get_indent()))
+        } else {
+            panic!("Impl declaration expected")
+        }
+    }
+
     fn compile_for_loop(&mut self, stmt: &Stmt) -> String {
         if let Stmt::ForLoopStatement {
             initializer,
@@ -235,18 +736,14 @@ impl Compiler {
             if let Some(init) = initializer {
                 comp_initializer = self.compile_var_declaration(init);
             }
-            let mut comp_condition = String::new();
-            if let Some(cond) = condition {
-                comp_condition = self.compile_binary_expr(cond);
-            } else {
+            let Some(cond) = condition else {
                 panic!("No condition");
-            }
-            let mut comp_update = String::new();
-            if let Some(up) = update {
-                comp_update = self.compile_expr(up);
-            } else {
+            };
+            let comp_condition = self.compile_binary_expr(cond);
+            let Some(up) = update else {
                 panic!("No update");
-            }
+            };
+            let comp_update = self.compile_expr(up);
 
             compiled_for_loop.push_str(&format!(
                 "for ({}; {}; {})",
@@ -254,12 +751,7 @@ impl Compiler {
             ));
             compiled_for_loop.push_str(" {\n");
 
-            self.increase_indent();
-            for stmt in body {
-                let compiled_stmt = self.compile_stmt(stmt);
-                compiled_for_loop.push_str(&format!("{}{}\n", self.get_indent(), compiled_stmt));
-            }
-            self.decrease_indent();
+            compiled_for_loop.push_str(&self.compile_block(body, ""));
 
             compiled_for_loop.push_str(&format!("{}}}", self.get_indent()));
             compiled_for_loop
@@ -276,30 +768,21 @@ impl Compiler {
         } = stmt
         {
             let mut compiled_for_in = String::new();
-            let mut comp_iterator = String::new();
-            let mut comp_iterable = String::new();
-
-            if let Some(iter) = iterator {
-                comp_iterator = self.compile_stmt(iter);
-            } else {
+            let Some(iter) = iterator else {
                 panic!("iterator expected");
-            }
+            };
+            let comp_iterator = self.compile_stmt(iter);
 
-            if let Some(itera) = iterable {
-                comp_iterable = self.compile_expr(itera);
-            } else {
+            let Some(itera) = iterable else {
                 panic!("iterable expected");
-            }
+            };
+            let comp_iterable = self.compile_expr(itera);
 
-            compiled_for_in.push_str(&format!("for ({} in {})", comp_iterator, comp_iterable));
+            // `for ... in` iterates the elements of an array, which is `for ... of` in JS
+            compiled_for_in.push_str(&format!("for ({} of {})", comp_iterator, comp_iterable));
             compiled_for_in.push_str(" {\n");
 
-            self.increase_indent();
-            for stmt in body {
-                let comp_stmt = self.compile_stmt(stmt);
-                compiled_for_in.push_str(&format!("{}{}\n", self.get_indent(), comp_stmt));
-            }
-            self.decrease_indent();
+            compiled_for_in.push_str(&self.compile_block(body, ""));
 
             compiled_for_in.push_str(&format!("{}}}", self.get_indent()));
             compiled_for_in
@@ -325,12 +808,7 @@ impl Compiler {
                 end = self.compile_expr(second_number.as_ref().unwrap()),
             ));
 
-            self.increase_indent();
-            for stmt in body {
-                let compiled_stmt = self.compile_stmt(stmt);
-                compiled_for_iter.push_str(&format!("{}{}\n", self.get_indent(), compiled_stmt));
-            }
-            self.decrease_indent();
+            compiled_for_iter.push_str(&self.compile_block(body, ""));
 
             compiled_for_iter.push_str(&format!("{}}}", self.get_indent()));
 
@@ -343,7 +821,7 @@ impl Compiler {
     fn compile_try_catch_stmt(&mut self, stmt: &Stmt) -> String {
         if let Stmt::TryCatchFinally {
             try_branch,
-            catch_branch,
+            catch_clauses,
             finally_branch,
         } = stmt
         {
@@ -351,38 +829,19 @@ impl Compiler {
             compiled_try.push_str("try {");
             compiled_try.push('\n');
 
-            self.increase_indent();
-            for stmt in try_branch {
-                let comp_stmt = self.compile_stmt(stmt);
-                compiled_try.push_str(&format!("{}{}", self.get_indent(), comp_stmt));
-                compiled_try.push('\n');
-            }
-            self.decrease_indent();
+            let propagations_before = self.propagations;
+            compiled_try.push_str(&self.compile_block(try_branch, ""));
+            let rethrows_propagation = self.propagations > propagations_before;
 
             compiled_try.push_str(&format!("{}}}", self.get_indent()));
-            compiled_try.push_str(" catch {");
-            compiled_try.push('\n');
-
-            self.increase_indent();
-            for stmt in catch_branch {
-                let comp_stmt = self.compile_stmt(stmt);
-                compiled_try.push_str(&format!("{}{}", self.get_indent(), comp_stmt));
-                compiled_try.push('\n');
-            }
-            self.decrease_indent();
+            compiled_try.push_str(&self.compile_catch_clauses(catch_clauses, rethrows_propagation));
 
             if let Some(finally_branch) = finally_branch {
                 compiled_try.push_str(&format!("{}}}", self.get_indent()));
                 compiled_try.push_str(" finally {");
                 compiled_try.push('\n');
 
-                self.increase_indent();
-                for stmt in finally_branch {
-                    let comp_stmt = self.compile_stmt(stmt);
-                    compiled_try.push_str(&format!("{}{}", self.get_indent(), comp_stmt));
-                    compiled_try.push('\n');
-                }
-                self.decrease_indent();
+                compiled_try.push_str(&self.compile_block(finally_branch, ""));
             }
 
             compiled_try.push_str(&format!("{}}}", self.get_indent()));
@@ -392,6 +851,94 @@ impl Compiler {
         }
     }
 
+    // A single untyped clause maps to a plain `catch`, anything else becomes
+    // an `instanceof` chain that rethrows unmatched errors.
+    // The sentinel of a `?` in the try block is passed on to the function.
+    fn compile_catch_clauses(
+        &mut self,
+        catch_clauses: &[CatchClause],
+        rethrows_propagation: bool,
+    ) -> String {
+        let mut compiled_catch = String::new();
+
+        if let [CatchClause {
+            binding,
+            error_type: None,
+            body,
+        }] = catch_clauses
+        {
+            match (binding, rethrows_propagation) {
+                (Some(binding), _) => {
+                    compiled_catch.push_str(&format!(" catch ({}) {{\n", binding))
+                }
+                (None, true) => compiled_catch.push_str(" catch (__error) {\n"),
+                (None, false) => compiled_catch.push_str(" catch {\n"),
+            }
+            if rethrows_propagation {
+                self.increase_indent();
+                compiled_catch.push_str(&format!(
+                    "{0}if ({1} instanceof __Propagation) throw {1}\n",
+                    self.get_indent(),
+                    binding.as_deref().unwrap_or("__error")
+                ));
+                self.decrease_indent();
+            }
+            compiled_catch.push_str(&self.compile_block(body, ""));
+            return compiled_catch;
+        }
+
+        compiled_catch.push_str(" catch (__error) {\n");
+        self.increase_indent();
+        compiled_catch.push_str(&self.get_indent());
+        if rethrows_propagation {
+            compiled_catch.push_str(&format!(
+                "if (__error instanceof __Propagation) throw __error\n{}",
+                self.get_indent()
+            ));
+        }
+
+        let mut has_catch_all = false;
+        for (i, catch_clause) in catch_clauses.iter().enumerate() {
+            if i > 0 {
+                compiled_catch.push_str(" else ");
+            }
+            match &catch_clause.error_type {
+                Some(Type::AliasedType(error_type)) => {
+                    compiled_catch.push_str(&format!("if (__error instanceof {}) {{\n", error_type))
+                }
+                Some(error_type) => panic!("Error-Typ erwartet, bekam {:?}", error_type),
+                None => {
+                    has_catch_all = true;
+                    compiled_catch.push_str("{\n");
+                }
+            }
+
+            self.increase_indent();
+            if let Some(binding) = &catch_clause.binding {
+                compiled_catch.push_str(&format!(
+                    "{}let {} = __error\n",
+                    self.get_indent(),
+                    binding
+                ));
+            }
+            compiled_catch.push_str(&self.compile_block_lines(&catch_clause.body, ""));
+            self.decrease_indent();
+            compiled_catch.push_str(&format!("{}}}", self.get_indent()));
+        }
+
+        if !has_catch_all {
+            compiled_catch.push_str(" else {\n");
+            self.increase_indent();
+            compiled_catch.push_str(&format!("{}throw __error\n", self.get_indent()));
+            self.decrease_indent();
+            compiled_catch.push_str(&format!("{}}}", self.get_indent()));
+        }
+        compiled_catch.push('\n');
+        self.decrease_indent();
+
+        compiled_catch
+    }
+
     fn compile_switch_stmt(&mut self, stmt: &Stmt) -> String {
         if let Stmt::SwitchStatement {
             condition,
@@ -408,26 +955,23 @@ impl Compiler {
             for case_branch in case_branches {
                 let comp_case_condition = self.compile_expr(&case_branch.condition);
                 comp_switch.push_str(&self.get_indent().to_string());
-                comp_switch.push_str(&format!("case {}:\n", comp_case_condition));
+                // every case is a block of its own, like in the source
+                comp_switch.push_str(&format!("case {}: {{\n", comp_case_condition));
 
+                comp_switch.push_str(&self.compile_block(&case_branch.body, ";"));
                 self.increase_indent();
-                for stmt in &case_branch.body {
-                    let comp_stmt = self.compile_stmt(stmt);
-                    comp_switch.push_str(&format!("{}{};\n", self.get_indent(), comp_stmt));
-                }
                 comp_switch.push_str(&format!("{}{};\n", self.get_indent(), "break"));
                 self.decrease_indent();
+                comp_switch.push_str(&format!("{}}}\n", self.get_indent()));
             }
 
-            comp_switch.push_str(&self.get_indent().to_string());
-            comp_switch.push_str("default:\n");
+            if let Some(default_branch) = default_branch {
+                comp_switch.push_str(&self.get_indent().to_string());
+                comp_switch.push_str("default: {\n");
 
-            self.increase_indent();
-            for stmt in default_branch {
-                let comp_stmt = self.compile_stmt(stmt);
-                comp_switch.push_str(&format!("{}{};\n", self.get_indent(), comp_stmt));
+                comp_switch.push_str(&self.compile_block(default_branch, ";"));
+                comp_switch.push_str(&format!("{}}}\n", self.get_indent()));
             }
-            self.decrease_indent();
 
             self.decrease_indent();
             comp_switch.push_str(&format!("{}}}", self.get_indent()));
@@ -437,31 +981,34 @@ impl Compiler {
         }
     }
 
-    fn compile_break_stmt(&mut self, stmt: &Stmt) -> String {
+    fn compile_break_stmt(&mut self, _stmt: &Stmt) -> String {
         "break".to_string()
     }
 
-    fn compile_continue_stmt(&mut self, stmt: &Stmt) -> String {
+    fn compile_continue_stmt(&mut self, _stmt: &Stmt) -> String {
         "continue".to_string()
     }
 
     fn compile_expr(&mut self, expr: &Expr) -> String {
-        match expr {
-            Expr::Binary { .. } => self.compile_binary_expr(expr),
-            Expr::NumericLiteral(val) => val.to_string(),
-            Expr::BooleanLiteral(bool) => bool.to_string(),
-            Expr::NullLiteral => "null".to_string(),
-            Expr::StringLiteral(string_literal) => format!("\"{}\"", string_literal),
-            Expr::ArrayLiteral { .. } => self.compile_array_literal(expr),
-            Expr::Identifier(ident) => ident.clone(),
-            Expr::Assignment { .. } => self.compile_assignment_expr(expr),
-            Expr::CompoundAssignment { .. } => self.compile_compound_expr(expr),
-            Expr::ObjectLiteral(..) => self.compile_object_literal(expr),
-            Expr::Member { .. } => self.compile_member_expr(expr),
-            Expr::Call { .. } => self.compile_call_expr(expr),
-            Expr::AwaitExpression { .. } => self.compile_await_expr(expr),
-            Expr::Unary { .. } => self.compile_unary_expr(expr),
-            Expr::Increment { .. } => self.compile_increment_expr(expr),
+        match &expr.kind {
+            ExprKind::Binary { .. } => self.compile_binary_expr(expr),
+            ExprKind::NumericLiteral(val) => val.to_string(),
+            ExprKind::BooleanLiteral(bool) => bool.to_string(),
+            ExprKind::NullLiteral => "null".to_string(),
+            ExprKind::StringLiteral(string_literal) => format!("\"{}\"", string_literal),
+            ExprKind::ArrayLiteral { .. } => self.compile_array_literal(expr),
+            ExprKind::Identifier(ident) if ident == "self" => "this".to_string(),
+            ExprKind::Identifier(ident) => ident.clone(),
+            ExprKind::Assignment { .. } => self.compile_assignment_expr(expr),
+            ExprKind::CompoundAssignment { .. } => self.compile_compound_expr(expr),
+            ExprKind::ObjectLiteral(..) => self.compile_object_literal(expr),
+            ExprKind::Member { .. } => self.compile_member_expr(expr),
+            ExprKind::Call { .. } => self.compile_call_expr(expr),
+            ExprKind::AwaitExpression { .. } => self.compile_await_expr(expr),
+            ExprKind::Spread { value } => format!("...{}", self.compile_expr(value)),
+            ExprKind::Propagate { .. } => self.compile_propagate_expr(expr),
+            ExprKind::Unary { .. } => self.compile_unary_expr(expr),
+            ExprKind::Increment { .. } => self.compile_increment_expr(expr),
             _ => {
                 panic!("expression not implemented {:?}", expr);
             }
@@ -469,11 +1016,11 @@ impl Compiler {
     }
 
     fn compile_binary_expr(&mut self, expr: &Expr) -> String {
-        if let Expr::Binary {
+        if let ExprKind::Binary {
             left,
             right,
             operator,
-        } = expr
+        } = &expr.kind
         {
             let left_value = self.compile_expr(left);
             let right_value = self.compile_expr(right);
@@ -490,7 +1037,7 @@ impl Compiler {
     }
 
     fn compile_array_literal(&mut self, expr: &Expr) -> String {
-        if let Expr::ArrayLiteral(values) = expr {
+        if let ExprKind::ArrayLiteral(values) = &expr.kind {
             let mut compiled_array: String = String::new();
             compiled_array.push('[');
             for expr in values {
@@ -505,7 +1052,7 @@ impl Compiler {
     }
 
     fn compile_assignment_expr(&mut self, expr: &Expr) -> String {
-        if let Expr::Assignment { assignee, value } = expr {
+        if let ExprKind::Assignment { assignee, value } = &expr.kind {
             let mut compiled_assignment: String = String::new();
             let assigne = self.compile_expr(assignee);
             let value = self.compile_expr(value);
@@ -519,11 +1066,11 @@ impl Compiler {
     }
 
     fn compile_compound_expr(&mut self, expr: &Expr) -> String {
-        if let Expr::CompoundAssignment {
+        if let ExprKind::CompoundAssignment {
             assignee,
             value,
             operator,
-        } = expr
+        } = &expr.kind
         {
             let compiled_assignee = self.compile_expr(assignee);
             let compile_value = self.compile_expr(value);
@@ -535,11 +1082,20 @@ impl Compiler {
     }
 
     fn compile_object_literal(&mut self, expr: &Expr) -> String {
-        if let Expr::ObjectLiteral(properties) = expr {
+        if let ExprKind::ObjectLiteral(properties) = &expr.kind {
             let mut compiled_object: String = String::new();
             compiled_object.push('{');
             for (i, property) in properties.iter().enumerate() {
-                if let Some(value) = &property.value {
+                if let Some(
+                    spread @ Expr {
+                        kind: ExprKind::Spread { .. },
+                        ..
+                    },
+                ) = &property.value
+                {
+                    let spread_compiled = self.compile_expr(spread);
+                    compiled_object.push_str(&spread_compiled);
+                } else if let Some(value) = &property.value {
                     let property_compiled = self.compile_expr(value);
                     compiled_object.push_str(&format!("{}: {}", property.key, property_compiled));
                 } else {
@@ -558,11 +1114,11 @@ impl Compiler {
     }
 
     fn compile_member_expr(&mut self, expr: &Expr) -> String {
-        if let Expr::Member {
+        if let ExprKind::Member {
             object,
             property,
             computed,
-        } = expr
+        } = &expr.kind
         {
             let mut compiled_member: String = String::new();
             let object_compiled = self.compile_expr(object);
@@ -578,19 +1134,64 @@ impl Compiler {
         }
     }
 
+    // Unwrapped in place, so the rest of the expression keeps its order.
+    fn compile_propagate_expr(&mut self, expr: &Expr) -> String {
+        if let ExprKind::Propagate { value } = &expr.kind {
+            let compiled_value = self.compile_expr(value);
+            self.propagations += 1;
+            self.uses_propagation = true;
+            format!("__unwrap({})", compiled_value)
+        } else {
+            panic!("Expected Propagate Expr")
+        }
+    }
+
     fn compile_call_expr(&mut self, expr: &Expr) -> String {
-        if let Expr::Call { caller, args } = expr {
+        if let ExprKind::Call { caller, args } = &expr.kind {
+            if let ExprKind::Identifier(fn_name) = &caller.kind {
+                if (fn_name == "Ok" || fn_name == "Err")
+                    && self.param_names_of(fn_name).is_none()
+                    && args.len() == 1
+                {
+                    let compiled_value = self.compile_expr(&args[0]);
+                    return if fn_name == "Ok" {
+                        format!("{{ ok: true, value: {} }}", compiled_value)
+                    } else {
+                        format!("{{ ok: false, error: {} }}", compiled_value)
+                    };
+                }
+            }
+
             let mut compiled_call: String = String::new();
+            if let ExprKind::Identifier(fn_name) = &caller.kind {
+                let is_collection = (fn_name == "Map" || fn_name == "Set")
+                    && self.param_names_of(fn_name).is_none();
+                if self.class_names.contains(fn_name) || is_collection {
+                    compiled_call.push_str("new ");
+                }
+            }
             let caller_compiled = self.compile_expr(caller);
             compiled_call.push_str(&format!("{}(", caller_compiled));
-            for (i, arg) in args.iter().enumerate() {
-                let compiled_arg = self.compile_expr(arg);
-                compiled_call.push_str(&compiled_arg);
 
-                if i < args.len() - 1 {
-                    compiled_call.push_str(", ");
+            let compiled_args = if args
+                .iter()
+                .any(|arg| matches!(&arg.kind, ExprKind::NamedArgument { .. }))
+            {
+                match self.compile_named_args(caller, args) {
+                    Ok(compiled_args) => compiled_args,
+                    Err(error) => {
+                        self.error.get_or_insert(error);
+                        Vec::new()
+                    }
                 }
-            }
+            } else {
+                let mut compiled_args = Vec::new();
+                for arg in args {
+                    compiled_args.push(self.compile_expr(arg));
+                }
+                compiled_args
+            };
+            compiled_call.push_str(&compiled_args.join(", "));
 
             compiled_call.push(')');
 
@@ -600,8 +1201,43 @@ impl Compiler {
         }
     }
 
+    fn compile_named_args(&mut self, caller: &Expr, args: &[Expr]) -> Result<Vec<String>, String> {
+        let param_names = match &caller.kind {
+            ExprKind::Identifier(fn_name) => self.param_names_of(fn_name).cloned(),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            format!(
+                "Benannte Argumente erfordern eine bekannte Funktion, nicht {:?}",
+                caller
+            )
+        })?;
+
+        let mut slots: Vec<Option<String>> = vec![None; param_names.len()];
+        for (idx, arg) in args.iter().enumerate() {
+            if let ExprKind::NamedArgument { name, value } = &arg.kind {
+                let position = param_names
+                    .iter()
+                    .position(|param_name| param_name.as_deref() == Some(name.as_str()))
+                    .ok_or_else(|| format!("Unbekannter Parameter '{}'", name))?;
+                slots[position] = Some(self.compile_expr(value));
+            } else {
+                slots[idx] = Some(self.compile_expr(arg));
+            }
+        }
+
+        // Skipped parameters get `undefined` so their JS default kicks in
+        while let Some(None) = slots.last() {
+            slots.pop();
+        }
+        Ok(slots
+            .into_iter()
+            .map(|slot| slot.unwrap_or_else(|| "undefined".to_string()))
+            .collect())
+    }
+
     fn compile_unary_expr(&mut self, expr: &Expr) -> String {
-        if let Expr::Unary { value, operator } = expr {
+        if let ExprKind::Unary { value, operator } = &expr.kind {
             format!("{}{}", operator, self.compile_expr(value))
         } else {
             panic!("unary expression expected");
@@ -609,7 +1245,10 @@ impl Compiler {
     }
 
     fn compile_await_expr(&mut self, expr: &Expr) -> String {
-        if let Expr::AwaitExpression { value } = expr {
+        if let ExprKind::AwaitExpression { value } = &expr.kind {
+            if self.function_depth == 0 {
+                self.uses_top_level_await = true;
+            }
             format!("await {}", self.compile_expr(value))
         } else {
             panic!("Await Expr expected");
@@ -617,7 +1256,7 @@ impl Compiler {
     }
 
     fn compile_increment_expr(&mut self, expr: &Expr) -> String {
-        if let Expr::Increment { identifier, prefix } = expr {
+        if let ExprKind::Increment { identifier, prefix } = &expr.kind {
             let compiled_identifier = self.compile_expr(identifier);
             if *prefix {
                 format!("++{}", compiled_identifier)
@@ -629,3 +1268,86 @@ impl Compiler {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Compiler;
+    use crate::parser::Parser;
+    use std::process::Command;
+
+    fn compile(source: &str) -> String {
+        let program = Parser::new().produce_ast(source);
+        Compiler::new()
+            .compile_programm(&program)
+            .expect("Programm kompiliert")
+    }
+
+    #[test]
+    fn propagation_keeps_the_order_of_calls() {
+        let compiled = compile(
+            r#"let calls: array<string> = [];
+fn side() -> num {
+    calls.push("side");
+    return 1;
+}
+fn parse(s: string) -> Result<num, string> {
+    calls.push("parse");
+    if (s == "") {
+        return Err("leer");
+    }
+    return Ok(2);
+}
+fn add(a: num, b: num) -> num {
+    return a + b;
+}
+fn run(s: string) -> Result<num, string> {
+    let x = add(side(), parse(s)?);
+    calls.push("add");
+    return Ok(x);
+}
+run("a");
+run("");
+console.log(calls.join(" "));
+"#,
+        );
+        let output = Command::new("node")
+            .arg("-e")
+            .arg(&compiled)
+            .output()
+            .expect("Node.js ausführbar");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "side parse add side parse"
+        );
+    }
+
+    #[test]
+    fn erased_declarations_leave_no_lines() {
+        let compiled = compile(
+            "declare fn fetch(url: string) -> any;
+interface Named { name: string; }
+type Id = num;
+fn f() {
+    declare const DEBUG: bool;
+    return 1;
+}",
+        );
+        assert_eq!(compiled, "function f() {\n    return 1\n}\n");
+    }
+
+    #[test]
+    fn catch_bodies_are_indented_inside_their_branch() {
+        let compiled = compile(
+            "try { let a = 1; } catch (e: TypeError) { let b = 2; } catch (e) { let c = 3; }",
+        );
+        assert!(compiled.contains(
+            "    if (__error instanceof TypeError) {
+        let e = __error
+        let b = 2
+    } else {
+        let e = __error
+        let c = 3
+    }"
+        ));
+    }
+}
@@ -0,0 +1,874 @@
+use crate::ast::Type;
+use crate::typechecker::{FunctionSignature, ModuleExports};
+use std::collections::{HashMap, HashSet};
+
+// Reader for the part of TypeScript declaration files that maps onto our
+// type model: functions, interfaces, type aliases and consts. Generic type
+// parameters become Any, everything else that is not understood is skipped.
+
+pub fn read_declaration_file(source: &str) -> Result<ModuleExports, String> {
+    let tokens = tokenize(source)?;
+    let declared_types = collect_declared_types(&tokens);
+    let mut reader = DtsReader {
+        tokens,
+        position: 0,
+        declared_types,
+        generic_params: Vec::new(),
+        interfaces: Vec::new(),
+        seen_functions: HashSet::new(),
+        exports: ModuleExports::default(),
+    };
+    reader.read_file()?;
+    Ok(reader.finish())
+}
+
+fn tokenize(source: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+        } else if ch == '/' {
+            chars.next();
+            match chars.peek() {
+                Some('/') => {
+                    while chars.peek().is_some_and(|&c| c != '\n') {
+                        chars.next();
+                    }
+                }
+                Some('*') => {
+                    chars.next();
+                    let mut previous = ' ';
+                    loop {
+                        match chars.next() {
+                            Some('/') if previous == '*' => break,
+                            Some(c) => previous = c,
+                            None => return Err("Unterminierter Kommentar".to_string()),
+                        }
+                    }
+                }
+                _ => tokens.push("/".to_string()),
+            }
+        } else if ch == '"' || ch == '\'' || ch == '`' {
+            chars.next();
+            let mut literal = String::from('"');
+            loop {
+                match chars.next() {
+                    Some('\\') => {
+                        if let Some(escaped) = chars.next() {
+                            literal.push(escaped);
+                        }
+                    }
+                    Some(c) if c == ch => break,
+                    Some(c) => literal.push(c),
+                    None => return Err("Unterminierter String".to_string()),
+                }
+            }
+            tokens.push(literal);
+        } else if ch.is_alphanumeric() || ch == '_' || ch == '$' {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_alphanumeric()
+                    || c == '_'
+                    || c == '$'
+                    || (c == '.' && word.starts_with(|d: char| d.is_ascii_digit()))
+                {
+                    word.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(word);
+        } else {
+            chars.next();
+            let token = match (ch, chars.peek()) {
+                ('=', Some('>')) => {
+                    chars.next();
+                    "=>".to_string()
+                }
+                ('.', Some('.')) => {
+                    chars.next();
+                    if chars.peek() == Some(&'.') {
+                        chars.next();
+                        "...".to_string()
+                    } else {
+                        "..".to_string()
+                    }
+                }
+                _ => ch.to_string(),
+            };
+            tokens.push(token);
+        }
+    }
+
+    Ok(tokens)
+}
+
+// Interfaces and aliases may be used before they are declared.
+fn collect_declared_types(tokens: &[String]) -> HashSet<String> {
+    tokens
+        .windows(2)
+        .filter(|pair| pair[0] == "interface" || pair[0] == "type")
+        .map(|pair| pair[1].clone())
+        .filter(|name| is_identifier(name))
+        .collect()
+}
+
+fn is_identifier(token: &str) -> bool {
+    token
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+}
+
+// fields, methods and the names of the fields marked `?`
+type Members = (
+    HashMap<String, Type>,
+    HashMap<String, (FunctionSignature, Type)>,
+    HashSet<String>,
+);
+
+struct InterfaceDecl {
+    name: String,
+    extends: Vec<String>,
+    fields: HashMap<String, Type>,
+    methods: HashMap<String, (FunctionSignature, Type)>,
+    optional_fields: HashSet<String>,
+}
+
+struct DtsReader {
+    tokens: Vec<String>,
+    position: usize,
+    declared_types: HashSet<String>,
+    generic_params: Vec<String>,
+    interfaces: Vec<InterfaceDecl>,
+    seen_functions: HashSet<String>,
+    exports: ModuleExports,
+}
+
+impl DtsReader {
+    fn at(&self) -> &str {
+        self.tokens
+            .get(self.position)
+            .map(String::as_str)
+            .unwrap_or("")
+    }
+
+    fn peek(&self, offset: usize) -> &str {
+        self.tokens
+            .get(self.position + offset)
+            .map(String::as_str)
+            .unwrap_or("")
+    }
+
+    fn not_eof(&self) -> bool {
+        self.position < self.tokens.len()
+    }
+
+    fn eat(&mut self) -> String {
+        let token = self.at().to_string();
+        self.position += 1;
+        token
+    }
+
+    fn eat_if(&mut self, expected: &str) -> bool {
+        if self.at() == expected {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        if self.eat_if(expected) {
+            Ok(())
+        } else {
+            Err(format!("'{}' erwartet, gefunden '{}'", expected, self.at()))
+        }
+    }
+
+    fn expect_identifier(&mut self) -> Result<String, String> {
+        if is_identifier(self.at()) {
+            Ok(self.eat())
+        } else {
+            Err(format!("Bezeichner erwartet, gefunden '{}'", self.at()))
+        }
+    }
+
+    fn read_file(&mut self) -> Result<(), String> {
+        while self.not_eof() {
+            while matches!(self.at(), "export" | "declare") {
+                self.eat();
+            }
+            match self.at() {
+                // `declare module "name" { ... }` describes the package itself
+                "module" if self.peek(1).starts_with('"') && self.peek(2) == "{" => {
+                    self.position += 3;
+                }
+                "}" => {
+                    self.eat();
+                }
+                "function" => self.read_function()?,
+                "interface" => self.read_interface()?,
+                "type" if is_identifier(self.peek(1)) => self.read_type_alias()?,
+                "const" | "let" | "var" => self.read_variable()?,
+                _ => self.skip_statement(),
+            }
+        }
+        Ok(())
+    }
+
+    // Skips up to the end of the current statement or block.
+    fn skip_statement(&mut self) {
+        let mut depth = 0;
+        while self.not_eof() {
+            match self.eat().as_str() {
+                "{" | "(" | "[" => depth += 1,
+                "}" | ")" | "]" => {
+                    depth -= 1;
+                    if depth <= 0 {
+                        self.eat_if(";");
+                        return;
+                    }
+                }
+                ";" if depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    fn read_generic_params(&mut self) -> Result<Vec<String>, String> {
+        let mut params = Vec::new();
+        if !self.eat_if("<") {
+            return Ok(params);
+        }
+        while self.at() != ">" {
+            let param = self.expect_identifier()?;
+            self.generic_params.push(param.clone());
+            params.push(param);
+            if self.eat_if("extends") {
+                self.read_type()?;
+            }
+            if self.eat_if("=") {
+                self.read_type()?;
+            }
+            if !self.eat_if(",") {
+                break;
+            }
+        }
+        self.expect(">")?;
+        Ok(params)
+    }
+
+    fn release_generic_params(&mut self, params: &[String]) {
+        let remaining = self.generic_params.len() - params.len();
+        self.generic_params.truncate(remaining);
+    }
+
+    fn read_function(&mut self) -> Result<(), String> {
+        self.expect("function")?;
+        let name = self.expect_identifier()?;
+        let generic_params = self.read_generic_params()?;
+        let (signature, return_type) = self.read_signature()?;
+        self.release_generic_params(&generic_params);
+        self.eat_if(";");
+
+        // only the first overload is kept
+        if self.seen_functions.insert(name.clone()) {
+            self.exports.add_function(&name, signature, return_type);
+        }
+        Ok(())
+    }
+
+    // `(params): ReturnType`
+    fn read_signature(&mut self) -> Result<(FunctionSignature, Type), String> {
+        self.expect("(")?;
+        let mut param_names = Vec::new();
+        let mut param_types = Vec::new();
+        let mut optional_params = Vec::new();
+        let mut rest_type = None;
+
+        while self.at() != ")" {
+            let is_rest = self.eat_if("...");
+            let param_name = if matches!(self.at(), "{" | "[") {
+                self.skip_balanced();
+                None
+            } else {
+                Some(self.expect_identifier()?)
+            };
+            let is_optional = self.eat_if("?");
+            let param_type = if self.eat_if(":") {
+                self.read_type()?
+            } else {
+                Type::Any
+            };
+
+            if param_name.as_deref() == Some("this") {
+                // `this` parameters only describe the receiver
+            } else if is_rest {
+                rest_type = Some(match param_type {
+                    Type::Array(element_type) => *element_type,
+                    _ => Type::Any,
+                });
+            } else {
+                param_names.push(param_name);
+                param_types.push(if is_optional {
+                    Self::optional(param_type)
+                } else {
+                    param_type
+                });
+                optional_params.push(is_optional);
+            }
+
+            if !self.eat_if(",") {
+                break;
+            }
+        }
+        self.expect(")")?;
+
+        let return_type = if self.eat_if(":") {
+            self.read_type()?
+        } else {
+            Type::Any
+        };
+
+        Ok((
+            FunctionSignature::new(param_names, param_types, optional_params, rest_type),
+            return_type,
+        ))
+    }
+
+    fn skip_balanced(&mut self) {
+        let mut depth = 0;
+        while self.not_eof() {
+            match self.eat().as_str() {
+                "{" | "(" | "[" | "<" => depth += 1,
+                "}" | ")" | "]" | ">" => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                return;
+            }
+        }
+    }
+
+    fn read_interface(&mut self) -> Result<(), String> {
+        self.expect("interface")?;
+        let name = self.expect_identifier()?;
+        let generic_params = self.read_generic_params()?;
+
+        let mut extends = Vec::new();
+        if self.eat_if("extends") {
+            loop {
+                if let Type::AliasedType(parent) = self.read_type()? {
+                    extends.push(parent);
+                }
+                if !self.eat_if(",") {
+                    break;
+                }
+            }
+        }
+
+        let (fields, methods, optional_fields) = self.read_members()?;
+        self.release_generic_params(&generic_params);
+
+        self.interfaces.push(InterfaceDecl {
+            name,
+            extends,
+            fields,
+            methods,
+            optional_fields,
+        });
+        Ok(())
+    }
+
+    // `{ name: T; method(x: T): R; }`, used by interfaces and object types
+    fn read_members(&mut self) -> Result<Members, String> {
+        let mut fields = HashMap::new();
+        let mut methods = HashMap::new();
+        let mut optional_fields = HashSet::new();
+
+        self.expect("{")?;
+        while self.not_eof() && self.at() != "}" {
+            self.eat_if("readonly");
+            if matches!(self.at(), "[" | "(" | "<" | "new")
+                || (!is_identifier(self.at()) && !self.at().starts_with('"'))
+            {
+                // index, call and construct signatures have no name
+                self.skip_member();
+                continue;
+            }
+
+            let member_name = self.eat().trim_start_matches('"').to_string();
+            let is_optional = self.eat_if("?");
+            if matches!(self.at(), "(" | "<") {
+                let generic_params = self.read_generic_params()?;
+                let (signature, return_type) = self.read_signature()?;
+                self.release_generic_params(&generic_params);
+                methods
+                    .entry(member_name)
+                    .or_insert((signature, return_type));
+            } else {
+                self.expect(":")?;
+                let field_type = self.read_type()?;
+                if is_optional {
+                    optional_fields.insert(member_name.clone());
+                    fields.insert(member_name, Self::optional(field_type));
+                } else {
+                    fields.insert(member_name, field_type);
+                }
+            }
+            if !self.eat_if(";") {
+                self.eat_if(",");
+            }
+        }
+        self.expect("}")?;
+
+        Ok((fields, methods, optional_fields))
+    }
+
+    fn skip_member(&mut self) {
+        let mut depth = 0;
+        while self.not_eof() {
+            match self.at() {
+                "{" | "(" | "[" | "<" => depth += 1,
+                ")" | "]" | ">" => depth -= 1,
+                "}" if depth == 0 => return,
+                "}" => depth -= 1,
+                ";" | "," if depth == 0 => {
+                    self.eat();
+                    return;
+                }
+                _ => {}
+            }
+            self.eat();
+        }
+    }
+
+    fn read_type_alias(&mut self) -> Result<(), String> {
+        self.expect("type")?;
+        let name = self.expect_identifier()?;
+        let generic_params = self.read_generic_params()?;
+        self.expect("=")?;
+        let aliased_type = self.read_type()?;
+        self.release_generic_params(&generic_params);
+        self.eat_if(";");
+
+        self.exports.add_type_alias(&name, aliased_type);
+        Ok(())
+    }
+
+    fn read_variable(&mut self) -> Result<(), String> {
+        self.eat();
+        let name = self.expect_identifier()?;
+        let var_type = if self.eat_if(":") {
+            self.read_type()?
+        } else {
+            Type::Any
+        };
+        self.skip_statement_end();
+
+        self.exports.add_variable(&name, var_type);
+        Ok(())
+    }
+
+    fn skip_statement_end(&mut self) {
+        if self.at() != ";" {
+            self.skip_statement();
+        } else {
+            self.eat();
+        }
+    }
+
+    fn read_type(&mut self) -> Result<Type, String> {
+        self.eat_if("|");
+        let mut members = vec![self.read_intersection_type()?];
+        while self.eat_if("|") {
+            members.push(self.read_intersection_type()?);
+        }
+        Ok(Self::union(members))
+    }
+
+    // Intersections cannot be expressed, so they fall back to Any.
+    fn read_intersection_type(&mut self) -> Result<Type, String> {
+        self.eat_if("&");
+        let first = self.read_array_type()?;
+        let mut is_intersection = false;
+        while self.eat_if("&") {
+            self.read_array_type()?;
+            is_intersection = true;
+        }
+        Ok(if is_intersection { Type::Any } else { first })
+    }
+
+    fn read_array_type(&mut self) -> Result<Type, String> {
+        let mut element_type = self.read_primary_type()?;
+        while self.at() == "[" && self.peek(1) == "]" {
+            self.eat();
+            self.eat();
+            element_type = Type::Array(Box::new(element_type));
+        }
+        Ok(element_type)
+    }
+
+    fn read_primary_type(&mut self) -> Result<Type, String> {
+        let token = self.at().to_string();
+        match token.as_str() {
+            "(" => {
+                if self.is_function_type() {
+                    self.skip_balanced();
+                    self.expect("=>")?;
+                    self.read_type()?;
+                    return Ok(Type::Any);
+                }
+                self.eat();
+                let inner_type = self.read_type()?;
+                self.expect(")")?;
+                Ok(inner_type)
+            }
+            "{" => {
+                let (fields, methods, _) = self.read_members()?;
+                let mut properties = fields;
+                for method_name in methods.into_keys() {
+                    properties.insert(method_name, Type::Any);
+                }
+                Ok(Type::Object(properties))
+            }
+            "[" => {
+                self.skip_balanced();
+                Ok(Type::Array(Box::new(Type::Any)))
+            }
+            "typeof" | "keyof" | "unique" | "readonly" => {
+                self.eat();
+                let operand = self.read_array_type()?;
+                Ok(if token == "readonly" {
+                    operand
+                } else {
+                    Type::Any
+                })
+            }
+            "true" | "false" => {
+                self.eat();
+                Ok(Type::BooleanLiteral(token == "true"))
+            }
+            "-" => {
+                self.eat();
+                let number = self.eat();
+                Ok(Type::NumberLiteral(-number.parse::<f64>().unwrap_or(0.0)))
+            }
+            _ if token.starts_with('"') => {
+                self.eat();
+                Ok(Type::StringLiteral(token[1..].to_string()))
+            }
+            _ if token.starts_with(|c: char| c.is_ascii_digit()) => {
+                self.eat();
+                Ok(Type::NumberLiteral(token.parse::<f64>().unwrap_or(0.0)))
+            }
+            _ if is_identifier(&token) => {
+                self.eat();
+                let mut name = token;
+                while self.at() == "." {
+                    self.eat();
+                    name = format!("{}.{}", name, self.expect_identifier()?);
+                }
+                let mut type_arguments = Vec::new();
+                if self.eat_if("<") {
+                    while self.at() != ">" {
+                        type_arguments.push(self.read_type()?);
+                        if !self.eat_if(",") {
+                            break;
+                        }
+                    }
+                    self.expect(">")?;
+                }
+                Ok(self.named_type(&name, type_arguments))
+            }
+            _ => Err(format!("Typ erwartet, gefunden '{}'", token)),
+        }
+    }
+
+    // `(a: T) => R` and `() => R` start like a parenthesized type
+    fn is_function_type(&self) -> bool {
+        let mut depth = 0;
+        for (offset, token) in self.tokens[self.position..].iter().enumerate() {
+            match token.as_str() {
+                "(" => depth += 1,
+                ")" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return self.peek(offset + 1) == "=>";
+                    }
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+
+    fn named_type(&self, name: &str, mut type_arguments: Vec<Type>) -> Type {
+        match name {
+            "string" | "String" => Type::String,
+            "number" | "Number" => Type::Number,
+            "boolean" | "Boolean" => Type::Boolean,
+            "void" => Type::Void,
+            "null" | "undefined" => Type::Null,
+            "Array" | "ReadonlyArray" if !type_arguments.is_empty() => {
+                Type::Array(Box::new(type_arguments.remove(0)))
+            }
+            "Map" | "ReadonlyMap" if type_arguments.len() == 2 => {
+                let key_type = type_arguments.remove(0);
+                Type::Map(Box::new(key_type), Box::new(type_arguments.remove(0)))
+            }
+            "Set" | "ReadonlySet" if type_arguments.len() == 1 => {
+                Type::Set(Box::new(type_arguments.remove(0)))
+            }
+            "Promise" | "PromiseLike" if type_arguments.len() == 1 => {
+                Type::Promise(Box::new(type_arguments.remove(0)))
+            }
+            "Iterable" | "Iterator" | "IterableIterator" if !type_arguments.is_empty() => {
+                Type::Iterator(Box::new(type_arguments.remove(0)))
+            }
+            _ if self.generic_params.iter().any(|param| param == name) => Type::Any,
+            _ if self.declared_types.contains(name) => Type::AliasedType(name.to_string()),
+            _ => Type::Any,
+        }
+    }
+
+    fn optional(checked_type: Type) -> Type {
+        match checked_type {
+            Type::Option(_) | Type::Any | Type::Null => checked_type,
+            _ => Type::Option(Box::new(checked_type)),
+        }
+    }
+
+    // `T | null | undefined` becomes `T?`
+    fn union(members: Vec<Type>) -> Type {
+        let mut flattened = Vec::new();
+        for member in members {
+            match member {
+                Type::Union(inner) => flattened.extend(inner),
+                Type::Option(inner) => {
+                    flattened.push(*inner);
+                    flattened.push(Type::Null);
+                }
+                other => flattened.push(other),
+            }
+        }
+        if flattened.contains(&Type::Any) {
+            return Type::Any;
+        }
+
+        let is_nullable = flattened.contains(&Type::Null);
+        let mut members: Vec<Type> = Vec::new();
+        for member in flattened {
+            if member != Type::Null && !members.contains(&member) {
+                members.push(member);
+            }
+        }
+
+        let non_null = match members.len() {
+            0 => return Type::Null,
+            1 => members.remove(0),
+            _ => Type::Union(members),
+        };
+        if is_nullable {
+            Type::Option(Box::new(non_null))
+        } else {
+            non_null
+        }
+    }
+
+    // Interfaces get their inherited members once all of them are known.
+    fn finish(mut self) -> ModuleExports {
+        let by_name: HashMap<String, usize> = self
+            .interfaces
+            .iter()
+            .enumerate()
+            .map(|(idx, interface)| (interface.name.clone(), idx))
+            .collect();
+
+        for idx in 0..self.interfaces.len() {
+            let mut fields = self.interfaces[idx].fields.clone();
+            let mut methods = self.interfaces[idx].methods.clone();
+            let mut optional_fields = self.interfaces[idx].optional_fields.clone();
+            let mut pending = self.interfaces[idx].extends.clone();
+            let mut visited = HashSet::new();
+            while let Some(parent) = pending.pop() {
+                let Some(&parent_idx) = by_name.get(&parent) else {
+                    continue;
+                };
+                if !visited.insert(parent_idx) {
+                    continue;
+                }
+                let parent = &self.interfaces[parent_idx];
+                for (name, field_type) in &parent.fields {
+                    if !fields.contains_key(name) {
+                        fields.insert(name.clone(), field_type.clone());
+                        if parent.optional_fields.contains(name) {
+                            optional_fields.insert(name.clone());
+                        }
+                    }
+                }
+                for (name, method) in &parent.methods {
+                    methods
+                        .entry(name.clone())
+                        .or_insert_with(|| method.clone());
+                }
+                pending.extend(parent.extends.iter().cloned());
+            }
+            let name = self.interfaces[idx].name.clone();
+            self.exports
+                .add_interface(&name, fields, methods, optional_fields);
+        }
+
+        self.exports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_declaration_file;
+    use crate::ast::{Stmt, Type};
+    use crate::parser::Parser;
+    use crate::typechecker::{TypeChecker, TypeError};
+
+    // Checks `program` against a module "lib" described by `declarations`.
+    fn check(declarations: &str, program: &str) -> Result<(), TypeError> {
+        let exports = read_declaration_file(declarations).expect("Typings lesbar");
+        let mut type_checker = TypeChecker::new();
+        type_checker.register_module("lib", exports);
+        type_checker.check_program(&Parser::new().produce_ast(program))
+    }
+
+    #[test]
+    fn functions() {
+        let declarations = "export function add(a: number, b?: number): number;";
+        assert!(check(
+            declarations,
+            r#"import { add } from "lib"; let x: num = add(1); let y: num = add(1, 2);"#
+        )
+        .is_ok());
+        assert!(check(declarations, r#"import { add } from "lib"; add("1");"#).is_err());
+        assert!(check(
+            declarations,
+            r#"import { add } from "lib"; let s: string = add(1);"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn overloads_keep_the_first_signature() {
+        let declarations = "
+            export function parse(text: string): number;
+            export function parse(text: string, radix: number): string;";
+        assert!(check(
+            declarations,
+            r#"import { parse } from "lib"; let n: num = parse("1");"#
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn consts() {
+        let declarations = "
+            export const VERSION: string;
+            export declare let count: number;";
+        assert!(check(
+            declarations,
+            r#"import { VERSION, count } from "lib"; let v: string = VERSION; let c: num = count;"#
+        )
+        .is_ok());
+        assert!(check(
+            declarations,
+            r#"import { VERSION } from "lib"; let n: num = VERSION;"#
+        )
+        .is_err());
+        // imported bindings are read-only
+        assert!(check(declarations, r#"import { count } from "lib"; count = 2;"#).is_err());
+    }
+
+    #[test]
+    fn interfaces() {
+        let declarations = "
+            export interface Point { x: number; label?: string; norm(): number; }
+            export function origin(): Point;";
+        assert!(check(
+            declarations,
+            r#"import { Point, origin } from "lib"; let p: Point = origin(); let x: num = p.x + p.norm();"#
+        )
+        .is_ok());
+        assert!(check(
+            declarations,
+            r#"import { origin } from "lib"; let p = origin(); let s: string = p.x;"#
+        )
+        .is_err());
+        assert!(check(
+            declarations,
+            r#"import { Point } from "lib"; let p: Point = { y: 1 };"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn optional_members_may_be_left_out() {
+        let declarations = "
+            export interface Opts { loud?: boolean; times: number; }
+            export interface Verbose extends Opts { level?: number; }
+            export function run(opts: Opts): void;";
+        assert!(check(
+            declarations,
+            r#"import { Opts, run } from "lib"; let o: Opts = { times: 2 }; run(o);"#
+        )
+        .is_ok());
+        assert!(check(
+            declarations,
+            r#"import { Verbose } from "lib"; let v: Verbose = { times: 2, loud: true };"#
+        )
+        .is_ok());
+        assert!(check(
+            declarations,
+            r#"import { Opts } from "lib"; let o: Opts = { loud: true };"#
+        )
+        .is_err());
+        assert!(check(
+            declarations,
+            r#"import { Opts } from "lib"; let o: Opts = { times: 2, loud: 1 };"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn generics_fall_back_to_any() {
+        let declarations = "
+            export function first<T>(items: T[]): T;
+            export function wrap<T>(value: T): Box<T>;";
+        // only the type parameter is unknown, not the array around it
+        assert!(check(declarations, r#"import { first } from "lib"; first(1);"#).is_err());
+
+        let program =
+            r#"import { first, wrap } from "lib"; let s = first([1, 2]); let b = wrap("x");"#;
+
+        let exports = read_declaration_file(declarations).expect("Typings lesbar");
+        let mut type_checker = TypeChecker::new();
+        type_checker.register_module("lib", exports);
+        let program = Parser::new().produce_ast(program);
+        type_checker
+            .check_program(&program)
+            .expect("Programm korrekt");
+        let Stmt::Program { body } = &program else {
+            panic!("Programm erwartet")
+        };
+        for declaration in &body[1..] {
+            let Stmt::VarDeclaration {
+                value: Some(value), ..
+            } = declaration
+            else {
+                panic!("Variablendeklaration erwartet")
+            };
+            assert_eq!(type_checker.type_info().type_of(value), Some(&Type::Any));
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::ast::SourceMap;
+use crate::typechecker::Suggestion;
+
+/// Position of a name in the source, `column` is the byte offset within the
+/// line, both start at 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub line: u32,
+    pub column: usize,
+    pub length: usize,
+}
+
+/// Replacing the text at `span` with `replacement` fixes a diagnostic.
+#[derive(Debug, Clone)]
+pub struct Edit {
+    pub span: Span,
+    pub replacement: String,
+}
+
+/// The edit replacing the misspelled name at the place the checker found
+/// it. There is none if the parser recorded no position for that name or
+/// the source does not have the name there.
+pub fn locate(source: &str, source_map: &SourceMap, suggestion: &Suggestion) -> Option<Edit> {
+    let position = suggestion.position(source_map)?;
+    let line_text = source
+        .lines()
+        .nth((position.line as usize).checked_sub(1)?)?;
+    let start = position.column.checked_sub(1)?;
+    let end = start + suggestion.name.len();
+    let is_name = line_text.get(start..end) == Some(suggestion.name.as_str())
+        && !line_text[end..].starts_with(|ch: char| ch.is_alphanumeric() || ch == '_');
+    if !is_name {
+        return None;
+    }
+    Some(Edit {
+        span: Span {
+            line: position.line,
+            column: position.column,
+            length: suggestion.name.len(),
+        },
+        replacement: suggestion.replacement.clone(),
+    })
+}
+
+pub fn apply(source: &str, edit: &Edit) -> String {
+    let mut fixed = String::with_capacity(source.len());
+    for (idx, line) in source.split_inclusive('\n').enumerate() {
+        if idx + 1 == edit.span.line as usize {
+            let start = edit.span.column - 1;
+            fixed.push_str(&line[..start]);
+            fixed.push_str(&edit.replacement);
+            fixed.push_str(&line[start + edit.span.length..]);
+        } else {
+            fixed.push_str(line);
+        }
+    }
+    fixed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply, locate};
+    use crate::parser::Parser;
+    use crate::typechecker::TypeChecker;
+
+    #[test]
+    fn fixes_the_name_the_checker_reported() {
+        let source = "let cfg = { colr: \"red\" };\nlet color = 1;\nconsole.log(colr, cfg.colr);\n";
+        let mut parser = Parser::new();
+        let program = parser.produce_ast(source);
+        let source_map = parser.into_source_map();
+        let mut type_checker = TypeChecker::new();
+        assert!(type_checker.check_program(&program).is_err());
+
+        let suggestion = type_checker.suggestion().expect("Vorschlag für colr");
+        let edit = locate(source, &source_map, suggestion).expect("Position von colr");
+        assert_eq!(
+            apply(source, &edit),
+            "let cfg = { colr: \"red\" };\nlet color = 1;\nconsole.log(color, cfg.colr);\n"
+        );
+        // a source that changed since it was checked is not fixed
+        assert!(locate("let colr = 1;", &source_map, suggestion).is_none());
+    }
+}
@@ -1,132 +1,709 @@
 use crate::ast::Type;
 use std::collections::HashMap;
 
+/// Signature of a built-in method. Inside the types [`ELEMENT`] stands for
+/// the element type of the receiving array or set, [`KEY`] and [`VALUE`] for
+/// the key and value type of a map, [`CALLBACK`] for a callback and
+/// [`CALLBACK_RESULT`] for the return type of the callback passed in.
+#[derive(Debug, Clone)]
+pub struct MethodSignature {
+    pub param_types: Vec<Type>,
+    /// parameters after these may be left out
+    pub required_params: usize,
+    pub rest_type: Option<Type>,
+    pub return_type: Type,
+    /// what the callback in the first parameter is called with
+    pub callback_params: Vec<Type>,
+}
+
+// The placeholders are no identifiers, so they never meet a user's type.
+pub const ELEMENT: &str = "<T>";
+pub const KEY: &str = "<K>";
+pub const VALUE: &str = "<V>";
+pub const CALLBACK: &str = "<Function>";
+pub const CALLBACK_RESULT: &str = "<U>";
+
+impl MethodSignature {
+    fn new(param_types: Vec<Type>, required_params: usize, return_type: Type) -> Self {
+        Self {
+            param_types,
+            required_params,
+            rest_type: None,
+            return_type,
+            callback_params: Vec::new(),
+        }
+    }
+
+    fn variadic(
+        param_types: Vec<Type>,
+        required_params: usize,
+        rest_type: Type,
+        return_type: Type,
+    ) -> Self {
+        Self {
+            param_types,
+            required_params,
+            rest_type: Some(rest_type),
+            return_type,
+            callback_params: Vec::new(),
+        }
+    }
+
+    fn calling(mut self, callback_params: Vec<Type>) -> Self {
+        self.callback_params = callback_params;
+        self
+    }
+
+    pub fn instantiate(&self, bindings: &[(&str, &Type)]) -> Self {
+        let bind = |target: &Type| {
+            bindings
+                .iter()
+                .fold(target.clone(), |bound, (placeholder, replacement)| {
+                    substitute(&bound, placeholder, replacement)
+                })
+        };
+        Self {
+            param_types: self.param_types.iter().map(bind).collect(),
+            required_params: self.required_params,
+            rest_type: self.rest_type.as_ref().map(bind),
+            return_type: bind(&self.return_type),
+            callback_params: self.callback_params.iter().map(bind).collect(),
+        }
+    }
+}
+
+pub fn element() -> Type {
+    Type::AliasedType(ELEMENT.to_string())
+}
+
+fn key() -> Type {
+    Type::AliasedType(KEY.to_string())
+}
+
+fn value() -> Type {
+    Type::AliasedType(VALUE.to_string())
+}
+
+/// Without tuples an entry of a map is an array of its key and value.
+pub fn map_entry_type(key_type: &Type, value_type: &Type) -> Type {
+    if key_type == value_type {
+        Type::Array(Box::new(key_type.clone()))
+    } else {
+        Type::Array(Box::new(Type::Union(vec![
+            key_type.clone(),
+            value_type.clone(),
+        ])))
+    }
+}
+
+pub fn callback() -> Type {
+    Type::AliasedType(CALLBACK.to_string())
+}
+
+pub fn callback_result() -> Type {
+    Type::AliasedType(CALLBACK_RESULT.to_string())
+}
+
+/// Replaces a placeholder; an optional `any` stays `any`, so untyped arrays
+/// keep behaving as before.
+pub fn substitute(target: &Type, placeholder: &str, replacement: &Type) -> Type {
+    match target {
+        Type::AliasedType(name) if name == placeholder => replacement.clone(),
+        Type::Array(inner) => Type::Array(Box::new(substitute(inner, placeholder, replacement))),
+        Type::Option(inner) => match substitute(inner, placeholder, replacement) {
+            Type::Any => Type::Any,
+            inner => Type::Option(Box::new(inner)),
+        },
+        Type::Map(key_type, value_type) => Type::Map(
+            Box::new(substitute(key_type, placeholder, replacement)),
+            Box::new(substitute(value_type, placeholder, replacement)),
+        ),
+        Type::Set(inner) => Type::Set(Box::new(substitute(inner, placeholder, replacement))),
+        Type::Iterator(inner) => {
+            Type::Iterator(Box::new(substitute(inner, placeholder, replacement)))
+        }
+        // promises never nest in JS
+        Type::Promise(inner) => match substitute(inner, placeholder, replacement) {
+            Type::Promise(resolved) => Type::Promise(resolved),
+            inner => Type::Promise(Box::new(inner)),
+        },
+        Type::Union(types) => Type::Union(
+            types
+                .iter()
+                .map(|member| substitute(member, placeholder, replacement))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Finds what a placeholder stands for by matching a parameter type against
+/// the type of the argument, e.g. `T` in `array<Promise<T>>`.
+pub fn bind_placeholder(target: &Type, actual: &Type, placeholder: &str) -> Option<Type> {
+    match (target, actual) {
+        (Type::AliasedType(name), _) if name == placeholder => Some(actual.clone()),
+        (Type::Array(target_inner), Type::Array(actual_inner))
+        | (Type::Option(target_inner), Type::Option(actual_inner))
+        | (Type::Set(target_inner), Type::Set(actual_inner))
+        | (Type::Iterator(target_inner), Type::Iterator(actual_inner))
+        | (Type::Promise(target_inner), Type::Promise(actual_inner)) => {
+            bind_placeholder(target_inner, actual_inner, placeholder)
+        }
+        // a plain value where a promise is expected counts as resolved
+        (Type::Promise(target_inner), _) => bind_placeholder(target_inner, actual, placeholder),
+        _ => None,
+    }
+}
+
+pub fn mentions_placeholder(target: &Type, placeholder: &str) -> bool {
+    substitute(target, placeholder, &Type::Null) != *target
+}
+
 pub struct JsStdLib {
-    pub objects: HashMap<String, HashMap<String, Type>>,
+    pub objects: HashMap<String, HashMap<String, MethodSignature>>,
+    pub properties: HashMap<String, HashMap<String, Type>>,
+    pub error_types: Vec<String>,
 }
 
 impl JsStdLib {
     pub fn new() -> Self {
         let mut objects = HashMap::new();
+        let mut properties = HashMap::new();
+        let number = || Type::Number;
+        let string = || Type::String;
+        let array_of = |element_type: Type| Type::Array(Box::new(element_type));
 
         // Array methods
         let mut array_methods = HashMap::new();
-        array_methods.insert("push".to_string(), Type::Number);
-        array_methods.insert("pop".to_string(), Type::Any);
-        array_methods.insert("shift".to_string(), Type::Any);
-        array_methods.insert("unshift".to_string(), Type::Number);
-        array_methods.insert("slice".to_string(), Type::Array(Box::new(Type::Any)));
-        array_methods.insert("splice".to_string(), Type::Array(Box::new(Type::Any)));
-        array_methods.insert("join".to_string(), Type::String);
-        array_methods.insert("length".to_string(), Type::Number);
-        array_methods.insert("forEach".to_string(), Type::Void);
-        array_methods.insert("map".to_string(), Type::Array(Box::new(Type::Any)));
-        array_methods.insert("filter".to_string(), Type::Array(Box::new(Type::Any)));
-        array_methods.insert("reduce".to_string(), Type::Any);
-        array_methods.insert("some".to_string(), Type::Boolean);
-        array_methods.insert("every".to_string(), Type::Boolean);
-        array_methods.insert("indexOf".to_string(), Type::Number);
-        array_methods.insert("includes".to_string(), Type::Boolean);
+        // element, index and the array itself
+        let visit_element = vec![element(), number(), array_of(element())];
+        array_methods.insert(
+            "push".to_string(),
+            MethodSignature::variadic(vec![], 0, element(), number()),
+        );
+        array_methods.insert(
+            "pop".to_string(),
+            MethodSignature::new(vec![], 0, Type::Option(Box::new(element()))),
+        );
+        array_methods.insert(
+            "shift".to_string(),
+            MethodSignature::new(vec![], 0, Type::Option(Box::new(element()))),
+        );
+        array_methods.insert(
+            "unshift".to_string(),
+            MethodSignature::variadic(vec![], 0, element(), number()),
+        );
+        array_methods.insert(
+            "slice".to_string(),
+            MethodSignature::new(vec![number(), number()], 0, array_of(element())),
+        );
+        array_methods.insert(
+            "splice".to_string(),
+            MethodSignature::variadic(vec![number(), number()], 1, element(), array_of(element())),
+        );
+        array_methods.insert(
+            "concat".to_string(),
+            MethodSignature::variadic(vec![], 0, array_of(element()), array_of(element())),
+        );
+        array_methods.insert(
+            "join".to_string(),
+            MethodSignature::new(vec![string()], 0, string()),
+        );
+        array_methods.insert(
+            "reverse".to_string(),
+            MethodSignature::new(vec![], 0, array_of(element())),
+        );
+        array_methods.insert(
+            "sort".to_string(),
+            MethodSignature::new(vec![callback()], 0, array_of(element()))
+                .calling(vec![element(), element()]),
+        );
+        array_methods.insert(
+            "forEach".to_string(),
+            MethodSignature::new(vec![callback()], 1, Type::Void).calling(visit_element.clone()),
+        );
+        array_methods.insert(
+            "map".to_string(),
+            MethodSignature::new(vec![callback()], 1, array_of(callback_result()))
+                .calling(visit_element.clone()),
+        );
+        array_methods.insert(
+            "filter".to_string(),
+            MethodSignature::new(vec![callback()], 1, array_of(element()))
+                .calling(visit_element.clone()),
+        );
+        array_methods.insert(
+            "find".to_string(),
+            MethodSignature::new(vec![callback()], 1, Type::Option(Box::new(element())))
+                .calling(visit_element.clone()),
+        );
+        array_methods.insert(
+            "findIndex".to_string(),
+            MethodSignature::new(vec![callback()], 1, number()).calling(visit_element.clone()),
+        );
+        array_methods.insert(
+            "reduce".to_string(),
+            MethodSignature::new(vec![callback(), Type::Any], 1, callback_result()).calling(vec![
+                Type::Any,
+                element(),
+                number(),
+                array_of(element()),
+            ]),
+        );
+        array_methods.insert(
+            "some".to_string(),
+            MethodSignature::new(vec![callback()], 1, Type::Boolean).calling(visit_element.clone()),
+        );
+        array_methods.insert(
+            "every".to_string(),
+            MethodSignature::new(vec![callback()], 1, Type::Boolean).calling(visit_element.clone()),
+        );
+        array_methods.insert(
+            "indexOf".to_string(),
+            MethodSignature::new(vec![element(), number()], 1, number()),
+        );
+        array_methods.insert(
+            "includes".to_string(),
+            MethodSignature::new(vec![element(), number()], 1, Type::Boolean),
+        );
         objects.insert("Array".to_string(), array_methods);
+        properties.insert(
+            "Array".to_string(),
+            HashMap::from([("length".to_string(), number())]),
+        );
 
         // String methods
         let mut string_methods = HashMap::new();
-        string_methods.insert("charAt".to_string(), Type::String);
-        string_methods.insert("concat".to_string(), Type::String);
-        string_methods.insert("includes".to_string(), Type::Boolean);
-        string_methods.insert("indexOf".to_string(), Type::Number);
-        string_methods.insert("replace".to_string(), Type::String);
-        string_methods.insert("slice".to_string(), Type::String);
-        string_methods.insert("split".to_string(), Type::Array(Box::new(Type::String)));
-        string_methods.insert("substring".to_string(), Type::String);
-        string_methods.insert("toLowerCase".to_string(), Type::String);
-        string_methods.insert("toUpperCase".to_string(), Type::String);
-        string_methods.insert("trim".to_string(), Type::String);
-        string_methods.insert("length".to_string(), Type::Number);
-        string_methods.insert("startsWith".to_string(), Type::Boolean);
-        string_methods.insert("endsWith".to_string(), Type::Boolean);
-        string_methods.insert("match".to_string(), Type::Array(Box::new(Type::String)));
-        string_methods.insert("padStart".to_string(), Type::String);
-        string_methods.insert("padEnd".to_string(), Type::String);
+        string_methods.insert(
+            "charAt".to_string(),
+            MethodSignature::new(vec![number()], 1, string()),
+        );
+        string_methods.insert(
+            "concat".to_string(),
+            MethodSignature::variadic(vec![], 0, string(), string()),
+        );
+        string_methods.insert(
+            "includes".to_string(),
+            MethodSignature::new(vec![string(), number()], 1, Type::Boolean),
+        );
+        string_methods.insert(
+            "indexOf".to_string(),
+            MethodSignature::new(vec![string(), number()], 1, number()),
+        );
+        string_methods.insert(
+            "replace".to_string(),
+            MethodSignature::new(vec![string(), string()], 2, string()),
+        );
+        string_methods.insert(
+            "slice".to_string(),
+            MethodSignature::new(vec![number(), number()], 0, string()),
+        );
+        string_methods.insert(
+            "split".to_string(),
+            MethodSignature::new(vec![string(), number()], 0, array_of(string())),
+        );
+        string_methods.insert(
+            "substring".to_string(),
+            MethodSignature::new(vec![number(), number()], 1, string()),
+        );
+        string_methods.insert(
+            "toLowerCase".to_string(),
+            MethodSignature::new(vec![], 0, string()),
+        );
+        string_methods.insert(
+            "toUpperCase".to_string(),
+            MethodSignature::new(vec![], 0, string()),
+        );
+        string_methods.insert(
+            "trim".to_string(),
+            MethodSignature::new(vec![], 0, string()),
+        );
+        string_methods.insert(
+            "startsWith".to_string(),
+            MethodSignature::new(vec![string(), number()], 1, Type::Boolean),
+        );
+        string_methods.insert(
+            "endsWith".to_string(),
+            MethodSignature::new(vec![string(), number()], 1, Type::Boolean),
+        );
+        // `match` gives null when nothing matched
+        string_methods.insert(
+            "match".to_string(),
+            MethodSignature::new(
+                vec![string()],
+                1,
+                Type::Option(Box::new(array_of(string()))),
+            ),
+        );
+        string_methods.insert(
+            "padStart".to_string(),
+            MethodSignature::new(vec![number(), string()], 1, string()),
+        );
+        string_methods.insert(
+            "padEnd".to_string(),
+            MethodSignature::new(vec![number(), string()], 1, string()),
+        );
         objects.insert("String".to_string(), string_methods);
+        properties.insert(
+            "String".to_string(),
+            HashMap::from([("length".to_string(), number())]),
+        );
+
+        // Map methods
+        let map_type = || Type::Map(Box::new(key()), Box::new(value()));
+        let mut map_methods = HashMap::new();
+        map_methods.insert(
+            "get".to_string(),
+            MethodSignature::new(vec![key()], 1, Type::Option(Box::new(value()))),
+        );
+        map_methods.insert(
+            "set".to_string(),
+            MethodSignature::new(vec![key(), value()], 2, map_type()),
+        );
+        map_methods.insert(
+            "has".to_string(),
+            MethodSignature::new(vec![key()], 1, Type::Boolean),
+        );
+        map_methods.insert(
+            "delete".to_string(),
+            MethodSignature::new(vec![key()], 1, Type::Boolean),
+        );
+        map_methods.insert(
+            "clear".to_string(),
+            MethodSignature::new(vec![], 0, Type::Void),
+        );
+        map_methods.insert(
+            "keys".to_string(),
+            MethodSignature::new(vec![], 0, Type::Iterator(Box::new(key()))),
+        );
+        map_methods.insert(
+            "values".to_string(),
+            MethodSignature::new(vec![], 0, Type::Iterator(Box::new(value()))),
+        );
+        map_methods.insert(
+            "entries".to_string(),
+            MethodSignature::new(
+                vec![],
+                0,
+                Type::Iterator(Box::new(map_entry_type(&key(), &value()))),
+            ),
+        );
+        map_methods.insert(
+            "forEach".to_string(),
+            MethodSignature::new(vec![callback()], 1, Type::Void).calling(vec![
+                value(),
+                key(),
+                Type::Map(Box::new(key()), Box::new(value())),
+            ]),
+        );
+        objects.insert("Map".to_string(), map_methods);
+        properties.insert(
+            "Map".to_string(),
+            HashMap::from([("size".to_string(), number())]),
+        );
+
+        // Set methods
+        let mut set_methods = HashMap::new();
+        set_methods.insert(
+            "add".to_string(),
+            MethodSignature::new(vec![element()], 1, Type::Set(Box::new(element()))),
+        );
+        set_methods.insert(
+            "has".to_string(),
+            MethodSignature::new(vec![element()], 1, Type::Boolean),
+        );
+        set_methods.insert(
+            "delete".to_string(),
+            MethodSignature::new(vec![element()], 1, Type::Boolean),
+        );
+        set_methods.insert(
+            "clear".to_string(),
+            MethodSignature::new(vec![], 0, Type::Void),
+        );
+        for name in ["keys", "values"] {
+            set_methods.insert(
+                name.to_string(),
+                MethodSignature::new(vec![], 0, Type::Iterator(Box::new(element()))),
+            );
+        }
+        set_methods.insert(
+            "entries".to_string(),
+            MethodSignature::new(
+                vec![],
+                0,
+                Type::Iterator(Box::new(Type::Array(Box::new(element())))),
+            ),
+        );
+        set_methods.insert(
+            "forEach".to_string(),
+            MethodSignature::new(vec![callback()], 1, Type::Void).calling(vec![
+                element(),
+                element(),
+                Type::Set(Box::new(element())),
+            ]),
+        );
+        objects.insert("Set".to_string(), set_methods);
+        properties.insert(
+            "Set".to_string(),
+            HashMap::from([("size".to_string(), number())]),
+        );
+
+        // Promise methods, statics and those of promise values share a table
+        // like the other objects
+        let promise_of = |inner: Type| Type::Promise(Box::new(inner));
+        let mut promise_methods = HashMap::new();
+        promise_methods.insert(
+            "all".to_string(),
+            MethodSignature::new(
+                vec![array_of(promise_of(element()))],
+                1,
+                promise_of(array_of(element())),
+            ),
+        );
+        promise_methods.insert(
+            "race".to_string(),
+            MethodSignature::new(
+                vec![array_of(promise_of(element()))],
+                1,
+                promise_of(element()),
+            ),
+        );
+        promise_methods.insert(
+            "resolve".to_string(),
+            MethodSignature::new(vec![element()], 1, promise_of(element())),
+        );
+        promise_methods.insert(
+            "reject".to_string(),
+            MethodSignature::new(vec![Type::Any], 1, promise_of(Type::Any)),
+        );
+        promise_methods.insert(
+            "then".to_string(),
+            MethodSignature::new(
+                vec![callback(), callback()],
+                1,
+                promise_of(callback_result()),
+            )
+            .calling(vec![element()]),
+        );
+        promise_methods.insert(
+            "catch".to_string(),
+            MethodSignature::new(vec![callback()], 1, promise_of(element()))
+                .calling(vec![Type::Any]),
+        );
+        promise_methods.insert(
+            "finally".to_string(),
+            MethodSignature::new(vec![callback()], 1, promise_of(element())),
+        );
+        objects.insert("Promise".to_string(), promise_methods);
 
         // Math object
         let mut math_methods = HashMap::new();
-        math_methods.insert("abs".to_string(), Type::Number);
-        math_methods.insert("ceil".to_string(), Type::Number);
-        math_methods.insert("floor".to_string(), Type::Number);
-        math_methods.insert("max".to_string(), Type::Number);
-        math_methods.insert("min".to_string(), Type::Number);
-        math_methods.insert("pow".to_string(), Type::Number);
-        math_methods.insert("random".to_string(), Type::Number);
-        math_methods.insert("round".to_string(), Type::Number);
-        math_methods.insert("sqrt".to_string(), Type::Number);
-        math_methods.insert("PI".to_string(), Type::Number);
-        math_methods.insert("E".to_string(), Type::Number);
+        for name in ["abs", "ceil", "floor", "round", "sqrt"] {
+            math_methods.insert(
+                name.to_string(),
+                MethodSignature::new(vec![number()], 1, number()),
+            );
+        }
+        for name in ["max", "min"] {
+            math_methods.insert(
+                name.to_string(),
+                MethodSignature::variadic(vec![], 0, number(), number()),
+            );
+        }
+        math_methods.insert(
+            "pow".to_string(),
+            MethodSignature::new(vec![number(), number()], 2, number()),
+        );
+        math_methods.insert(
+            "random".to_string(),
+            MethodSignature::new(vec![], 0, number()),
+        );
         objects.insert("Math".to_string(), math_methods);
+        properties.insert(
+            "Math".to_string(),
+            HashMap::from([("PI".to_string(), number()), ("E".to_string(), number())]),
+        );
 
         // Number methods
         let mut number_methods = HashMap::new();
-        number_methods.insert("toFixed".to_string(), Type::String);
-        number_methods.insert("toPrecision".to_string(), Type::String);
-        number_methods.insert("toString".to_string(), Type::String);
-        number_methods.insert("valueOf".to_string(), Type::Number);
-        number_methods.insert("MAX_VALUE".to_string(), Type::Number);
-        number_methods.insert("MIN_VALUE".to_string(), Type::Number);
+        for name in ["toFixed", "toPrecision", "toString"] {
+            number_methods.insert(
+                name.to_string(),
+                MethodSignature::new(vec![number()], 0, string()),
+            );
+        }
+        number_methods.insert(
+            "valueOf".to_string(),
+            MethodSignature::new(vec![], 0, number()),
+        );
         objects.insert("Number".to_string(), number_methods);
+        properties.insert(
+            "Number".to_string(),
+            HashMap::from([
+                ("MAX_VALUE".to_string(), number()),
+                ("MIN_VALUE".to_string(), number()),
+            ]),
+        );
 
         // Object methods
         let mut object_methods = HashMap::new();
-        object_methods.insert("keys".to_string(), Type::Array(Box::new(Type::String)));
-        object_methods.insert("values".to_string(), Type::Array(Box::new(Type::Any)));
+        object_methods.insert(
+            "keys".to_string(),
+            MethodSignature::new(vec![Type::Any], 1, array_of(string())),
+        );
+        object_methods.insert(
+            "values".to_string(),
+            MethodSignature::new(vec![Type::Any], 1, array_of(Type::Any)),
+        );
         object_methods.insert(
             "entries".to_string(),
-            Type::Array(Box::new(Type::Array(Box::new(Type::Any)))),
+            MethodSignature::new(vec![Type::Any], 1, array_of(array_of(Type::Any))),
+        );
+        object_methods.insert(
+            "hasOwnProperty".to_string(),
+            MethodSignature::new(vec![string()], 1, Type::Boolean),
+        );
+        object_methods.insert(
+            "toString".to_string(),
+            MethodSignature::new(vec![], 0, string()),
         );
-        object_methods.insert("hasOwnProperty".to_string(), Type::Boolean);
-        object_methods.insert("toString".to_string(), Type::String);
         objects.insert("Object".to_string(), object_methods);
 
         // Console methods
         let mut console_methods = HashMap::new();
-        console_methods.insert("log".to_string(), Type::Void);
-        console_methods.insert("error".to_string(), Type::Void);
-        console_methods.insert("warn".to_string(), Type::Void);
-        console_methods.insert("info".to_string(), Type::Void);
-        console_methods.insert("debug".to_string(), Type::Void);
-        console_methods.insert("table".to_string(), Type::Void);
+        for name in ["log", "error", "warn", "info", "debug"] {
+            console_methods.insert(
+                name.to_string(),
+                MethodSignature::variadic(vec![], 0, Type::Any, Type::Void),
+            );
+        }
+        console_methods.insert(
+            "table".to_string(),
+            MethodSignature::new(vec![Type::Any], 1, Type::Void),
+        );
         objects.insert("console".to_string(), console_methods);
 
         // JSON methods
         let mut json_methods = HashMap::new();
-        json_methods.insert("parse".to_string(), Type::Any);
-        json_methods.insert("stringify".to_string(), Type::String);
+        json_methods.insert(
+            "parse".to_string(),
+            MethodSignature::new(vec![string()], 1, Type::Any),
+        );
+        json_methods.insert(
+            "stringify".to_string(),
+            MethodSignature::new(vec![Type::Any, Type::Any, Type::Any], 1, string()),
+        );
         objects.insert("JSON".to_string(), json_methods);
 
-        Self { objects }
+        // Error constructors, all sharing the shape of `Error`
+        let error_types = vec![
+            "Error".to_string(),
+            "TypeError".to_string(),
+            "RangeError".to_string(),
+            "SyntaxError".to_string(),
+            "ReferenceError".to_string(),
+        ];
+
+        Self {
+            objects,
+            properties,
+            error_types,
+        }
     }
 
-    pub fn get_method_type(&self, object_name: &str, method_name: &str) -> Option<Type> {
+    /// Type of a global like `Math`, methods used as values are not tracked.
+    pub fn object_type(&self, object_name: &str) -> Type {
+        let mut fields = self
+            .properties
+            .get(object_name)
+            .cloned()
+            .unwrap_or_default();
         if let Some(methods) = self.objects.get(object_name) {
-            if let Some(method_type) = methods.get(method_name) {
-                return Some(method_type.clone());
+            for method_name in methods.keys() {
+                fields.insert(method_name.clone(), Type::Any);
             }
         }
-        None
+        Type::Object(fields)
+    }
+
+    pub fn error_fields() -> HashMap<String, Type> {
+        let mut error_fields = HashMap::new();
+        error_fields.insert("name".to_string(), Type::String);
+        error_fields.insert("message".to_string(), Type::String);
+        error_fields.insert("stack".to_string(), Type::String);
+        error_fields
+    }
+
+    pub fn get_method(&self, object_name: &str, method_name: &str) -> Option<MethodSignature> {
+        self.objects.get(object_name)?.get(method_name).cloned()
     }
 
-    pub fn get_primitive_method_type(
+    fn primitive_name(primitive_type: &Type) -> Option<&'static str> {
+        match primitive_type {
+            Type::String => Some("String"),
+            Type::Number => Some("Number"),
+            Type::Array(_) => Some("Array"),
+            Type::Map(..) => Some("Map"),
+            Type::Set(_) => Some("Set"),
+            Type::Promise(_) => Some("Promise"),
+            Type::Object(_) => Some("Object"),
+            _ => None,
+        }
+    }
+
+    /// Method of a value, with the placeholders already replaced by the
+    /// element, key and value types.
+    /// Returns the name used in error messages along with the signature.
+    pub fn get_primitive_method(
         &self,
         primitive_type: &Type,
         method_name: &str,
+    ) -> Option<(String, MethodSignature)> {
+        let object_name = Self::primitive_name(primitive_type)?;
+        let signature = self.get_method(object_name, method_name)?;
+        let signature = match primitive_type {
+            Type::Array(element_type) | Type::Set(element_type) | Type::Promise(element_type) => {
+                signature.instantiate(&[(ELEMENT, element_type)])
+            }
+            Type::Map(key_type, value_type) => {
+                signature.instantiate(&[(KEY, key_type), (VALUE, value_type)])
+            }
+            _ => signature,
+        };
+        Some((format!("{}.{}", object_name, method_name), signature))
+    }
+
+    /// Names of the methods and properties of a stdlib object.
+    pub fn member_names(&self, object_name: &str) -> Vec<String> {
+        let methods = self
+            .objects
+            .get(object_name)
+            .into_iter()
+            .flat_map(|m| m.keys());
+        let properties = self
+            .properties
+            .get(object_name)
+            .into_iter()
+            .flat_map(|p| p.keys());
+        methods.chain(properties).cloned().collect()
+    }
+
+    pub fn primitive_member_names(&self, primitive_type: &Type) -> Vec<String> {
+        Self::primitive_name(primitive_type)
+            .map(|object_name| self.member_names(object_name))
+            .unwrap_or_default()
+    }
+
+    pub fn get_primitive_property_type(
+        &self,
+        primitive_type: &Type,
+        property_name: &str,
     ) -> Option<Type> {
-        match primitive_type {
-            Type::String => self.get_method_type("String", method_name),
-            Type::Number => self.get_method_type("Number", method_name),
-            Type::Array(_) => self.get_method_type("Array", method_name),
-            Type::Object(_) => self.get_method_type("Object", method_name),
-            _ => None,
-        }
+        let object_name = Self::primitive_name(primitive_type)?;
+        self.properties
+            .get(object_name)?
+            .get(property_name)
+            .cloned()
     }
 }
 
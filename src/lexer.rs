@@ -1,5 +1,5 @@
-use crate::ast::Type::Null;
-use std::str::Chars;
+use std::iter::Peekable;
+use std::str::CharIndices;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum TokenType {
@@ -19,6 +19,17 @@ pub enum TokenType {
     Fn,
     Arrow,
 
+    Class,
+    Extends,
+    Static,
+
+    Interface,
+    Impl,
+
+    Import,
+    Export,
+    Declare,
+
     Switch,
     Case,
     Default,
@@ -44,6 +55,7 @@ pub enum TokenType {
     Try,
     Catch,
     Finally,
+    Throw,
 
     GreaterThen,
     LessThen,
@@ -60,9 +72,12 @@ pub enum TokenType {
     Comma,
     Dot,
     DotDot,
+    DotDotDot,
     Colon,
     Semicolon,
     Question,
+    Pipe,
+    Hash,
 
     OpenParen,
     CloseParen,
@@ -81,6 +96,8 @@ pub struct Token {
     pub token_type: TokenType,
     pub value: String,
     pub line: u32,
+    /// byte offset within the line, starting at 1
+    pub column: usize,
 }
 
 impl Token {
@@ -89,6 +106,7 @@ impl Token {
             token_type,
             value,
             line,
+            column: 0,
         }
     }
 
@@ -97,6 +115,7 @@ impl Token {
             token_type,
             value: value.to_string(),
             line,
+            column: 0,
         }
     }
 }
@@ -106,21 +125,47 @@ pub fn tokenize(source_code: &str) -> Vec<Token> {
 }
 
 pub struct Lexer<'a> {
-    chars: std::iter::Peekable<Chars<'a>>,
+    chars: SourceChars<'a>,
     tokens: Vec<Token>,
 }
 
+// The characters of the source together with their byte offset.
+struct SourceChars<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    length: usize,
+}
+
+impl<'a> SourceChars<'a> {
+    fn next(&mut self) -> Option<char> {
+        self.chars.next().map(|(_, ch)| ch)
+    }
+
+    fn peek(&mut self) -> Option<&char> {
+        self.chars.peek().map(|(_, ch)| ch)
+    }
+
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.length, |(offset, _)| *offset)
+    }
+}
+
 impl<'a> Lexer<'a> {
     pub fn new(source: &'a str) -> Self {
         Self {
-            chars: source.chars().peekable(),
+            chars: SourceChars {
+                chars: source.char_indices().peekable(),
+                length: source.len(),
+            },
             tokens: Vec::new(),
         }
     }
 
     pub fn tokenize(mut self) -> Vec<Token> {
         let mut line: u32 = 1;
+        let mut line_start = 0;
         while let Some(&c) = self.chars.peek() {
+            let start = self.chars.offset();
+            let tokens_before = self.tokens.len();
             match c {
                 '(' => {
                     self.chars.next();
@@ -221,8 +266,14 @@ impl<'a> Lexer<'a> {
                     self.chars.next();
                     if let Some(&'.') = self.chars.peek() {
                         self.chars.next();
-                        self.tokens
-                            .push(Token::new_static(TokenType::DotDot, "..", line));
+                        if let Some(&'.') = self.chars.peek() {
+                            self.chars.next();
+                            self.tokens
+                                .push(Token::new_static(TokenType::DotDotDot, "...", line));
+                        } else {
+                            self.tokens
+                                .push(Token::new_static(TokenType::DotDot, "..", line));
+                        }
                     } else {
                         self.tokens
                             .push(Token::new_static(TokenType::Dot, ".", line));
@@ -238,21 +289,30 @@ impl<'a> Lexer<'a> {
                     self.tokens
                         .push(Token::new_static(TokenType::Colon, ":", line));
                 }
+                '#' => {
+                    self.chars.next();
+                    self.tokens
+                        .push(Token::new_static(TokenType::Hash, "#", line));
+                }
                 _ => {
                     if c.is_ascii_digit() {
                         self.tokenize_number(line);
-                    } else if c.is_alphabetic() {
+                    } else if c.is_alphabetic() || c == '_' {
                         self.tokenize_identifier(line);
                     } else if c.is_whitespace() {
+                        self.chars.next();
                         if c == '\n' {
                             line += 1;
+                            line_start = self.chars.offset();
                         }
-                        self.chars.next();
                     } else {
                         self.chars.next();
                     }
                 }
             }
+            for token in &mut self.tokens[tokens_before..] {
+                token.column = start - line_start + 1;
+            }
         }
 
         self.tokens
@@ -386,7 +446,8 @@ impl<'a> Lexer<'a> {
                         self.tokens
                             .push(Token::new_static(TokenType::BinaryOperator, "||", line));
                     } else {
-                        panic!("Unknown symbol");
+                        self.tokens
+                            .push(Token::new_static(TokenType::Pipe, "|", line));
                     }
                 }
                 '&' => {
@@ -408,7 +469,7 @@ impl<'a> Lexer<'a> {
         let mut identifier = String::new();
 
         while let Some(&ch) = self.chars.peek() {
-            if ch.is_alphabetic() || ch.is_ascii_digit() {
+            if ch.is_alphabetic() || ch.is_ascii_digit() || ch == '_' {
                 identifier.push(ch);
                 self.chars.next();
             } else {
@@ -430,6 +491,17 @@ impl<'a> Lexer<'a> {
 
             "fn" => TokenType::Fn,
 
+            "class" => TokenType::Class,
+            "extends" => TokenType::Extends,
+            "static" => TokenType::Static,
+
+            "interface" => TokenType::Interface,
+            "impl" => TokenType::Impl,
+
+            "import" => TokenType::Import,
+            "export" => TokenType::Export,
+            "declare" => TokenType::Declare,
+
             "true" => TokenType::True,
             "false" => TokenType::False,
 
@@ -444,6 +516,7 @@ impl<'a> Lexer<'a> {
             "try" => TokenType::Try,
             "catch" => TokenType::Catch,
             "finally" => TokenType::Finally,
+            "throw" => TokenType::Throw,
 
             "switch" => TokenType::Switch,
             "case" => TokenType::Case,
@@ -1,6 +1,10 @@
 pub mod ast;
 pub mod compiler;
+pub mod dts;
+pub mod fix;
 pub mod js_stdlib;
 pub mod lexer;
+pub mod lint;
+pub mod modules;
 pub mod parser;
 pub mod typechecker;
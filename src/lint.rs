@@ -0,0 +1,1041 @@
+use crate::ast::{Expr, ExprKind, Pattern, SourceMap, Stmt, Type};
+use crate::typechecker::{Symbol, SymbolId, TypeInfo};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Name of the file with project wide lint levels, one `rule = level` per
+/// line. It is looked up from the linted file upwards.
+pub const CONFIG_FILE: &str = ".lslint";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl Level {
+    pub fn from_name(name: &str) -> Option<Level> {
+        match name {
+            "allow" => Some(Level::Allow),
+            "warn" => Some(Level::Warn),
+            "deny" => Some(Level::Deny),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Lint {
+    pub rule: &'static str,
+    pub level: Level,
+    /// line of the statement the rule fired on
+    pub line: Option<u32>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BindingKind {
+    Variable,
+    Parameter,
+    Function,
+}
+
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub name: String,
+    pub kind: BindingKind,
+    pub used: bool,
+    pub line: Option<u32>,
+    /// what the checker declared for it, `None` if the checker did not see
+    /// the declaration
+    pub symbol: Option<SymbolId>,
+    // levels where the binding is declared, its lints are reported there
+    levels: Rc<HashMap<String, Level>>,
+}
+
+/// What rules can look at besides the node they are called for.
+pub struct LintContext<'a> {
+    type_info: &'a TypeInfo,
+    scopes: Vec<Vec<Binding>>,
+}
+
+impl<'a> LintContext<'a> {
+    pub fn type_of(&self, expr: &Expr) -> Option<&Type> {
+        self.type_info.type_of(expr)
+    }
+
+    pub fn symbol_of(&self, expr: &Expr) -> Option<&Symbol> {
+        self.type_info.symbol_of(expr)
+    }
+
+    /// Binding of `name` in a scope enclosing the current one.
+    pub fn outer_binding(&self, name: &str) -> Option<&Binding> {
+        let (_, outer_scopes) = self.scopes.split_last()?;
+        outer_scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.iter().find(|binding| binding.name == name))
+    }
+}
+
+/// A named check. Every hook returns the messages to report, at the level
+/// the rule has where the node is.
+pub trait LintRule {
+    fn name(&self) -> &'static str;
+
+    fn default_level(&self) -> Level {
+        Level::Warn
+    }
+
+    fn check_stmt(&self, _stmt: &Stmt, _cx: &LintContext) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn check_expr(&self, _expr: &Expr, _cx: &LintContext) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Called before the binding is added to the current scope.
+    fn check_declaration(&self, _binding: &Binding, _cx: &LintContext) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Called for every binding when its scope ends.
+    fn check_scope_end(&self, _binding: &Binding) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+pub struct Linter {
+    rules: Vec<Box<dyn LintRule>>,
+    // levels from the config file
+    levels: HashMap<String, Level>,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Linter {
+    pub fn new() -> Linter {
+        let mut linter = Linter {
+            rules: Vec::new(),
+            levels: HashMap::new(),
+        };
+        linter.register(Box::new(UnusedVariables));
+        linter.register(Box::new(UnusedParameters));
+        linter.register(Box::new(UnusedFunctions));
+        linter.register(Box::new(Shadowing));
+        linter.register(Box::new(NullComparison));
+        linter.register(Box::new(ConstantCondition));
+        linter.register(Box::new(EmptyCatch));
+        linter.register(Box::new(SelfAssignment));
+        linter
+    }
+
+    pub fn register(&mut self, rule: Box<dyn LintRule>) {
+        self.rules.push(rule);
+    }
+
+    pub fn rule_names(&self) -> Vec<&'static str> {
+        self.rules.iter().map(|rule| rule.name()).collect()
+    }
+
+    pub fn set_level(&mut self, rule: &str, level: Level) -> Result<(), String> {
+        if !self.rule_names().contains(&rule) {
+            return Err(format!("Unbekannte Lint-Regel '{}'", rule));
+        }
+        self.levels.insert(rule.to_string(), level);
+        Ok(())
+    }
+
+    /// The config file in the directory of `file` or the nearest one above.
+    pub fn find_config(file: &Path) -> Option<PathBuf> {
+        let dir = file.parent()?;
+        dir.ancestors()
+            .map(|dir| dir.join(CONFIG_FILE))
+            .find(|path| path.is_file())
+    }
+
+    pub fn load_config(&mut self, path: &Path) -> Result<(), String> {
+        let config = fs::read_to_string(path)
+            .map_err(|e| format!("Fehler beim Lesen von {}: {}", path.display(), e))?;
+        for (line_number, line) in config.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (rule, level) = line.split_once('=').ok_or_else(|| {
+                format!(
+                    "{}:{}: Erwarte 'regel = allow|warn|deny'",
+                    path.display(),
+                    line_number + 1
+                )
+            })?;
+            let level = Level::from_name(level.trim()).ok_or_else(|| {
+                format!(
+                    "{}:{}: Unbekannte Stufe '{}'",
+                    path.display(),
+                    line_number + 1,
+                    level.trim()
+                )
+            })?;
+            self.set_level(rule.trim(), level)
+                .map_err(|e| format!("{}:{}: {}", path.display(), line_number + 1, e))?;
+        }
+        Ok(())
+    }
+
+    /// Lints a checked program, `type_info` is what its checker learned and
+    /// `source_map` what its parser recorded.
+    pub fn lint(&self, program: &Stmt, type_info: &TypeInfo, source_map: &SourceMap) -> Vec<Lint> {
+        let mut walker = Walker {
+            source_map,
+            line: None,
+            rules: &self.rules,
+            cx: LintContext {
+                type_info,
+                scopes: vec![Vec::new()],
+            },
+            levels: Rc::new(self.levels.clone()),
+            lints: Vec::new(),
+        };
+        if let Stmt::Program { body } = program {
+            walker.walk_block(body);
+        }
+        walker.end_scope();
+        walker.lints
+    }
+}
+
+struct Walker<'a> {
+    source_map: &'a SourceMap,
+    // line of the innermost statement being walked
+    line: Option<u32>,
+    rules: &'a [Box<dyn LintRule>],
+    cx: LintContext<'a>,
+    levels: Rc<HashMap<String, Level>>,
+    lints: Vec<Lint>,
+}
+
+impl<'a> Walker<'a> {
+    fn report(
+        &mut self,
+        rule: &dyn LintRule,
+        levels: &HashMap<String, Level>,
+        line: Option<u32>,
+        messages: Vec<String>,
+    ) {
+        let level = levels
+            .get(rule.name())
+            .copied()
+            .unwrap_or(rule.default_level());
+        if level == Level::Allow {
+            return;
+        }
+        for message in messages {
+            self.lints.push(Lint {
+                rule: rule.name(),
+                level,
+                line,
+                message,
+            });
+        }
+    }
+
+    fn apply_attribute(&mut self, name: &str, arguments: &[String]) {
+        // read by the type checker
+        if name == "strict" {
+            return;
+        }
+        let Some(level) = Level::from_name(name) else {
+            self.lints.push(Lint {
+                rule: "unknown_attribute",
+                level: Level::Warn,
+                line: self.line,
+                message: format!("Unbekanntes Attribut '{}'", name),
+            });
+            return;
+        };
+        let mut levels = (*self.levels).clone();
+        for rule in arguments {
+            if self.rules.iter().any(|known| known.name() == rule) {
+                levels.insert(rule.clone(), level);
+            } else {
+                self.lints.push(Lint {
+                    rule: "unknown_attribute",
+                    level: Level::Warn,
+                    line: self.line,
+                    message: format!("Unbekannte Lint-Regel '{}'", rule),
+                });
+            }
+        }
+        self.levels = Rc::new(levels);
+    }
+
+    // Levels each statement of the block is linted with. `#[...]` applies to
+    // the next statement, `#![...]` to the rest of the block.
+    fn statement_levels(&mut self, stmts: &[Stmt]) -> Vec<Rc<HashMap<String, Level>>> {
+        let block_levels = self.levels.clone();
+        let mut statement_levels = Vec::new();
+        let mut pending: Vec<(&String, &Vec<String>)> = Vec::new();
+        for stmt in stmts {
+            if let Stmt::Attribute {
+                name,
+                arguments,
+                applies_to_file,
+            } = stmt
+            {
+                self.line = self.source_map.line_of(stmt);
+                if *applies_to_file {
+                    self.apply_attribute(name, arguments);
+                } else {
+                    pending.push((name, arguments));
+                }
+                statement_levels.push(self.levels.clone());
+                continue;
+            }
+            let rest_levels = self.levels.clone();
+            for (name, arguments) in pending.drain(..) {
+                self.apply_attribute(name, arguments);
+            }
+            statement_levels.push(self.levels.clone());
+            self.levels = rest_levels;
+        }
+        self.levels = block_levels;
+        statement_levels
+    }
+
+    fn walk_block(&mut self, stmts: &[Stmt]) {
+        let block_line = self.line;
+        let statement_levels = self.statement_levels(stmts);
+        let block_levels = self.levels.clone();
+        // functions can be called before their declaration
+        for (stmt, levels) in stmts.iter().zip(&statement_levels) {
+            let (declaration, exported) = match stmt {
+                Stmt::Export { declaration } => (declaration.as_ref(), true),
+                _ => (stmt, false),
+            };
+            if let Stmt::FunctionDeclaration { name, .. } = declaration {
+                self.levels = levels.clone();
+                self.line = self.source_map.line_of(stmt);
+                self.declare(name, BindingKind::Function, exported);
+            }
+        }
+
+        for (stmt, levels) in stmts.iter().zip(statement_levels) {
+            self.levels = levels;
+            self.walk_stmt(stmt);
+        }
+        self.levels = block_levels;
+        self.line = block_line;
+    }
+
+    fn walk_scoped_block(&mut self, stmts: &[Stmt]) {
+        self.cx.scopes.push(Vec::new());
+        self.walk_block(stmts);
+        self.end_scope();
+    }
+
+    fn end_scope(&mut self) {
+        let Some(scope) = self.cx.scopes.pop() else {
+            return;
+        };
+        for binding in &scope {
+            for rule in self.rules {
+                let messages = rule.check_scope_end(binding);
+                self.report(
+                    rule.as_ref(),
+                    &binding.levels.clone(),
+                    binding.line,
+                    messages,
+                );
+            }
+        }
+    }
+
+    fn declare(&mut self, name: &str, kind: BindingKind, used: bool) {
+        let symbol = self
+            .source_map
+            .name_position(name.as_ptr())
+            .and_then(|position| self.cx.type_info.symbol_at(position))
+            .map(|symbol| symbol.id);
+        let binding = Binding {
+            name: name.to_string(),
+            kind,
+            used,
+            line: self.line,
+            symbol,
+            levels: self.levels.clone(),
+        };
+        for rule in self.rules {
+            let messages = rule.check_declaration(&binding, &self.cx);
+            let levels = self.levels.clone();
+            self.report(rule.as_ref(), &levels, self.line, messages);
+        }
+        self.cx.scopes.last_mut().expect("lint scope").push(binding);
+    }
+
+    fn declare_pattern(&mut self, pattern: &Pattern, used: bool) {
+        match pattern {
+            Pattern::Identifier(name) => self.declare(name, BindingKind::Variable, used),
+            Pattern::Array { elements, rest } => {
+                for element in elements {
+                    if let Some(default_value) = &element.default_value {
+                        self.walk_expr(default_value);
+                    }
+                    self.declare_pattern(&element.pattern, used);
+                }
+                if let Some(rest) = rest {
+                    self.declare_pattern(rest, used);
+                }
+            }
+            Pattern::Object { properties, rest } => {
+                for property in properties {
+                    if let Some(default_value) = &property.default_value {
+                        self.walk_expr(default_value);
+                    }
+                    self.declare_pattern(&property.pattern, used);
+                }
+                if let Some(rest) = rest {
+                    self.declare(rest, BindingKind::Variable, used);
+                }
+            }
+        }
+    }
+
+    // Reading an identifier uses the declaration the checker resolved it to,
+    // by name only if the checker did not see it.
+    fn mark_read(&mut self, expr: &Expr, name: &str) {
+        if let Some(symbol) = self.cx.symbol_of(expr).map(|symbol| symbol.id) {
+            let binding = self
+                .cx
+                .scopes
+                .iter_mut()
+                .flatten()
+                .find(|binding| binding.symbol == Some(symbol));
+            if let Some(binding) = binding {
+                binding.used = true;
+                return;
+            }
+        }
+        self.mark_used(name);
+    }
+
+    fn mark_used(&mut self, name: &str) {
+        for scope in self.cx.scopes.iter_mut().rev() {
+            if let Some(binding) = scope.iter_mut().rev().find(|binding| binding.name == name) {
+                binding.used = true;
+                return;
+            }
+        }
+    }
+
+    fn check_stmt_rules(&mut self, stmt: &Stmt) {
+        for rule in self.rules {
+            let messages = rule.check_stmt(stmt, &self.cx);
+            let levels = self.levels.clone();
+            self.report(rule.as_ref(), &levels, self.line, messages);
+        }
+    }
+
+    fn walk_stmt(&mut self, stmt: &Stmt) {
+        let outer_line = self.line;
+        // statements outside of blocks, like exported ones, have no line
+        if let Some(line) = self.source_map.line_of(stmt) {
+            self.line = Some(line);
+        }
+        self.check_stmt_rules(stmt);
+        match stmt {
+            Stmt::VarDeclaration { pattern, value, .. } => {
+                if let Some(value) = value {
+                    self.walk_expr(value);
+                }
+                self.declare_pattern(pattern, false);
+            }
+            Stmt::FunctionDeclaration { .. } => self.walk_function(stmt, true),
+            Stmt::ClassDeclaration { members, .. } => {
+                for member in members {
+                    match member {
+                        crate::ast::ClassMember::Field {
+                            value: Some(value), ..
+                        } => self.walk_expr(value),
+                        crate::ast::ClassMember::Method { declaration, .. } => {
+                            // parameters may be dictated by the parent or an interface
+                            self.walk_function(declaration, false)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Stmt::ImplDeclaration { methods, .. } => {
+                for method in methods {
+                    self.walk_function(method, false);
+                }
+            }
+            Stmt::Export { declaration } => {
+                self.walk_stmt(declaration);
+                if let Stmt::VarDeclaration { pattern, .. } = declaration.as_ref() {
+                    let mut names = Vec::new();
+                    pattern_names(pattern, &mut names);
+                    for name in names {
+                        self.mark_used(name);
+                    }
+                }
+            }
+            Stmt::Expression(expr) => self.walk_expr(expr),
+            Stmt::IfStatement {
+                condition,
+                then_branch,
+                else_if_branches,
+                else_branch,
+            } => {
+                self.walk_expr(condition);
+                self.walk_scoped_block(then_branch);
+                for branch in else_if_branches.iter().flatten() {
+                    self.walk_expr(&branch.condition);
+                    self.walk_scoped_block(&branch.body);
+                }
+                if let Some(else_branch) = else_branch {
+                    self.walk_scoped_block(else_branch);
+                }
+            }
+            Stmt::WhileStatement { condition, body } => {
+                self.walk_expr(condition);
+                self.walk_scoped_block(body);
+            }
+            Stmt::ReturnStatement { value: Some(value) } => self.walk_expr(value),
+            Stmt::ThrowStatement { value } => self.walk_expr(value),
+            Stmt::TryCatchFinally {
+                try_branch,
+                catch_clauses,
+                finally_branch,
+            } => {
+                self.walk_scoped_block(try_branch);
+                for clause in catch_clauses {
+                    self.cx.scopes.push(Vec::new());
+                    if let Some(binding) = &clause.binding {
+                        // `catch (e)` is how the error type is picked
+                        self.declare(binding, BindingKind::Variable, true);
+                    }
+                    self.walk_block(&clause.body);
+                    self.end_scope();
+                }
+                if let Some(finally_branch) = finally_branch {
+                    self.walk_scoped_block(finally_branch);
+                }
+            }
+            Stmt::SwitchStatement {
+                condition,
+                case_branches,
+                default_branch,
+            } => {
+                self.walk_expr(condition);
+                for branch in case_branches {
+                    self.walk_expr(&branch.condition);
+                    self.walk_scoped_block(&branch.body);
+                }
+                if let Some(default_branch) = default_branch {
+                    self.walk_scoped_block(default_branch);
+                }
+            }
+            Stmt::ForLoopStatement {
+                initializer,
+                condition,
+                update,
+                body,
+            } => {
+                self.cx.scopes.push(Vec::new());
+                if let Some(initializer) = initializer {
+                    self.walk_stmt(initializer);
+                }
+                if let Some(condition) = condition {
+                    self.walk_expr(condition);
+                }
+                if let Some(update) = update {
+                    self.walk_expr(update);
+                }
+                self.walk_block(body);
+                self.end_scope();
+            }
+            Stmt::ForInLoopStatement {
+                iterator,
+                iterable,
+                body,
+            } => {
+                if let Some(iterable) = iterable {
+                    self.walk_expr(iterable);
+                }
+                self.cx.scopes.push(Vec::new());
+                if let Some(iterator) = iterator {
+                    if let Stmt::VarDeclaration { pattern, .. } = iterator.as_ref() {
+                        self.declare_pattern(pattern, false);
+                    }
+                }
+                self.walk_block(body);
+                self.end_scope();
+            }
+            Stmt::ForLoopIterated {
+                first_number,
+                second_number,
+                iterator_name,
+                body,
+            } => {
+                for number in [first_number, second_number].into_iter().flatten() {
+                    self.walk_expr(number);
+                }
+                self.cx.scopes.push(Vec::new());
+                if let Some(iterator_name) = iterator_name {
+                    self.declare(iterator_name, BindingKind::Variable, false);
+                }
+                self.walk_block(body);
+                self.end_scope();
+            }
+            _ => {}
+        }
+        self.line = outer_line;
+    }
+
+    fn walk_function(&mut self, stmt: &Stmt, check_parameters: bool) {
+        let Stmt::FunctionDeclaration {
+            name,
+            parameters,
+            param_defaults,
+            body,
+            ..
+        } = stmt
+        else {
+            return;
+        };
+        for default_value in param_defaults.iter().flatten() {
+            self.walk_expr(default_value);
+        }
+
+        self.cx.scopes.push(Vec::new());
+        for parameter in parameters {
+            let mut names = Vec::new();
+            pattern_names(parameter, &mut names);
+            for parameter_name in names {
+                self.declare(parameter_name, BindingKind::Parameter, !check_parameters);
+            }
+        }
+        // recursive calls do not count as a use of the function
+        let used_before = self.is_used(name);
+        self.walk_block(body);
+        if !used_before {
+            self.reset_used(name);
+        }
+        self.end_scope();
+    }
+
+    fn is_used(&self, name: &str) -> bool {
+        self.cx
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.iter().rev().find(|binding| binding.name == name))
+            .is_some_and(|binding| binding.used)
+    }
+
+    fn reset_used(&mut self, name: &str) {
+        for scope in self.cx.scopes.iter_mut().rev() {
+            if let Some(binding) = scope.iter_mut().rev().find(|binding| binding.name == name) {
+                if binding.kind == BindingKind::Function {
+                    binding.used = false;
+                }
+                return;
+            }
+        }
+    }
+
+    fn walk_expr(&mut self, expr: &Expr) {
+        for rule in self.rules {
+            let messages = rule.check_expr(expr, &self.cx);
+            let levels = self.levels.clone();
+            self.report(rule.as_ref(), &levels, self.line, messages);
+        }
+        match &expr.kind {
+            ExprKind::Identifier(name) => self.mark_read(expr, name),
+            // writing a variable is not reading it
+            ExprKind::Assignment { assignee, value } => {
+                if !matches!(&assignee.kind, ExprKind::Identifier(_)) {
+                    self.walk_expr(assignee);
+                }
+                self.walk_expr(value);
+            }
+            ExprKind::CompoundAssignment {
+                assignee, value, ..
+            } => {
+                self.walk_expr(assignee);
+                self.walk_expr(value);
+            }
+            ExprKind::Binary { left, right, .. } => {
+                self.walk_expr(left);
+                self.walk_expr(right);
+            }
+            ExprKind::Unary { value, .. }
+            | ExprKind::AwaitExpression { value }
+            | ExprKind::Spread { value }
+            | ExprKind::NamedArgument { value, .. }
+            | ExprKind::Propagate { value } => self.walk_expr(value),
+            ExprKind::Increment { identifier, .. } => self.walk_expr(identifier),
+            ExprKind::Call { caller, args } => {
+                self.walk_expr(caller);
+                for arg in args {
+                    self.walk_expr(arg);
+                }
+            }
+            ExprKind::Member {
+                object,
+                property,
+                computed,
+            } => {
+                self.walk_expr(object);
+                if *computed {
+                    self.walk_expr(property);
+                }
+            }
+            ExprKind::ArrayLiteral(values) => {
+                for value in values {
+                    self.walk_expr(value);
+                }
+            }
+            ExprKind::ObjectLiteral(properties) => {
+                for property in properties {
+                    match &property.value {
+                        Some(value) => self.walk_expr(value),
+                        // `{ name }` reads the variable `name`
+                        None => self.mark_used(&property.key),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// The names themselves, their addresses are what the source map knows.
+fn pattern_names<'a>(pattern: &'a Pattern, names: &mut Vec<&'a String>) {
+    match pattern {
+        Pattern::Identifier(name) => names.push(name),
+        Pattern::Array { elements, rest } => {
+            for element in elements {
+                pattern_names(&element.pattern, names);
+            }
+            if let Some(rest) = rest {
+                pattern_names(rest, names);
+            }
+        }
+        Pattern::Object { properties, rest } => {
+            for property in properties {
+                pattern_names(&property.pattern, names);
+            }
+            names.extend(rest);
+        }
+    }
+}
+
+fn is_unused(binding: &Binding, kind: BindingKind) -> bool {
+    binding.kind == kind && !binding.used && !binding.name.starts_with('_')
+}
+
+struct UnusedVariables;
+
+impl LintRule for UnusedVariables {
+    fn name(&self) -> &'static str {
+        "unused_variables"
+    }
+
+    fn check_scope_end(&self, binding: &Binding) -> Vec<String> {
+        if !is_unused(binding, BindingKind::Variable) {
+            return Vec::new();
+        }
+        vec![format!("Variable '{}' wird nie gelesen", binding.name)]
+    }
+}
+
+struct UnusedParameters;
+
+impl LintRule for UnusedParameters {
+    fn name(&self) -> &'static str {
+        "unused_parameters"
+    }
+
+    fn check_scope_end(&self, binding: &Binding) -> Vec<String> {
+        if !is_unused(binding, BindingKind::Parameter) {
+            return Vec::new();
+        }
+        vec![format!("Parameter '{}' wird nie verwendet", binding.name)]
+    }
+}
+
+struct UnusedFunctions;
+
+impl LintRule for UnusedFunctions {
+    fn name(&self) -> &'static str {
+        "unused_functions"
+    }
+
+    fn check_scope_end(&self, binding: &Binding) -> Vec<String> {
+        if !is_unused(binding, BindingKind::Function) {
+            return Vec::new();
+        }
+        vec![format!("Funktion '{}' wird nie verwendet", binding.name)]
+    }
+}
+
+struct Shadowing;
+
+impl LintRule for Shadowing {
+    fn name(&self) -> &'static str {
+        "shadowing"
+    }
+
+    fn check_declaration(&self, binding: &Binding, cx: &LintContext) -> Vec<String> {
+        if cx.outer_binding(&binding.name).is_none() {
+            return Vec::new();
+        }
+        vec![format!(
+            "'{}' verdeckt eine gleichnamige Deklaration aus einem äußeren Gültigkeitsbereich",
+            binding.name
+        )]
+    }
+}
+
+struct NullComparison;
+
+impl LintRule for NullComparison {
+    fn name(&self) -> &'static str {
+        "null_comparison"
+    }
+
+    fn check_expr(&self, expr: &Expr, cx: &LintContext) -> Vec<String> {
+        let ExprKind::Binary {
+            left,
+            right,
+            operator,
+        } = &expr.kind
+        else {
+            return Vec::new();
+        };
+        if operator != "==" && operator != "!=" {
+            return Vec::new();
+        }
+        let compared = match (&left.kind, &right.kind) {
+            (ExprKind::NullLiteral, _) => right,
+            (_, ExprKind::NullLiteral) => left,
+            _ => return Vec::new(),
+        };
+        let Some(compared_type) = cx.type_of(compared) else {
+            return Vec::new();
+        };
+        let can_be_null = match &compared_type {
+            Type::Option(_) | Type::Null | Type::Any => true,
+            Type::Union(members) => members.contains(&Type::Null),
+            _ => false,
+        };
+        if can_be_null {
+            return Vec::new();
+        }
+        vec![format!(
+            "Vergleich mit null ist immer {}, {:?} ist nie null",
+            operator == "!=",
+            compared_type
+        )]
+    }
+}
+
+struct ConstantCondition;
+
+impl ConstantCondition {
+    fn constant_value(expr: &Expr) -> Option<bool> {
+        match &expr.kind {
+            ExprKind::BooleanLiteral(value) => Some(*value),
+            ExprKind::Unary { operator, value } if operator == "!" => {
+                Self::constant_value(value).map(|value| !value)
+            }
+            ExprKind::Binary {
+                left,
+                right,
+                operator,
+            } => match (&left.kind, &right.kind) {
+                (ExprKind::NumericLiteral(left), ExprKind::NumericLiteral(right)) => {
+                    Self::compare(operator, left.partial_cmp(right)?)
+                }
+                (ExprKind::StringLiteral(left), ExprKind::StringLiteral(right)) => {
+                    Self::compare(operator, left.cmp(right))
+                }
+                _ => {
+                    let left = Self::constant_value(left)?;
+                    let right = Self::constant_value(right)?;
+                    match operator.as_str() {
+                        "&&" => Some(left && right),
+                        "||" => Some(left || right),
+                        "==" => Some(left == right),
+                        "!=" => Some(left != right),
+                        _ => None,
+                    }
+                }
+            },
+            _ => None,
+        }
+    }
+
+    fn compare(operator: &str, ordering: std::cmp::Ordering) -> Option<bool> {
+        match operator {
+            "==" => Some(ordering.is_eq()),
+            "!=" => Some(ordering.is_ne()),
+            "<" => Some(ordering.is_lt()),
+            ">" => Some(ordering.is_gt()),
+            "<=" => Some(ordering.is_le()),
+            ">=" => Some(ordering.is_ge()),
+            _ => None,
+        }
+    }
+}
+
+impl LintRule for ConstantCondition {
+    fn name(&self) -> &'static str {
+        "constant_condition"
+    }
+
+    fn check_stmt(&self, stmt: &Stmt, _cx: &LintContext) -> Vec<String> {
+        let conditions: Vec<&Expr> = match stmt {
+            Stmt::IfStatement {
+                condition,
+                else_if_branches,
+                ..
+            } => std::iter::once(condition)
+                .chain(
+                    else_if_branches
+                        .iter()
+                        .flatten()
+                        .map(|branch| &branch.condition),
+                )
+                .collect(),
+            // `while (true)` is how endless loops are written
+            Stmt::WhileStatement { condition, .. }
+                if !matches!(&condition.kind, ExprKind::BooleanLiteral(true)) =>
+            {
+                vec![condition]
+            }
+            Stmt::ForLoopStatement {
+                condition: Some(condition),
+                ..
+            } => vec![condition],
+            _ => Vec::new(),
+        };
+        conditions
+            .into_iter()
+            .filter_map(Self::constant_value)
+            .map(|value| format!("Bedingung ist immer {}", value))
+            .collect()
+    }
+}
+
+struct EmptyCatch;
+
+impl LintRule for EmptyCatch {
+    fn name(&self) -> &'static str {
+        "empty_catch"
+    }
+
+    fn check_stmt(&self, stmt: &Stmt, _cx: &LintContext) -> Vec<String> {
+        let Stmt::TryCatchFinally { catch_clauses, .. } = stmt else {
+            return Vec::new();
+        };
+        catch_clauses
+            .iter()
+            .filter(|clause| clause.body.is_empty())
+            .map(|_| "Leerer catch-Block verschluckt den Fehler".to_string())
+            .collect()
+    }
+}
+
+struct SelfAssignment;
+
+impl SelfAssignment {
+    fn same_place(left: &Expr, right: &Expr) -> bool {
+        match (&left.kind, &right.kind) {
+            (ExprKind::Identifier(left), ExprKind::Identifier(right)) => left == right,
+            (
+                ExprKind::Member {
+                    object: left_object,
+                    property: left_property,
+                    computed: false,
+                },
+                ExprKind::Member {
+                    object: right_object,
+                    property: right_property,
+                    computed: false,
+                },
+            ) => {
+                Self::same_place(left_object, right_object)
+                    && Self::same_place(left_property, right_property)
+            }
+            _ => false,
+        }
+    }
+
+    fn describe(expr: &Expr) -> String {
+        match &expr.kind {
+            ExprKind::Identifier(name) => name.clone(),
+            ExprKind::Member {
+                object, property, ..
+            } => format!("{}.{}", Self::describe(object), Self::describe(property)),
+            _ => "...".to_string(),
+        }
+    }
+}
+
+impl LintRule for SelfAssignment {
+    fn name(&self) -> &'static str {
+        "self_assignment"
+    }
+
+    fn check_expr(&self, expr: &Expr, _cx: &LintContext) -> Vec<String> {
+        let ExprKind::Assignment { assignee, value } = &expr.kind else {
+            return Vec::new();
+        };
+        if !Self::same_place(assignee, value) {
+            return Vec::new();
+        }
+        vec![format!(
+            "'{}' wird sich selbst zugewiesen",
+            Self::describe(assignee)
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Linter;
+    use crate::parser::Parser;
+    use crate::typechecker::TypeChecker;
+
+    #[test]
+    fn reads_count_for_the_declaration_they_resolve_to() {
+        let source = "let x = 1;\nif (true) {\n    let x = 2;\n    console.log(x);\n}\n";
+        let mut parser = Parser::new();
+        let program = parser.produce_ast(source);
+        let source_map = parser.into_source_map();
+        let mut type_checker = TypeChecker::new();
+        type_checker.set_source_map(source_map.clone());
+        type_checker
+            .check_program(&program)
+            .expect("Programm ist korrekt");
+
+        let lints = Linter::new().lint(&program, type_checker.type_info(), &source_map);
+        let unused: Vec<_> = lints
+            .iter()
+            .filter(|lint| lint.rule == "unused_variables")
+            .map(|lint| lint.line)
+            .collect();
+        assert_eq!(unused, vec![Some(1)]);
+    }
+}
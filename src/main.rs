@@ -1,10 +1,5 @@
-mod ast;
-mod compiler;
-mod js_stdlib;
-mod lexer;
-mod parser;
-mod typechecker;
-use compiler::Compiler;
+use lenniscript::compiler::Compiler;
+use lenniscript::{parser, typechecker};
 use std::fs::File;
 use std::io::Write;
 
@@ -20,10 +15,11 @@ fn main() {
                         break;
                     }
                 }
-                let zahl: num = await 5+5;
+                let zahl: num = await Promise.resolve(10);
             }
             catch {
                 console.log("ERROR");
+                return 0;
             }
             finally {
                 console.log("clean up");
@@ -80,11 +76,16 @@ fn main() {
     "#;
 
     let mut parser = parser::Parser::new();
-    let ast = parser.produceAst(source);
+    let ast = parser.produce_ast(source);
     println!("{:?}", &ast);
     let mut type_checker = typechecker::TypeChecker::new();
+    type_checker.set_source_map(parser.into_source_map());
     match type_checker.check_program(&ast) {
         Ok(_) => {
+            type_checker.print_current_scope();
+            for warning in type_checker.warnings() {
+                eprintln!("Warnung: {}", warning);
+            }
             let mut compiler = Compiler::new();
             match compiler.compile_programm(&ast) {
                 Ok(compiled_output) => {
@@ -0,0 +1,308 @@
+use crate::ast::{SourceMap, Stmt};
+use crate::compiler::Compiler;
+use crate::dts::read_declaration_file;
+use crate::fix::{self, Edit};
+use crate::lint::{Level, Linter};
+use crate::parser::Parser;
+use crate::typechecker::{ModuleExports, TypeChecker};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub enum ModuleSource {
+    Lenniscript(Stmt),
+    /// typings of a JS module, read from a `.d.ts` file
+    Declarations(ModuleExports),
+}
+
+pub struct Module {
+    pub path: PathBuf,
+    pub source: ModuleSource,
+    /// import source as written in the file and the resolved path
+    pub imports: Vec<(String, PathBuf)>,
+    /// lines of the statements, empty for declarations
+    pub source_map: SourceMap,
+}
+
+/// A type error, with the edit fixing it if the checker had a suggestion.
+pub struct CheckError {
+    pub message: String,
+    pub fix: Option<(PathBuf, Edit)>,
+}
+
+pub struct ModuleGraph {
+    pub modules: HashMap<PathBuf, Module>,
+    /// dependencies come before the modules importing them
+    pub order: Vec<PathBuf>,
+    strict: bool,
+}
+
+pub fn resolve_import(importer: &Path, source: &str) -> Result<PathBuf, String> {
+    let base_dir = importer.parent().unwrap_or_else(|| Path::new("."));
+    if !source.starts_with("./") && !source.starts_with("../") {
+        return resolve_package_typings(base_dir, source).ok_or_else(|| {
+            format!(
+                "Keine Typdeklarationen für Paket '{}' gefunden (importiert von {})",
+                source,
+                importer.display()
+            )
+        });
+    }
+
+    let mut path = base_dir.join(source);
+    match path.extension().and_then(|extension| extension.to_str()) {
+        None => {
+            path.set_extension("ls");
+        }
+        // JS files are described by the `.d.ts` file next to them
+        Some("js") | Some("mjs") => {
+            path.set_extension("d.ts");
+        }
+        _ => {}
+    }
+
+    path.canonicalize().map_err(|_| {
+        format!(
+            "Modul '{}' nicht gefunden (importiert von {})",
+            source,
+            importer.display()
+        )
+    })
+}
+
+// Looks for the typings of an npm package the way node looks for packages,
+// in every `node_modules` up from the importing file.
+fn resolve_package_typings(base_dir: &Path, package: &str) -> Option<PathBuf> {
+    for dir in base_dir.ancestors() {
+        let node_modules = dir.join("node_modules");
+        let package_dir = node_modules.join(package);
+
+        if let Ok(manifest) = fs::read_to_string(package_dir.join("package.json")) {
+            for field in ["types", "typings"] {
+                if let Some(typings) = manifest_field(&manifest, field) {
+                    let path = package_dir.join(typings);
+                    if path.is_file() {
+                        return path.canonicalize().ok();
+                    }
+                }
+            }
+        }
+
+        let candidates = [
+            package_dir.join("index.d.ts"),
+            node_modules.join("@types").join(package).join("index.d.ts"),
+        ];
+        if let Some(path) = candidates.iter().find(|path| path.is_file()) {
+            return path.canonicalize().ok();
+        }
+    }
+    None
+}
+
+// Good enough for the flat string fields of a package.json
+fn manifest_field(manifest: &str, field: &str) -> Option<String> {
+    let key = format!("\"{}\"", field);
+    let after_key = &manifest[manifest.find(&key)? + key.len()..];
+    let after_colon = after_key.trim_start().strip_prefix(':')?.trim_start();
+    let value = after_colon.strip_prefix('"')?;
+    Some(value[..value.find('"')?].to_string())
+}
+
+impl ModuleGraph {
+    pub fn load(entry: &Path) -> Result<ModuleGraph, String> {
+        let entry = entry
+            .canonicalize()
+            .map_err(|e| format!("Fehler beim Lesen von {}: {}", entry.display(), e))?;
+
+        let mut graph = ModuleGraph {
+            modules: HashMap::new(),
+            order: Vec::new(),
+            strict: false,
+        };
+        graph.visit(&entry, &mut Vec::new())?;
+        Ok(graph)
+    }
+
+    /// Checks every module in strict mode, not only those asking for it.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    fn visit(&mut self, path: &Path, stack: &mut Vec<PathBuf>) -> Result<(), String> {
+        if let Some(position) = stack.iter().position(|visiting| visiting == path) {
+            let cycle: Vec<String> = stack[position..]
+                .iter()
+                .chain(std::iter::once(&path.to_path_buf()))
+                .map(|module| module.display().to_string())
+                .collect();
+            return Err(format!("Zyklischer Import: {}", cycle.join(" -> ")));
+        }
+        if self.modules.contains_key(path) {
+            return Ok(());
+        }
+
+        let source = fs::read_to_string(path)
+            .map_err(|e| format!("Fehler beim Lesen von {}: {}", path.display(), e))?;
+
+        if path.to_string_lossy().ends_with(".d.ts") {
+            let declarations =
+                read_declaration_file(&source).map_err(|e| format!("{}: {}", path.display(), e))?;
+            self.order.push(path.to_path_buf());
+            self.modules.insert(
+                path.to_path_buf(),
+                Module {
+                    path: path.to_path_buf(),
+                    source: ModuleSource::Declarations(declarations),
+                    imports: Vec::new(),
+                    source_map: SourceMap::default(),
+                },
+            );
+            return Ok(());
+        }
+
+        let mut parser = Parser::new();
+        let program = parser.produce_ast(&source);
+        let source_map = parser.into_source_map();
+
+        let mut imports = Vec::new();
+        if let Stmt::Program { body } = &program {
+            for stmt in body {
+                if let Stmt::Import { source, .. } = stmt {
+                    imports.push((source.clone(), resolve_import(path, source)?));
+                }
+            }
+        }
+
+        stack.push(path.to_path_buf());
+        for (_, import_path) in &imports {
+            self.visit(import_path, stack)?;
+        }
+        stack.pop();
+
+        self.order.push(path.to_path_buf());
+        self.modules.insert(
+            path.to_path_buf(),
+            Module {
+                path: path.to_path_buf(),
+                source: ModuleSource::Lenniscript(program),
+                imports,
+                source_map,
+            },
+        );
+        Ok(())
+    }
+
+    /// Typechecks every module on its own, imports only see the exports.
+    /// Returns the warnings of all modules.
+    pub fn check(&self) -> Result<Vec<String>, String> {
+        let mut warnings = Vec::new();
+        let checked = self.check_modules().map_err(|error| error.message)?;
+        for (path, type_checker) in checked {
+            for warning in type_checker.warnings() {
+                warnings.push(format!("{}: {}", path.display(), warning));
+            }
+        }
+        Ok(warnings)
+    }
+
+    /// Typechecks and lints every module. Returns the warnings, lints denied
+    /// by their level are returned as errors.
+    pub fn lint(&self, linter: &Linter) -> Result<(Vec<String>, Vec<String>), String> {
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let checked = self.check_modules().map_err(|error| error.message)?;
+        for (path, type_checker) in checked {
+            for warning in type_checker.warnings() {
+                warnings.push(format!("{}: {}", path.display(), warning));
+            }
+            let ModuleSource::Lenniscript(program) = &self.modules[path].source else {
+                continue;
+            };
+            let source_map = &self.modules[path].source_map;
+            for lint in linter.lint(program, type_checker.type_info(), source_map) {
+                let location = match lint.line {
+                    Some(line) => format!("{}:{}", path.display(), line),
+                    None => path.display().to_string(),
+                };
+                let message = format!("{}: {} [{}]", location, lint.message, lint.rule);
+                match lint.level {
+                    Level::Deny => errors.push(message),
+                    _ => warnings.push(message),
+                }
+            }
+        }
+        Ok((warnings, errors))
+    }
+
+    /// Typechecks every module, only the first type error is reported.
+    pub fn check_fixable(&self) -> Result<(), CheckError> {
+        self.check_modules().map(|_| ())
+    }
+
+    fn check_modules(&self) -> Result<Vec<(&PathBuf, TypeChecker)>, CheckError> {
+        let mut exports: HashMap<&PathBuf, ModuleExports> = HashMap::new();
+        let mut checked = Vec::new();
+        for path in &self.order {
+            let module = &self.modules[path];
+            let program = match &module.source {
+                ModuleSource::Lenniscript(program) => program,
+                ModuleSource::Declarations(declarations) => {
+                    exports.insert(path, declarations.clone());
+                    continue;
+                }
+            };
+            let mut type_checker = TypeChecker::new();
+            type_checker.set_strict(self.strict);
+            type_checker.set_source_map(module.source_map.clone());
+            for (source, import_path) in &module.imports {
+                type_checker.register_module(source, exports[import_path].clone());
+            }
+            if let Err(error) = type_checker.check_program(program) {
+                let fix = type_checker.suggestion().and_then(|suggestion| {
+                    let source = fs::read_to_string(path).ok()?;
+                    Some((
+                        path.clone(),
+                        fix::locate(&source, &module.source_map, suggestion)?,
+                    ))
+                });
+                return Err(CheckError {
+                    message: format!("{}: {:?}", path.display(), error),
+                    fix,
+                });
+            }
+            exports.insert(path, type_checker.exports());
+            checked.push((path, type_checker));
+        }
+        Ok(checked)
+    }
+
+    /// Compiles every module to an ES module next to its source, JS modules
+    /// with typings are imported as they are.
+    pub fn compile(&self) -> Result<Vec<(PathBuf, String)>, String> {
+        let mut outputs = Vec::new();
+        for path in &self.order {
+            let module = &self.modules[path];
+            let ModuleSource::Lenniscript(program) = &module.source else {
+                continue;
+            };
+            let mut compiler = Compiler::new();
+            compiler.set_es_module(true);
+            for (source, import_path) in &module.imports {
+                match &self.modules[import_path].source {
+                    ModuleSource::Lenniscript(dependency) => {
+                        compiler.register_dependency(source, dependency)
+                    }
+                    ModuleSource::Declarations(declarations) => {
+                        compiler.register_external_dependency(source, declarations)
+                    }
+                }
+            }
+            let compiled = compiler
+                .compile_programm(program)
+                .map_err(|error| format!("{}: {}", path.display(), error))?;
+            let output_path = PathBuf::from(Compiler::output_path(&path.to_string_lossy()));
+            outputs.push((output_path, compiled));
+        }
+        Ok(outputs)
+    }
+}
@@ -1,15 +1,16 @@
 use crate::ast;
-use crate::ast::Expr::{Binary, CompoundAssignment};
-use crate::ast::Stmt::WhileStatement;
-use crate::ast::Type::{Boolean, Number};
-use crate::ast::{caseBranch, ElseIfBranch, Expr, Property, Stmt, Type};
-use crate::lexer;
+use crate::ast::ExprKind::CompoundAssignment;
+use crate::ast::{
+    CaseBranch, CatchClause, ClassMember, ElseIfBranch, Expr, ExprId, ExprKind, InterfaceMember,
+    Pattern, PatternElement, PatternProperty, Property, Stmt, Type,
+};
 use crate::lexer::TokenType::Not;
 use crate::lexer::{tokenize, Token, TokenType};
-use std::str::FromStr;
 
 pub struct Parser {
     tokens: Vec<Token>,
+    source_map: ast::SourceMap,
+    next_expr_id: u32,
 }
 /*
 - Assignment
@@ -23,9 +24,19 @@ pub struct Parser {
 - Primary
 */
 
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Parser {
     pub fn new() -> Self {
-        Self { tokens: Vec::new() }
+        Self {
+            tokens: Vec::new(),
+            source_map: ast::SourceMap::default(),
+            next_expr_id: 0,
+        }
     }
 
     fn not_eof(&self) -> bool {
@@ -36,6 +47,43 @@ impl Parser {
         }
     }
     fn get_type(&mut self) -> Type {
+        let first_type = self.get_single_type();
+        if self.at().token_type != TokenType::Pipe {
+            return first_type;
+        }
+
+        let mut members = vec![first_type];
+        while self.at().token_type == TokenType::Pipe {
+            self.eat();
+            members.push(self.get_single_type());
+        }
+        Type::Union(members)
+    }
+
+    fn get_single_type(&mut self) -> Type {
+        let literal_type = match self.at().token_type {
+            TokenType::_String => Some(Type::StringLiteral(self.eat().value)),
+            TokenType::_Number => Some(Type::NumberLiteral(
+                self.eat().value.parse::<f64>().unwrap(),
+            )),
+            TokenType::True => {
+                self.eat();
+                Some(Type::BooleanLiteral(true))
+            }
+            TokenType::False => {
+                self.eat();
+                Some(Type::BooleanLiteral(false))
+            }
+            TokenType::Null => {
+                self.eat();
+                Some(Type::Null)
+            }
+            _ => None,
+        };
+        if let Some(literal_type) = literal_type {
+            return literal_type;
+        }
+
         let type_token_string = self
             .expect_multiple(
                 vec![TokenType::TypeAnnotation, TokenType::Identifier],
@@ -45,45 +93,41 @@ impl Parser {
         let var_type = match type_token_string.as_str() {
             "array" => {
                 self.expect(TokenType::LessThen, "Expected less then after array ");
-                let type_array_string = self
-                    .expect(
-                        TokenType::TypeAnnotation,
-                        "You need to specify the array type",
-                    )
-                    .value;
-                match type_array_string.as_str() {
-                    "bool" => {
-                        self.expect(
-                            TokenType::GreaterThen,
-                            "greeater then after specifing array type needed",
-                        );
-                        Type::Array(Box::new(Boolean))
-                    }
-                    "num" => {
-                        self.expect(
-                            TokenType::GreaterThen,
-                            "greeater then after specifing array type needed",
-                        );
-                        Type::Array(Box::new(Number))
-                    }
-                    "string" => {
-                        self.expect(
-                            TokenType::GreaterThen,
-                            "greeater then after specifing array type needed",
-                        );
-                        Type::Array(Box::new(Type::String))
-                    }
-
-                    _ => {
-                        panic!("Error in get type array");
-                    }
-                }
+                let element_type = self.get_type();
+                self.expect(
+                    TokenType::GreaterThen,
+                    "greeater then after specifing array type needed",
+                );
+                Type::Array(Box::new(element_type))
             }
 
             "bool" => Type::Boolean,
             "num" => Type::Number,
             "string" => Type::String,
 
+            "map" => {
+                let mut type_arguments = self.parse_type_arguments(2);
+                let key_type = type_arguments.remove(0);
+                let value_type = type_arguments.remove(0);
+                Type::Map(Box::new(key_type), Box::new(value_type))
+            }
+            "set" => Type::Set(Box::new(self.parse_type_arguments(1).remove(0))),
+            "iterator" => Type::Iterator(Box::new(self.parse_type_arguments(1).remove(0))),
+
+            "Option" if self.at().token_type == TokenType::LessThen => {
+                let mut type_arguments = self.parse_type_arguments(1);
+                Type::Option(Box::new(type_arguments.remove(0)))
+            }
+            "Promise" if self.at().token_type == TokenType::LessThen => {
+                Type::Promise(Box::new(self.parse_type_arguments(1).remove(0)))
+            }
+            "Result" if self.at().token_type == TokenType::LessThen => {
+                let mut type_arguments = self.parse_type_arguments(2);
+                let value_type = type_arguments.remove(0);
+                let error_type = type_arguments.remove(0);
+                Type::Result(Box::new(value_type), Box::new(error_type))
+            }
+
             _ => Type::AliasedType(type_token_string),
         };
 
@@ -94,10 +138,51 @@ impl Parser {
             var_type
         }
     }
+    fn parse_type_arguments(&mut self, expected_count: usize) -> Vec<Type> {
+        self.expect(
+            TokenType::LessThen,
+            "Expected less then before type arguments",
+        );
+        let mut type_arguments = vec![self.get_type()];
+        while self.at().token_type == TokenType::Comma {
+            self.eat();
+            type_arguments.push(self.get_type());
+        }
+        self.expect(
+            TokenType::GreaterThen,
+            "greeater then after type arguments needed",
+        );
+
+        if type_arguments.len() != expected_count {
+            panic!(
+                "Erwartete {} Typargumente, gefunden {}",
+                expected_count,
+                type_arguments.len()
+            );
+        }
+        type_arguments
+    }
+
     fn at(&self) -> &Token {
         self.tokens.first().expect("Keine Tokens verfügbar")
     }
 
+    fn position(&self) -> ast::Position {
+        let token = self.at();
+        ast::Position {
+            line: token.line,
+            column: token.column,
+        }
+    }
+
+    // every expression gets an id of its own and remembers where it started
+    fn expr(&mut self, start: ast::Position, kind: ExprKind) -> Expr {
+        let id = ExprId(self.next_expr_id);
+        self.next_expr_id += 1;
+        self.source_map.record_expression(id, start);
+        Expr { id, kind }
+    }
+
     fn at_and_check(&self, expected: TokenType, err: &str) {
         let token = self.tokens[0].token_type;
 
@@ -111,7 +196,18 @@ impl Parser {
     }
 
     fn eat(&mut self) -> Token {
-        self.tokens.remove(0)
+        let token = self.tokens.remove(0);
+        // names are moved into the AST, their text keeps its address
+        if token.token_type == TokenType::Identifier {
+            self.source_map.record_name(
+                &token.value,
+                ast::Position {
+                    line: token.line,
+                    column: token.column,
+                },
+            );
+        }
+        token
     }
 
     fn expect(&mut self, expected: TokenType, err: &str) -> Token {
@@ -138,16 +234,39 @@ impl Parser {
         token
     }
 
-    pub fn produceAst(&mut self, source_code: &str) -> Stmt {
+    pub fn produce_ast(&mut self, source_code: &str) -> Stmt {
         self.tokens = tokenize(source_code);
+        self.source_map = ast::SourceMap::default();
         let mut body = Vec::new();
+        let mut lines = Vec::new();
         while self.not_eof() {
+            lines.push(self.at().line);
             body.push(self.parse_stmt());
         }
+        self.source_map.record_statements(&body, &lines);
 
         Stmt::Program { body }
     }
 
+    /// Lines of the statements and positions of the names in the last
+    /// parsed program.
+    pub fn into_source_map(self) -> ast::SourceMap {
+        self.source_map
+    }
+
+    // Statements up to the closing brace of a block, the brace is left for
+    // the caller.
+    fn parse_block_body(&mut self) -> Vec<Stmt> {
+        let mut body = Vec::new();
+        let mut lines = Vec::new();
+        while self.not_eof() && self.at().token_type != TokenType::CloseBrace {
+            lines.push(self.at().line);
+            body.push(self.parse_stmt());
+        }
+        self.source_map.record_statements(&body, &lines);
+        body
+    }
+
     fn parse_stmt(&mut self) -> Stmt {
         let tk = self.at().token_type;
 
@@ -164,16 +283,32 @@ impl Parser {
 
             TokenType::Fn | TokenType::Async => self.parse_fn_declaration(),
 
+            TokenType::Class => self.parse_class_declaration(),
+
+            TokenType::Interface => self.parse_interface_declaration(),
+
+            TokenType::Impl => self.parse_impl_declaration(),
+
+            TokenType::Import => self.parse_import_stmt(),
+
+            TokenType::Export => self.parse_export_stmt(),
+
+            TokenType::Declare => self.parse_declare_stmt(),
+
             TokenType::Break => self.parse_break_stmt(),
 
             TokenType::Continue => self.parse_continue_stmt(),
 
             TokenType::Try => self.parse_try_catch_stmt(),
 
+            TokenType::Throw => self.parse_throw_stmt(),
+
             TokenType::For => self.parse_for_statement(),
 
             TokenType::Switch => self.parse_switch_statement(),
 
+            TokenType::Hash => self.parse_attribute(),
+
             _ => {
                 let expr = self.parse_expr();
                 self.expect(TokenType::Semicolon, "Erwarte Semikolon nach Ausdruck");
@@ -182,6 +317,40 @@ impl Parser {
         }
     }
 
+    fn parse_attribute(&mut self) -> Stmt {
+        self.eat();
+        let applies_to_file = self.at().token_type == TokenType::Not;
+        if applies_to_file {
+            self.eat();
+        }
+        self.expect(TokenType::OpenBracket, "Erwarte '[' nach '#'");
+        let name = self
+            .expect(TokenType::Identifier, "Erwarte Attributnamen")
+            .value;
+
+        let mut arguments = Vec::new();
+        if self.at().token_type == TokenType::OpenParen {
+            self.eat();
+            while self.at().token_type != TokenType::CloseParen {
+                arguments.push(
+                    self.expect(TokenType::Identifier, "Erwarte Bezeichner im Attribut")
+                        .value,
+                );
+                if self.at().token_type != TokenType::CloseParen {
+                    self.expect(TokenType::Comma, "Erwarte ',' zwischen Attributargumenten");
+                }
+            }
+            self.eat();
+        }
+        self.expect(TokenType::CloseBracket, "Erwarte ']' am Ende des Attributs");
+
+        Stmt::Attribute {
+            name,
+            arguments,
+            applies_to_file,
+        }
+    }
+
     fn parse_return_statement(&mut self) -> Stmt {
         self.eat();
         let expr = self.parse_expr();
@@ -213,11 +382,7 @@ impl Parser {
         );
         self.expect(TokenType::OpenBrace, "Expected open brace after condition");
 
-        let mut body: Vec<Stmt> = Vec::new();
-        while self.at().token_type != TokenType::CloseBrace {
-            let stmt = self.parse_stmt();
-            body.push(stmt);
-        }
+        let body = self.parse_block_body();
         self.expect(TokenType::CloseBrace, "Expected Closing Brace after body");
 
         let mut else_if_branches: Vec<ElseIfBranch> = Vec::new();
@@ -236,11 +401,7 @@ impl Parser {
                 );
                 self.expect(TokenType::OpenBrace, "Expected open brace after condition");
 
-                let mut else_if_body: Vec<Stmt> = Vec::new();
-                while self.at().token_type != TokenType::CloseBrace {
-                    let stmt = self.parse_stmt();
-                    else_if_body.push(stmt);
-                }
+                let else_if_body = self.parse_block_body();
                 self.expect(
                     TokenType::CloseBrace,
                     "Expected Closing Brace after else-if body",
@@ -253,11 +414,7 @@ impl Parser {
                 else_if_branches.push(else_if);
             } else {
                 self.expect(TokenType::OpenBrace, "Expected open brace after else");
-                let mut else_body: Vec<Stmt> = Vec::new();
-                while self.at().token_type != TokenType::CloseBrace {
-                    let stmt = self.parse_stmt();
-                    else_body.push(stmt);
-                }
+                let else_body = self.parse_block_body();
                 self.expect(
                     TokenType::CloseBrace,
                     "Expected Closing Brace after else body",
@@ -294,11 +451,7 @@ impl Parser {
             "Expected Opening Brace after condition",
         );
 
-        let mut body: Vec<Stmt> = Vec::new();
-        while self.at().token_type != TokenType::CloseBrace {
-            let stmt: Stmt = self.parse_stmt();
-            body.push(stmt);
-        }
+        let body = self.parse_block_body();
 
         self.expect(
             TokenType::CloseBrace,
@@ -315,32 +468,29 @@ impl Parser {
     fn parse_var_declaration(&mut self) -> Stmt {
         let constant = self.at().token_type == TokenType::Const;
         self.eat();
-        let identifier = self
-            .expect(
-                TokenType::Identifier,
-                "Erwartete Bezeichner nach let/const Schlüsselwort",
-            )
-            .value;
+        let pattern = self.parse_pattern();
+
+        let mut var_type = Type::Any;
+        if self.at().token_type == TokenType::Colon {
+            self.eat();
+            var_type = self.get_type();
+        }
 
+        // the value may be assigned later, the checker makes sure it is
         if self.at().token_type == TokenType::Semicolon {
             self.eat();
 
-            if constant {
-                panic!("Konstanten müssen initialisiert werden");
+            if !matches!(pattern, Pattern::Identifier(_)) {
+                panic!("Destructuring-Deklarationen müssen initialisiert werden");
             }
 
             return Stmt::VarDeclaration {
                 constant,
-                identifier,
-                var_type: Type::Any,
+                pattern,
+                var_type,
                 value: None,
             };
         }
-        let mut var_type = Type::Any;
-        if self.at().token_type == TokenType::Colon {
-            self.eat();
-            var_type = self.get_type();
-        }
         self.expect(TokenType::Equals, "Erwartete '=' nach Bezeichner");
         let value = self.parse_expr();
         self.expect(
@@ -350,12 +500,118 @@ impl Parser {
 
         Stmt::VarDeclaration {
             constant,
-            identifier,
+            pattern,
             var_type,
             value: Some(value),
         }
     }
 
+    fn parse_pattern(&mut self) -> Pattern {
+        match self.at().token_type {
+            TokenType::OpenBracket => self.parse_array_pattern(),
+            TokenType::OpenBrace => self.parse_object_pattern(),
+            _ => Pattern::Identifier(
+                self.expect(
+                    TokenType::Identifier,
+                    "Erwartete Bezeichner oder Destructuring-Muster",
+                )
+                .value,
+            ),
+        }
+    }
+
+    fn parse_pattern_default(&mut self) -> Option<Expr> {
+        if self.at().token_type == TokenType::Equals {
+            self.eat();
+            Some(self.parse_expr())
+        } else {
+            None
+        }
+    }
+
+    fn parse_array_pattern(&mut self) -> Pattern {
+        self.eat();
+        let mut elements = Vec::new();
+        let mut rest = None;
+
+        while self.at().token_type != TokenType::CloseBracket {
+            if self.at().token_type == TokenType::DotDotDot {
+                self.eat();
+                rest = Some(Box::new(self.parse_pattern()));
+                break;
+            }
+
+            let pattern = self.parse_pattern();
+            let default_value = self.parse_pattern_default();
+            elements.push(PatternElement {
+                pattern,
+                default_value,
+            });
+
+            if self.at().token_type == TokenType::Comma {
+                self.eat();
+            } else {
+                break;
+            }
+        }
+
+        self.expect(
+            TokenType::CloseBracket,
+            "Expected closing bracket after array pattern",
+        );
+        Pattern::Array { elements, rest }
+    }
+
+    fn parse_object_pattern(&mut self) -> Pattern {
+        self.eat();
+        let mut properties = Vec::new();
+        let mut rest = None;
+
+        while self.at().token_type != TokenType::CloseBrace {
+            if self.at().token_type == TokenType::DotDotDot {
+                self.eat();
+                rest = Some(
+                    self.expect(TokenType::Identifier, "Identifier expected after ...")
+                        .value,
+                );
+                break;
+            }
+
+            let key = self
+                .expect(TokenType::Identifier, "Object pattern key expected")
+                .value;
+            let pattern = if self.at().token_type == TokenType::Colon {
+                self.eat();
+                self.parse_pattern()
+            } else {
+                // the binding is a copy of the key, it stands where the key does
+                let binding = key.clone();
+                if let Some(position) = self.source_map.name_position(key.as_ptr()) {
+                    self.source_map.record_name(&binding, position);
+                }
+                Pattern::Identifier(binding)
+            };
+            let default_value = self.parse_pattern_default();
+            properties.push(PatternProperty {
+                key,
+                pattern,
+                default_value,
+            });
+
+            if self.at().token_type == TokenType::Comma {
+                self.eat();
+            } else {
+                break;
+            }
+        }
+
+        self.expect(
+            TokenType::CloseBrace,
+            "Expected closing brace after object pattern",
+        );
+        Pattern::Object { properties, rest }
+    }
+
     fn parse_fn_declaration(&mut self) -> Stmt {
         let mut is_async = false;
         if self.at().token_type == TokenType::Async {
@@ -370,34 +626,23 @@ impl Parser {
             .expect(TokenType::Identifier, "name expected after fn keyword")
             .value;
 
-        let (args, arg_types) = self.parse_args();
-        let mut params: Vec<String> = Vec::new();
+        self.parse_fn_signature_and_body(name, is_async)
+    }
 
-        for arg in args {
-            if let Expr::Identifier(symbol) = arg {
-                params.push(symbol);
-            } else {
-                println!("{:?}", arg);
-                panic!("Inside function declaration expected parameters to be of type string.");
-            }
-        }
-        let return_type: Type;
-        if self.at().token_type == TokenType::Arrow {
+    fn parse_fn_signature_and_body(&mut self, name: String, is_async: bool) -> Stmt {
+        let (params, arg_types, param_defaults, has_rest_parameter) = self.parse_params();
+        let return_type = if self.at().token_type == TokenType::Arrow {
             self.eat();
-            return_type = self.get_type();
+            self.get_type()
         } else {
-            return_type = Type::Void;
-        }
+            Type::Void
+        };
 
         self.expect(
             TokenType::OpenBrace,
             "Expected function body following declaration",
         );
-        let mut body: Vec<Stmt> = Vec::new();
-
-        while self.at().token_type != TokenType::CloseBrace {
-            body.push(self.parse_stmt());
-        }
+        let body = self.parse_block_body();
 
         self.expect(
             TokenType::CloseBrace,
@@ -410,10 +655,359 @@ impl Parser {
             return_type,
             parameters: params,
             param_types: arg_types,
+            param_defaults,
+            has_rest_parameter,
             is_async,
         }
     }
 
+    fn parse_class_declaration(&mut self) -> Stmt {
+        self.eat(); // Eat the `class`
+        let name = self
+            .expect(TokenType::Identifier, "Klassenname nach 'class' erwartet")
+            .value;
+
+        let parent = if self.at().token_type == TokenType::Extends {
+            self.eat();
+            Some(
+                self.expect(TokenType::Identifier, "Klassenname nach 'extends' erwartet")
+                    .value,
+            )
+        } else {
+            None
+        };
+
+        self.expect(TokenType::OpenBrace, "'{' nach Klassenkopf erwartet");
+        let mut members = Vec::new();
+        while self.at().token_type != TokenType::CloseBrace {
+            members.push(self.parse_class_member());
+        }
+        self.expect(TokenType::CloseBrace, "'}' am Ende der Klasse erwartet");
+
+        Stmt::ClassDeclaration {
+            name,
+            parent,
+            members,
+        }
+    }
+
+    fn parse_class_member(&mut self) -> ClassMember {
+        let is_static = if self.at().token_type == TokenType::Static {
+            self.eat();
+            true
+        } else {
+            false
+        };
+
+        match self.at().token_type {
+            TokenType::Fn | TokenType::Async => ClassMember::Method {
+                declaration: self.parse_fn_declaration(),
+                is_static,
+            },
+            TokenType::Identifier if self.at().value == "constructor" => {
+                if is_static {
+                    panic!("Konstruktor kann nicht static sein");
+                }
+                self.eat();
+                ClassMember::Method {
+                    declaration: self.parse_fn_signature_and_body("constructor".to_string(), false),
+                    is_static,
+                }
+            }
+            TokenType::Identifier => {
+                let name = self.eat().value;
+                let mut field_type = Type::Any;
+                if self.at().token_type == TokenType::Colon {
+                    self.eat();
+                    field_type = self.get_type();
+                }
+                let value = if self.at().token_type == TokenType::Equals {
+                    self.eat();
+                    Some(self.parse_expr())
+                } else {
+                    None
+                };
+                self.expect(TokenType::Semicolon, "Feld muss mit Semikolon enden");
+                ClassMember::Field {
+                    name,
+                    field_type,
+                    value,
+                    is_static,
+                }
+            }
+            _ => panic!(
+                "Feld, Methode oder Konstruktor in Klasse erwartet, bekam {:?}",
+                self.at()
+            ),
+        }
+    }
+
+    fn parse_import_stmt(&mut self) -> Stmt {
+        self.eat(); // Eat the `import`
+        self.expect(TokenType::OpenBrace, "'{' nach 'import' erwartet");
+        let mut names = Vec::new();
+        while self.at().token_type != TokenType::CloseBrace {
+            names.push(
+                self.expect(TokenType::Identifier, "Name in Import-Liste erwartet")
+                    .value,
+            );
+            if self.at().token_type != TokenType::CloseBrace {
+                self.expect(TokenType::Comma, "',' zwischen Importen erwartet");
+            }
+        }
+        self.expect(
+            TokenType::CloseBrace,
+            "'}' am Ende der Import-Liste erwartet",
+        );
+
+        // `from` is no keyword, so `Array.from` keeps working
+        let from = self.expect(TokenType::Identifier, "'from' nach Import-Liste erwartet");
+        if from.value != "from" {
+            panic!("'from' nach Import-Liste erwartet, bekam {:?}", from);
+        }
+        let source = self
+            .expect(TokenType::_String, "Modulpfad nach 'from' erwartet")
+            .value;
+        self.expect(TokenType::Semicolon, "Import muss mit Semikolon enden");
+
+        Stmt::Import { names, source }
+    }
+
+    fn parse_export_stmt(&mut self) -> Stmt {
+        self.eat(); // Eat the `export`
+        let declaration = match self.at().token_type {
+            TokenType::Fn
+            | TokenType::Async
+            | TokenType::Class
+            | TokenType::Interface
+            | TokenType::TypeAlias
+            | TokenType::Const
+            | TokenType::Let => self.parse_stmt(),
+            _ => panic!(
+                "Nur Deklarationen können exportiert werden, bekam {:?}",
+                self.at()
+            ),
+        };
+
+        Stmt::Export {
+            declaration: Box::new(declaration),
+        }
+    }
+
+    fn parse_interface_declaration(&mut self) -> Stmt {
+        self.eat(); // Eat the `interface`
+        let name = self
+            .expect(TokenType::Identifier, "Name nach 'interface' erwartet")
+            .value;
+        let members = self.parse_interface_body();
+
+        Stmt::InterfaceDeclaration { name, members }
+    }
+
+    fn parse_interface_body(&mut self) -> Vec<InterfaceMember> {
+        self.expect(TokenType::OpenBrace, "'{' nach Interface-Name erwartet");
+
+        let mut members = Vec::new();
+        while self.at().token_type != TokenType::CloseBrace {
+            if self.at().token_type == TokenType::Fn {
+                self.eat();
+                let method_name = self
+                    .expect(TokenType::Identifier, "Methodenname nach 'fn' erwartet")
+                    .value;
+                members.push(InterfaceMember::Method {
+                    declaration: self.parse_fn_signature(method_name, false),
+                });
+            } else {
+                let field_name = self
+                    .expect(
+                        TokenType::Identifier,
+                        "Feld oder Methode in Interface erwartet",
+                    )
+                    .value;
+                self.expect(TokenType::Colon, "Felder in Interfaces brauchen einen Typ");
+                let field_type = self.get_type();
+                self.expect(TokenType::Semicolon, "Feld muss mit Semikolon enden");
+                members.push(InterfaceMember::Field {
+                    name: field_name,
+                    field_type,
+                });
+            }
+        }
+        self.expect(TokenType::CloseBrace, "'}' am Ende des Interfaces erwartet");
+
+        members
+    }
+
+    // Signature without body, ending with a semicolon
+    fn parse_fn_signature(&mut self, name: String, is_async: bool) -> Stmt {
+        let (parameters, param_types, param_defaults, has_rest_parameter) = self.parse_params();
+        let return_type = if self.at().token_type == TokenType::Arrow {
+            self.eat();
+            self.get_type()
+        } else {
+            Type::Void
+        };
+        self.expect(
+            TokenType::Semicolon,
+            "Funktionssignatur muss mit Semikolon enden",
+        );
+
+        Stmt::FunctionDeclaration {
+            name,
+            parameters,
+            param_types,
+            param_defaults,
+            has_rest_parameter,
+            return_type,
+            body: Vec::new(),
+            is_async,
+        }
+    }
+
+    fn parse_declare_stmt(&mut self) -> Stmt {
+        self.eat(); // Eat the `declare`
+        let declaration = match self.at().token_type {
+            TokenType::Fn | TokenType::Async => {
+                let is_async = self.eat().token_type == TokenType::Async;
+                if is_async {
+                    self.expect(TokenType::Fn, "fn expected after async keyword");
+                }
+                let name = self
+                    .expect(TokenType::Identifier, "name expected after fn keyword")
+                    .value;
+                self.parse_fn_signature(name, is_async)
+            }
+            TokenType::Const => {
+                self.eat();
+                let name = self
+                    .expect(TokenType::Identifier, "Name nach 'declare const' erwartet")
+                    .value;
+                self.expect(TokenType::Colon, "'declare const' braucht einen Typ");
+                let var_type = self.get_type();
+                self.expect(TokenType::Semicolon, "Deklaration muss mit Semikolon enden");
+                Stmt::VarDeclaration {
+                    constant: true,
+                    pattern: Pattern::Identifier(name),
+                    value: None,
+                    var_type,
+                }
+            }
+            // `object` is no keyword, it only has a meaning after `declare`
+            TokenType::Identifier if self.at().value == "object" => {
+                self.eat();
+                let name = self
+                    .expect(TokenType::Identifier, "Name nach 'declare object' erwartet")
+                    .value;
+                let members = self.parse_interface_body();
+                Stmt::InterfaceDeclaration { name, members }
+            }
+            _ => panic!(
+                "'fn', 'const' oder 'object' nach 'declare' erwartet, bekam {:?}",
+                self.at()
+            ),
+        };
+
+        Stmt::Declare {
+            declaration: Box::new(declaration),
+        }
+    }
+
+    fn parse_impl_declaration(&mut self) -> Stmt {
+        self.eat(); // Eat the `impl`
+        let interface = self
+            .expect(TokenType::Identifier, "Interface-Name nach 'impl' erwartet")
+            .value;
+        self.expect(TokenType::For, "'for' nach Interface-Name erwartet");
+        let target = self
+            .expect(TokenType::Identifier, "Klassenname nach 'for' erwartet")
+            .value;
+
+        let mut methods = Vec::new();
+        if self.at().token_type == TokenType::Semicolon {
+            self.eat();
+        } else {
+            self.expect(TokenType::OpenBrace, "'{' oder ';' nach impl erwartet");
+            while self.at().token_type != TokenType::CloseBrace {
+                match self.at().token_type {
+                    TokenType::Fn | TokenType::Async => methods.push(self.parse_fn_declaration()),
+                    _ => panic!("Nur Methoden sind in impl-Blöcken erlaubt"),
+                }
+            }
+            self.expect(
+                TokenType::CloseBrace,
+                "'}' am Ende des impl-Blocks erwartet",
+            );
+        }
+
+        Stmt::ImplDeclaration {
+            interface,
+            target,
+            methods,
+        }
+    }
+
+    fn parse_params(&mut self) -> (Vec<Pattern>, Vec<Type>, Vec<Option<Expr>>, bool) {
+        self.expect(TokenType::OpenParen, "Expected open parenthesis");
+        let mut params = Vec::new();
+        let mut param_types = Vec::new();
+        let mut param_defaults = Vec::new();
+        let mut has_rest_parameter = false;
+
+        while self.at().token_type != TokenType::CloseParen {
+            if self.at().token_type == TokenType::DotDotDot {
+                self.eat();
+                has_rest_parameter = true;
+            }
+            params.push(self.parse_pattern());
+
+            let optional = self.at().token_type == TokenType::Question;
+            if optional {
+                self.eat();
+            }
+            let mut param_type = Type::Any;
+            if self.at().token_type == TokenType::Colon {
+                self.eat();
+                param_type = self.get_type();
+            }
+
+            // `port?: num` is sugar for `port: num? = null`
+            let default_value = if optional {
+                if !matches!(param_type, Type::Option(_) | Type::Any) {
+                    param_type = Type::Option(Box::new(param_type));
+                }
+                let start = self.position();
+                Some(self.expr(start, ExprKind::NullLiteral))
+            } else if self.at().token_type == TokenType::Equals {
+                self.eat();
+                Some(self.parse_expr())
+            } else {
+                None
+            };
+            if has_rest_parameter && default_value.is_some() {
+                panic!("Rest-Parameter können weder optional sein noch einen Default-Wert haben");
+            }
+            param_types.push(param_type);
+            param_defaults.push(default_value);
+
+            if has_rest_parameter {
+                break;
+            }
+            if self.at().token_type == TokenType::Comma {
+                self.eat();
+            } else {
+                break;
+            }
+        }
+
+        let message = if has_rest_parameter {
+            "Rest parameter must be the last parameter"
+        } else {
+            "Missing closing parenthesis inside parameter list"
+        };
+        self.expect(TokenType::CloseParen, message);
+        (params, param_types, param_defaults, has_rest_parameter)
+    }
+
     fn parse_break_stmt(&mut self) -> Stmt {
         self.eat();
         self.expect(TokenType::Semicolon, "Erwarte Semikolon nach Ausdruck");
@@ -426,41 +1020,69 @@ impl Parser {
         Stmt::ContinueStatement
     }
 
+    fn parse_throw_stmt(&mut self) -> Stmt {
+        self.eat();
+        let value = self.parse_expr();
+        self.expect(TokenType::Semicolon, "Erwarte Semikolon nach Ausdruck");
+        Stmt::ThrowStatement { value }
+    }
+
     fn parse_try_catch_stmt(&mut self) -> Stmt {
         self.eat();
         self.expect(TokenType::OpenBrace, "Open Brace Expected after try");
 
-        let mut try_branch = Vec::new();
-        while self.not_eof() && self.at().token_type != TokenType::CloseBrace {
-            try_branch.push(self.parse_stmt());
-        }
+        let try_branch = self.parse_block_body();
         self.expect(
             TokenType::CloseBrace,
             "CLosing Brace after try body Expected",
         );
 
-        self.expect(TokenType::Catch, "Catch stmt expected after try stmt");
-        self.expect(TokenType::OpenBrace, "Open Brace Expected after catch");
+        self.at_and_check(TokenType::Catch, "Catch stmt expected after try stmt");
+        let mut catch_clauses = Vec::new();
+        while self.at().token_type == TokenType::Catch {
+            self.eat();
 
-        let mut catch_branch = Vec::new();
-        while self.not_eof() && self.at().token_type != TokenType::CloseBrace {
-            catch_branch.push(self.parse_stmt());
+            let mut binding = None;
+            let mut error_type = None;
+            if self.at().token_type == TokenType::OpenParen {
+                self.eat();
+                binding = Some(
+                    self.expect(
+                        TokenType::Identifier,
+                        "Identifier expected in catch binding",
+                    )
+                    .value,
+                );
+                if self.at().token_type == TokenType::Colon {
+                    self.eat();
+                    error_type = Some(self.get_type());
+                }
+                self.expect(
+                    TokenType::CloseParen,
+                    "Closing Paren expected after catch binding",
+                );
+            }
+
+            self.expect(TokenType::OpenBrace, "Open Brace Expected after catch");
+            let body = self.parse_block_body();
+            self.expect(
+                TokenType::CloseBrace,
+                "Closing Brace after catch body Expected",
+            );
+
+            catch_clauses.push(CatchClause {
+                binding,
+                error_type,
+                body,
+            });
         }
-        self.expect(
-            TokenType::CloseBrace,
-            "Closing Brace after catch body Expected",
-        );
 
         let mut finally_branch: Option<Vec<Stmt>> = None;
 
         if self.at().token_type == TokenType::Finally {
             self.eat();
-            let mut finally_body = Vec::new();
             self.expect(TokenType::OpenBrace, "Open Brace Expected after finally");
-
-            while self.not_eof() && self.at().token_type != TokenType::CloseBrace {
-                finally_body.push(self.parse_stmt());
-            }
+            let finally_body = self.parse_block_body();
             self.expect(
                 TokenType::CloseBrace,
                 "CLosing Brace after finally body Expected",
@@ -471,7 +1093,7 @@ impl Parser {
 
         Stmt::TryCatchFinally {
             try_branch,
-            catch_branch,
+            catch_clauses,
             finally_branch,
         }
     }
@@ -516,17 +1138,12 @@ impl Parser {
                     let constant = self.at().token_type == TokenType::Const;
                     self.eat();
 
-                    let identifier = self
-                        .expect(
-                            TokenType::Identifier,
-                            "Erwartete Bezeichner nach let/const Schlüsselwort",
-                        )
-                        .value;
+                    let pattern = self.parse_pattern();
 
                     if self.at().token_type == TokenType::In {
                         Some(Box::new(Stmt::VarDeclaration {
                             constant,
-                            identifier,
+                            pattern,
                             var_type: Type::Any,
                             value: None,
                         }))
@@ -544,7 +1161,7 @@ impl Parser {
                         );
                         Some(Box::new(Stmt::VarDeclaration {
                             constant,
-                            identifier,
+                            pattern,
                             var_type,
                             value: Some(value),
                         }))
@@ -580,14 +1197,14 @@ impl Parser {
 
         let body = if self.at().token_type == TokenType::OpenBrace {
             self.eat();
-            let mut statements = Vec::new();
-            while self.not_eof() && self.at().token_type != TokenType::CloseBrace {
-                statements.push(self.parse_stmt());
-            }
+            let statements = self.parse_block_body();
             self.expect(TokenType::CloseBrace, "Expected '}' after for loop body");
             statements
         } else {
-            vec![self.parse_stmt()]
+            let line = self.at().line;
+            let statements = vec![self.parse_stmt()];
+            self.source_map.record_statements(&statements, &[line]);
+            statements
         };
 
         if iterable.is_some() {
@@ -643,13 +1260,10 @@ impl Parser {
                     TokenType::OpenBrace,
                     "open brace after case condition expected",
                 );
-                let mut case_body = Vec::new();
-                while self.not_eof() && self.at().token_type != TokenType::CloseBrace {
-                    case_body.push(self.parse_stmt());
-                }
+                let case_body = self.parse_block_body();
                 self.expect(TokenType::CloseBrace, "Closing brace after case body");
 
-                cases.push(caseBranch {
+                cases.push(CaseBranch {
                     condition: case_condition,
                     body: case_body,
                 })
@@ -663,10 +1277,7 @@ impl Parser {
                     TokenType::OpenBrace,
                     "open brace after switch arrow expected",
                 );
-                let mut default_body = Vec::new();
-                while self.not_eof() && self.at().token_type != TokenType::CloseBrace {
-                    default_body.push(self.parse_stmt());
-                }
+                let default_body = self.parse_block_body();
                 self.expect(TokenType::CloseBrace, "Closing brace after case body");
 
                 default_branch = Some(default_body);
@@ -684,22 +1295,26 @@ impl Parser {
         Stmt::SwitchStatement {
             condition,
             case_branches: cases,
-            default_branch: default_branch.expect("default branch needed"),
+            default_branch,
         }
     }
 
     fn parse_assignment_expr(&mut self) -> Expr {
+        let start = self.position();
         let mut left = self.parse_object_expr();
 
-        if (self.at().token_type == TokenType::Equals) {
-            match &left {
-                Expr::Identifier(_) | Expr::Member { .. } => {
+        if self.at().token_type == TokenType::Equals {
+            match &left.kind {
+                ExprKind::Identifier(_) | ExprKind::Member { .. } => {
                     self.eat();
                     let value = self.parse_assignment_expr();
-                    left = Expr::Assignment {
-                        assignee: Box::new(left),
-                        value: Box::new(value),
-                    }
+                    left = self.expr(
+                        start,
+                        ExprKind::Assignment {
+                            assignee: Box::new(left),
+                            value: Box::new(value),
+                        },
+                    )
                 }
                 _ => panic!("Invalid left-hand side in assignment expression"),
             }
@@ -711,14 +1326,17 @@ impl Parser {
                 || operator == "%="
                 || operator == "/="
             {
-                match &left {
-                    Expr::Identifier(_) | Expr::Member { .. } => {
+                match &left.kind {
+                    ExprKind::Identifier(_) | ExprKind::Member { .. } => {
                         let value = self.parse_expr();
-                        left = CompoundAssignment {
-                            assignee: Box::new(left),
-                            value: Box::new(value),
-                            operator,
-                        }
+                        left = self.expr(
+                            start,
+                            CompoundAssignment {
+                                assignee: Box::new(left),
+                                value: Box::new(value),
+                                operator,
+                            },
+                        )
                     }
 
                     _ => panic!("Invalid left-hand side in assignment expression"),
@@ -734,12 +1352,13 @@ impl Parser {
             return self.parse_logic_expr();
         }
 
+        let start = self.position();
         self.eat();
         let mut properties: Vec<Property> = Vec::new();
 
         if self.at().token_type == TokenType::CloseBrace {
             self.eat();
-            return Expr::ObjectLiteral(properties);
+            return self.expr(start, ExprKind::ObjectLiteral(properties));
         }
 
         loop {
@@ -747,6 +1366,17 @@ impl Parser {
                 break;
             }
 
+            if self.at().token_type == TokenType::DotDotDot {
+                properties.push(Property {
+                    key: String::new(),
+                    value: Some(self.parse_spread_or_expr()),
+                });
+                if self.at().token_type == TokenType::Comma {
+                    self.eat();
+                }
+                continue;
+            }
+
             let key = self
                 .expect(TokenType::Identifier, "Object literal key expected")
                 .value;
@@ -785,25 +1415,30 @@ impl Parser {
             TokenType::CloseBrace,
             "Object literal missing closing brace.",
         );
-        Expr::ObjectLiteral(properties)
+        self.expr(start, ExprKind::ObjectLiteral(properties))
     }
     fn parse_logic_expr(&mut self) -> Expr {
+        let start = self.position();
         let mut left = self.parse_comparison_expr();
 
         while self.at().value == "&&" || self.at().value == "||" {
             let operator = self.eat().value;
             let right = self.parse_comparison_expr();
 
-            left = Expr::Binary {
-                left: Box::new(left),
-                right: Box::new(right),
-                operator,
-            }
+            left = self.expr(
+                start,
+                ExprKind::Binary {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    operator,
+                },
+            )
         }
 
         left
     }
     fn parse_comparison_expr(&mut self) -> Expr {
+        let start = self.position();
         let mut left = self.parse_additive_expr();
 
         while matches!(
@@ -817,44 +1452,61 @@ impl Parser {
         ) {
             let operator = self.eat().value;
             let right = self.parse_additive_expr();
-            left = Expr::Binary {
-                left: Box::new(left),
-                right: Box::new(right),
-                operator,
-            };
+            left = self.expr(
+                start,
+                ExprKind::Binary {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    operator,
+                },
+            );
         }
 
         left
     }
 
     fn parse_call_member_expr(&mut self) -> Expr {
-        let member = self.parse_member_expr();
+        let start = self.position();
+        let mut member = self.parse_member_expr();
 
         if self.at().token_type == TokenType::OpenParen {
-            return self.parse_call_expr(member);
+            member = self.parse_call_expr(start, member);
+        }
+
+        while self.at().token_type == TokenType::Question {
+            self.eat();
+            member = self.expr(
+                start,
+                ExprKind::Propagate {
+                    value: Box::new(member),
+                },
+            );
         }
 
         member
     }
 
-    fn parse_call_expr(&mut self, caller: Expr) -> Expr {
-        let (args, _) = self.parse_args();
-        let mut call_expr = Expr::Call {
-            caller: Box::new(caller),
-            args,
-        };
+    fn parse_call_expr(&mut self, start: ast::Position, caller: Expr) -> Expr {
+        let args = self.parse_args();
+        let mut call_expr = self.expr(
+            start,
+            ExprKind::Call {
+                caller: Box::new(caller),
+                args,
+            },
+        );
 
         if self.at().token_type == TokenType::OpenParen {
-            call_expr = self.parse_call_expr(call_expr);
+            call_expr = self.parse_call_expr(start, call_expr);
         }
 
         call_expr
     }
 
-    fn parse_args(&mut self) -> (Vec<Expr>, Vec<Type>) {
+    fn parse_args(&mut self) -> Vec<Expr> {
         self.expect(TokenType::OpenParen, "Expected open parenthesis");
-        let result = if self.at().token_type == TokenType::CloseParen {
-            (Vec::new(), Vec::new())
+        let args = if self.at().token_type == TokenType::CloseParen {
+            Vec::new()
         } else {
             self.parse_arguments_list()
         };
@@ -864,37 +1516,58 @@ impl Parser {
             "Missing closing parenthesis inside arguments list",
         );
 
-        result
+        args
     }
 
-    fn parse_arguments_list(&mut self) -> (Vec<Expr>, Vec<Type>) {
-        let mut args: Vec<Expr> = vec![self.parse_assignment_expr()];
-        let mut args_types: Vec<Type> = vec![Type::Any];
-
-        if self.at().token_type == TokenType::Colon {
+    fn parse_spread_or_expr(&mut self) -> Expr {
+        if self.at().token_type == TokenType::DotDotDot {
+            let start = self.position();
             self.eat();
-            let param_type = self.get_type();
+            let value = self.parse_expr();
+            self.expr(
+                start,
+                ExprKind::Spread {
+                    value: Box::new(value),
+                },
+            )
+        } else {
+            self.parse_expr()
+        }
+    }
 
-            args_types[0] = param_type;
+    fn parse_argument(&mut self) -> Expr {
+        let is_named = self.at().token_type == TokenType::Identifier
+            && self.tokens.get(1).map(|token| token.token_type) == Some(TokenType::Colon);
+        if is_named {
+            let start = self.position();
+            let name = self.eat().value;
+            self.eat();
+            let value = self.parse_expr();
+            return self.expr(
+                start,
+                ExprKind::NamedArgument {
+                    name,
+                    value: Box::new(value),
+                },
+            );
         }
 
+        self.parse_spread_or_expr()
+    }
+
+    fn parse_arguments_list(&mut self) -> Vec<Expr> {
+        let mut args: Vec<Expr> = vec![self.parse_argument()];
+
         while self.at().token_type == TokenType::Comma {
             self.eat();
-            args.push(self.parse_assignment_expr());
-            if self.at().token_type == TokenType::Colon {
-                self.eat();
-                let param_type = self.get_type();
-
-                args_types.push(param_type);
-            } else {
-                args_types.push(Type::Any);
-            }
+            args.push(self.parse_argument());
         }
 
-        (args, args_types)
+        args
     }
 
     fn parse_member_expr(&mut self) -> Expr {
+        let start = self.position();
         let mut object = self.parse_unary_expr();
 
         while self.at().token_type == TokenType::Dot
@@ -905,13 +1578,25 @@ impl Parser {
             let computed: bool;
             if operator.token_type == TokenType::Dot {
                 computed = false;
-                property = self.parse_primary_expr();
-                if let Expr::Identifier(value) = &property {
-                    object = Expr::Member {
-                        object: Box::new(object),
-                        property: Box::new(property),
-                        computed,
-                    }
+                // keywords are fine as property names, e.g. `promise.catch`
+                let is_keyword = self.at().token_type != TokenType::Identifier
+                    && self.at().value.chars().all(|c| c.is_alphabetic());
+                property = if is_keyword {
+                    let property_start = self.position();
+                    let name = self.eat().value;
+                    self.expr(property_start, ExprKind::Identifier(name))
+                } else {
+                    self.parse_primary_expr()
+                };
+                if let ExprKind::Identifier(_) = &property.kind {
+                    object = self.expr(
+                        start,
+                        ExprKind::Member {
+                            object: Box::new(object),
+                            property: Box::new(property),
+                            computed,
+                        },
+                    )
                 } else {
                     panic!("Cannonot use dot operator without right hand side being a identifier")
                 }
@@ -922,11 +1607,14 @@ impl Parser {
                     TokenType::CloseBracket,
                     "Missing closing bracket in computed value.",
                 );
-                object = Expr::Member {
-                    object: Box::new(object),
-                    property: Box::new(property),
-                    computed,
-                }
+                object = self.expr(
+                    start,
+                    ExprKind::Member {
+                        object: Box::new(object),
+                        property: Box::new(property),
+                        computed,
+                    },
+                )
             }
         }
 
@@ -935,71 +1623,85 @@ impl Parser {
 
     fn parse_unary_expr(&mut self) -> Expr {
         if self.at().token_type == Not {
+            let start = self.position();
             let operator = self.eat().value;
             let value = self.parse_unary_expr();
-            return Expr::Unary {
-                operator,
-                value: Box::new(value),
-            };
+            return self.expr(
+                start,
+                ExprKind::Unary {
+                    operator,
+                    value: Box::new(value),
+                },
+            );
         }
 
         self.parse_primary_expr()
     }
 
     fn parse_additive_expr(&mut self) -> Expr {
+        let start = self.position();
         let mut left = self.parse_multiplicative_expr();
 
         while ["+", "-"].contains(&self.at().value.as_str()) {
             let operator = self.eat().value;
             let right = self.parse_multiplicative_expr();
-            left = Expr::Binary {
-                left: Box::new(left),
-                right: Box::new(right),
-                operator,
-            }
+            left = self.expr(
+                start,
+                ExprKind::Binary {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    operator,
+                },
+            )
         }
 
         left
     }
 
     fn parse_multiplicative_expr(&mut self) -> Expr {
+        let start = self.position();
         let mut left = self.parse_call_member_expr();
 
         while ["*", "/", "%"].contains(&self.at().value.as_str()) {
             let operator = self.eat().value;
             let right = self.parse_call_member_expr();
-            left = Expr::Binary {
-                left: Box::new(left),
-                right: Box::new(right),
-                operator,
-            }
+            left = self.expr(
+                start,
+                ExprKind::Binary {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    operator,
+                },
+            )
         }
 
         left
     }
     fn parse_primary_expr(&mut self) -> Expr {
         let tk = self.at().token_type;
+        let start = self.position();
 
         match tk {
             TokenType::_Number => {
                 let token = self.eat();
                 let value = token.value.parse::<f64>().unwrap();
-                Expr::NumericLiteral(value)
+                self.expr(start, ExprKind::NumericLiteral(value))
             }
             TokenType::True => {
-                let token = self.eat();
-                Expr::BooleanLiteral(true)
+                self.eat();
+                self.expr(start, ExprKind::BooleanLiteral(true))
             }
             TokenType::False => {
-                let token = self.eat();
-                Expr::BooleanLiteral(false)
+                self.eat();
+                self.expr(start, ExprKind::BooleanLiteral(false))
             }
             TokenType::Null => {
-                let token = self.eat();
-                Expr::NullLiteral
+                self.eat();
+                self.expr(start, ExprKind::NullLiteral)
             }
             TokenType::Increment => {
                 self.eat();
+                let identifier_start = self.position();
                 let identifier = self
                     .expect(
                         TokenType::Identifier,
@@ -1007,22 +1709,30 @@ impl Parser {
                     )
                     .value;
 
-                Expr::Increment {
-                    identifier: Box::new(Expr::Identifier(identifier)),
-                    prefix: true,
-                }
+                let identifier = self.expr(identifier_start, ExprKind::Identifier(identifier));
+                self.expr(
+                    start,
+                    ExprKind::Increment {
+                        identifier: Box::new(identifier),
+                        prefix: true,
+                    },
+                )
             }
             TokenType::Identifier => {
                 let token = self.eat(); // Consumes 'i'
                 let name = token.value;
+                let identifier = self.expr(start, ExprKind::Identifier(name));
                 if self.at().token_type == TokenType::Increment {
                     self.eat();
-                    Expr::Increment {
-                        identifier: Box::new(Expr::Identifier(name)),
-                        prefix: false,
-                    }
+                    self.expr(
+                        start,
+                        ExprKind::Increment {
+                            identifier: Box::new(identifier),
+                            prefix: false,
+                        },
+                    )
                 } else {
-                    Expr::Identifier(name)
+                    identifier
                 }
             }
             TokenType::OpenBracket => {
@@ -1030,29 +1740,32 @@ impl Parser {
                 let mut elements: Vec<Expr> = Vec::new();
                 if self.at().token_type == TokenType::CloseBracket {
                     self.eat();
-                    return Expr::ArrayLiteral(elements);
+                    return self.expr(start, ExprKind::ArrayLiteral(elements));
                 }
 
-                elements.push(self.parse_expr());
+                elements.push(self.parse_spread_or_expr());
 
                 while self.at().token_type == TokenType::Comma {
                     self.eat();
-                    elements.push(self.parse_expr());
+                    elements.push(self.parse_spread_or_expr());
                 }
 
                 self.expect(
                     TokenType::CloseBracket,
                     "Expected closing bracket ']' for array literal",
                 );
-                Expr::ArrayLiteral(elements)
+                self.expr(start, ExprKind::ArrayLiteral(elements))
             }
 
             TokenType::Await => {
                 self.eat();
                 let value = self.parse_expr();
-                Expr::AwaitExpression {
-                    value: Box::new(value),
-                }
+                self.expr(
+                    start,
+                    ExprKind::AwaitExpression {
+                        value: Box::new(value),
+                    },
+                )
             }
 
             TokenType::OpenParen => {
@@ -1064,7 +1777,7 @@ impl Parser {
             TokenType::_String => {
                 let token = self.eat();
                 let value = token.value;
-                Expr::StringLiteral(value)
+                self.expr(start, ExprKind::StringLiteral(value))
             }
             _ => {
                 panic!(
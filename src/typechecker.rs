@@ -1,39 +1,641 @@
-use crate::ast::{Expr, Stmt, Type};
-use crate::js_stdlib::JsStdLib;
-use std::collections::HashMap;
-use std::fmt::format;
-use std::ptr::null;
+use crate::ast::{
+    ClassMember, Expr, ExprId, ExprKind, InterfaceMember, Pattern, Position, SourceMap, Stmt, Type,
+};
+use crate::js_stdlib::{self, JsStdLib, MethodSignature};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 #[derive(Debug, Clone)]
 pub struct VarInfo {
     var_type: Type,
     is_const: bool,
+    assignment: Assignment,
+}
+
+/// Whether a variable declared without a value has been assigned on every
+/// path that leads to the current statement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Assignment {
+    Assigned,
+    Unassigned,
+    Maybe,
+}
+
+impl Assignment {
+    fn merge(self, other: Assignment) -> Assignment {
+        if self == other {
+            self
+        } else {
+            Assignment::Maybe
+        }
+    }
 }
 
 impl VarInfo {
     pub fn new(var_type: Type, is_const: bool) -> VarInfo {
-        return VarInfo { var_type, is_const };
+        VarInfo {
+            var_type,
+            is_const,
+            assignment: Assignment::Assigned,
+        }
+    }
+
+    fn unassigned(var_type: Type, is_const: bool) -> VarInfo {
+        VarInfo {
+            var_type,
+            is_const,
+            assignment: Assignment::Unassigned,
+        }
+    }
+}
+
+/// Assignment state of the not yet definitely assigned variables, by scope
+/// index and name.
+type AssignmentStates = Vec<(usize, String, Assignment)>;
+
+/// Finds a member of some type by name.
+type MemberLookup<'a, T> = Box<dyn Fn(&str) -> Option<T> + 'a>;
+
+#[derive(Debug, Clone)]
+pub struct FunctionSignature {
+    param_names: Vec<Option<String>>,
+    param_types: Vec<Type>,
+    optional_params: Vec<bool>,
+    rest_type: Option<Type>,
+}
+
+impl FunctionSignature {
+    pub fn new(
+        param_names: Vec<Option<String>>,
+        param_types: Vec<Type>,
+        optional_params: Vec<bool>,
+        rest_type: Option<Type>,
+    ) -> FunctionSignature {
+        FunctionSignature {
+            param_names,
+            param_types,
+            optional_params,
+            rest_type,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MethodInfo {
+    signature: FunctionSignature,
+    return_type: Type,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClassInfo {
+    parent: Option<String>,
+    constructor: FunctionSignature,
+    fields: HashMap<String, Type>,
+    methods: HashMap<String, MethodInfo>,
+    static_fields: HashMap<String, Type>,
+    static_methods: HashMap<String, MethodInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InterfaceInfo {
+    fields: HashMap<String, Type>,
+    methods: HashMap<String, MethodInfo>,
+    // fields a conforming value may leave out, like `label?: string`
+    optional_fields: HashSet<String>,
+}
+
+/// Everything a module exports, with type aliases already resolved and
+/// classes flattened so importers don't need the non-exported parts.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleExports {
+    variables: HashMap<String, VarInfo>,
+    function_signatures: HashMap<String, FunctionSignature>,
+    function_return_types: HashMap<String, Type>,
+    type_aliases: HashMap<String, Type>,
+    classes: HashMap<String, ClassInfo>,
+    interfaces: HashMap<String, InterfaceInfo>,
+}
+
+impl ModuleExports {
+    pub fn add_variable(&mut self, name: &str, var_type: Type) {
+        self.variables
+            .insert(name.to_string(), VarInfo::new(var_type, true));
+    }
+
+    pub fn add_function(&mut self, name: &str, signature: FunctionSignature, return_type: Type) {
+        self.function_signatures.insert(name.to_string(), signature);
+        self.function_return_types
+            .insert(name.to_string(), return_type);
+    }
+
+    pub fn add_type_alias(&mut self, name: &str, aliased_type: Type) {
+        self.type_aliases.insert(name.to_string(), aliased_type);
+    }
+
+    pub fn add_interface(
+        &mut self,
+        name: &str,
+        fields: HashMap<String, Type>,
+        methods: HashMap<String, (FunctionSignature, Type)>,
+        optional_fields: HashSet<String>,
+    ) {
+        let methods: HashMap<String, MethodInfo> = methods
+            .into_iter()
+            .map(|(method_name, (signature, return_type))| {
+                (
+                    method_name,
+                    MethodInfo {
+                        signature,
+                        return_type,
+                    },
+                )
+            })
+            .collect();
+        // a second interface with the same name adds its members
+        let interface = self
+            .interfaces
+            .entry(name.to_string())
+            .or_insert_with(|| InterfaceInfo {
+                fields: HashMap::new(),
+                methods: HashMap::new(),
+                optional_fields: HashSet::new(),
+            });
+        interface.fields.extend(fields);
+        interface.methods.extend(methods);
+        interface.optional_fields.extend(optional_fields);
+    }
+
+    /// Names that only exist for the typechecker and have no JS value.
+    pub fn type_names(&self) -> Vec<String> {
+        self.type_aliases
+            .keys()
+            .chain(self.interfaces.keys())
+            .cloned()
+            .collect()
+    }
+
+    pub fn function_param_names(&self) -> HashMap<String, Vec<Option<String>>> {
+        self.function_signatures
+            .iter()
+            .map(|(name, signature)| (name.clone(), signature.param_names.clone()))
+            .collect()
+    }
+
+    fn has_type(&self, name: &str) -> bool {
+        self.type_aliases.contains_key(name)
+            || self.interfaces.contains_key(name)
+            || self.classes.contains_key(name)
+    }
+
+    // Types not imported by name get a key no declaration of the importer
+    // can take, like `import("./shapes.ls").Circle`.
+    fn qualified(&self, source: &str, imported_names: &[String]) -> ModuleExports {
+        let renames: HashMap<String, String> = self
+            .type_aliases
+            .keys()
+            .chain(self.interfaces.keys())
+            .chain(self.classes.keys())
+            .filter(|name| !imported_names.contains(name))
+            .map(|name| (name.clone(), format!("import(\"{}\").{}", source, name)))
+            .collect();
+        let rename_key = |name: &String| renames.get(name).unwrap_or(name).clone();
+
+        ModuleExports {
+            variables: self
+                .variables
+                .iter()
+                .map(|(name, var_info)| {
+                    let var_type = Self::rename_type(&var_info.var_type, &renames);
+                    (name.clone(), VarInfo::new(var_type, var_info.is_const))
+                })
+                .collect(),
+            function_signatures: self
+                .function_signatures
+                .iter()
+                .map(|(name, signature)| {
+                    (name.clone(), Self::rename_signature(signature, &renames))
+                })
+                .collect(),
+            function_return_types: Self::rename_fields(&self.function_return_types, &renames),
+            type_aliases: self
+                .type_aliases
+                .iter()
+                .map(|(name, aliased_type)| {
+                    (rename_key(name), Self::rename_type(aliased_type, &renames))
+                })
+                .collect(),
+            classes: self
+                .classes
+                .iter()
+                .map(|(name, class_info)| {
+                    let class_info = ClassInfo {
+                        parent: class_info.parent.as_ref().map(rename_key),
+                        constructor: Self::rename_signature(&class_info.constructor, &renames),
+                        fields: Self::rename_fields(&class_info.fields, &renames),
+                        methods: Self::rename_methods(&class_info.methods, &renames),
+                        static_fields: Self::rename_fields(&class_info.static_fields, &renames),
+                        static_methods: Self::rename_methods(&class_info.static_methods, &renames),
+                    };
+                    (rename_key(name), class_info)
+                })
+                .collect(),
+            interfaces: self
+                .interfaces
+                .iter()
+                .map(|(name, interface)| {
+                    let interface = InterfaceInfo {
+                        fields: Self::rename_fields(&interface.fields, &renames),
+                        methods: Self::rename_methods(&interface.methods, &renames),
+                        optional_fields: interface.optional_fields.clone(),
+                    };
+                    (rename_key(name), interface)
+                })
+                .collect(),
+        }
+    }
+
+    fn rename_type(checked_type: &Type, renames: &HashMap<String, String>) -> Type {
+        let rename = |inner_type: &Type| Box::new(Self::rename_type(inner_type, renames));
+        match checked_type {
+            Type::AliasedType(name) => match renames.get(name) {
+                Some(renamed) => Type::AliasedType(renamed.clone()),
+                None => checked_type.clone(),
+            },
+            Type::Array(element_type) => Type::Array(rename(element_type)),
+            Type::Object(fields) => Type::Object(Self::rename_fields(fields, renames)),
+            Type::Option(inner_type) => Type::Option(rename(inner_type)),
+            Type::Result(value_type, error_type) => {
+                Type::Result(rename(value_type), rename(error_type))
+            }
+            Type::Map(key_type, value_type) => Type::Map(rename(key_type), rename(value_type)),
+            Type::Set(element_type) => Type::Set(rename(element_type)),
+            Type::Iterator(element_type) => Type::Iterator(rename(element_type)),
+            Type::Promise(inner_type) => Type::Promise(rename(inner_type)),
+            Type::Union(members) => Type::Union(
+                members
+                    .iter()
+                    .map(|member| Self::rename_type(member, renames))
+                    .collect(),
+            ),
+            _ => checked_type.clone(),
+        }
+    }
+
+    fn rename_fields(
+        fields: &HashMap<String, Type>,
+        renames: &HashMap<String, String>,
+    ) -> HashMap<String, Type> {
+        fields
+            .iter()
+            .map(|(name, field_type)| (name.clone(), Self::rename_type(field_type, renames)))
+            .collect()
+    }
+
+    fn rename_signature(
+        signature: &FunctionSignature,
+        renames: &HashMap<String, String>,
+    ) -> FunctionSignature {
+        FunctionSignature::new(
+            signature.param_names.clone(),
+            signature
+                .param_types
+                .iter()
+                .map(|param_type| Self::rename_type(param_type, renames))
+                .collect(),
+            signature.optional_params.clone(),
+            signature
+                .rest_type
+                .as_ref()
+                .map(|rest_type| Self::rename_type(rest_type, renames)),
+        )
+    }
+
+    fn rename_methods(
+        methods: &HashMap<String, MethodInfo>,
+        renames: &HashMap<String, String>,
+    ) -> HashMap<String, MethodInfo> {
+        methods
+            .iter()
+            .map(|(name, method)| {
+                let method = MethodInfo {
+                    signature: Self::rename_signature(&method.signature, renames),
+                    return_type: Self::rename_type(&method.return_type, renames),
+                };
+                (name.clone(), method)
+            })
+            .collect()
+    }
+}
+
+/// How control can leave a statement: by falling through to the next one,
+/// otherwise by `break`/`continue` on some path, otherwise only by `return`
+/// or `throw`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Completion {
+    Normal,
+    Break,
+    Return,
+}
+
+impl Completion {
+    fn either(self, other: Completion) -> Completion {
+        match (self, other) {
+            (Completion::Normal, _) | (_, Completion::Normal) => Completion::Normal,
+            (Completion::Break, _) | (_, Completion::Break) => Completion::Break,
+            _ => Completion::Return,
+        }
+    }
+}
+
+/// Variables and functions are lexically scoped, a nested function only
+/// exists inside the function declaring it.
+#[derive(Default)]
+struct Scope {
+    variables: HashMap<String, VarInfo>,
+    functions: HashMap<String, MethodInfo>,
+    variable_symbols: HashMap<String, SymbolId>,
+    function_symbols: HashMap<String, SymbolId>,
+    // what the program declared here, described for error notes, with the
+    // line of the name if the parser recorded it
+    declarations: HashMap<String, (String, Option<u32>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolKind {
+    Variable,
+    Constant,
+    Function,
+    Class,
+    Interface,
+    TypeAlias,
+    Field,
+    Method,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SymbolId(usize);
+
+/// A declaration of the checked program or of the globals it can use. For
+/// functions and methods the type is their return type, for classes and
+/// interfaces the type of their instances, for type aliases the aliased type.
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub id: SymbolId,
+    pub name: String,
+    pub kind: SymbolKind,
+    pub symbol_type: Type,
+    /// where the declaration names the symbol, `None` for globals and
+    /// names the checker made up like `self`
+    pub position: Option<Position>,
+    // `None` for globals and imports' origins outside the program
+    declaration: Option<*const Stmt>,
+}
+
+impl Symbol {
+    /// Class members belong to the class, parameters to their function.
+    pub fn is_declared_by(&self, stmt: &Stmt) -> bool {
+        self.declaration == Some(stmt as *const Stmt)
+    }
+}
+
+/// An identifier expression naming a symbol.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reference {
+    pub expr: ExprId,
+    pub position: Option<Position>,
+}
+
+/// Everything the checker learned about a program, with aliases resolved.
+/// Expressions are looked up by the id their parser gave them, so the queries
+/// hold for the checked program and every clone of it.
+#[derive(Default)]
+pub struct TypeInfo {
+    expression_types: HashMap<ExprId, Type>,
+    symbols: Vec<Symbol>,
+    // identifier expression to the symbol it names
+    uses: HashMap<ExprId, SymbolId>,
+    // by symbol id, the identifiers naming it in the order they were checked
+    references: Vec<Vec<Reference>>,
+    declarations: HashMap<Position, SymbolId>,
+    type_aliases: HashMap<String, Type>,
+}
+
+impl TypeInfo {
+    fn add_symbol(
+        &mut self,
+        name: &str,
+        kind: SymbolKind,
+        symbol_type: Type,
+        position: Option<Position>,
+        declaration: Option<*const Stmt>,
+    ) -> SymbolId {
+        let id = SymbolId(self.symbols.len());
+        self.symbols.push(Symbol {
+            id,
+            name: name.to_string(),
+            kind,
+            symbol_type,
+            position,
+            declaration,
+        });
+        self.references.push(Vec::new());
+        if let Some(position) = position {
+            self.declarations.insert(position, id);
+        }
+        id
+    }
+
+    fn add_use(&mut self, expr: &Expr, id: SymbolId, position: Option<Position>) {
+        if self.uses.insert(expr.id, id).is_none() {
+            self.references[id.0].push(Reference {
+                expr: expr.id,
+                position,
+            });
+        }
+    }
+
+    pub fn type_of(&self, expr: &Expr) -> Option<&Type> {
+        self.expression_types.get(&expr.id)
+    }
+
+    /// The declaration an identifier expression refers to.
+    pub fn symbol_of(&self, expr: &Expr) -> Option<&Symbol> {
+        self.uses.get(&expr.id).map(|id| self.symbol(*id))
+    }
+
+    pub fn symbol(&self, id: SymbolId) -> &Symbol {
+        &self.symbols[id.0]
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// Symbols introduced by a statement, e.g. the bindings of a variable
+    /// declaration or a function with its parameters.
+    pub fn declared_by(&self, stmt: &Stmt) -> Vec<&Symbol> {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.is_declared_by(stmt))
+            .collect()
+    }
+
+    /// The symbol whose declaration names it at `position`.
+    pub fn symbol_at(&self, position: Position) -> Option<&Symbol> {
+        self.declarations.get(&position).map(|id| self.symbol(*id))
+    }
+
+    pub fn references(&self, id: SymbolId) -> &[Reference] {
+        &self.references[id.0]
+    }
+
+    pub fn resolve_alias(&self, name: &str) -> Option<&Type> {
+        self.type_aliases.get(name)
+    }
+
+    pub fn type_aliases(&self) -> &HashMap<String, Type> {
+        &self.type_aliases
     }
 }
 
 pub struct TypeChecker {
-    scope_stack: Vec<HashMap<String, VarInfo>>,
-    function_signatures: HashMap<String, Vec<Type>>,
+    scope_stack: Vec<Scope>,
     type_alias: HashMap<String, Type>,
-    function_return_type: HashMap<String, Type>,
     current_return_type: Option<Type>,
     currently_async: bool,
+    // first scope of the function being checked, variables below it belong
+    // to enclosing functions
+    function_scope_depth: usize,
+    type_info: TypeInfo,
+    warnings: Vec<String>,
     currently_loop: bool,
+    // states at the start and at each `break` of the loops being checked,
+    // a switch catches the `break`s of its cases
+    loop_exits: Vec<(AssignmentStates, Vec<AssignmentStates>)>,
+    currently_constructor: bool,
+    classes: HashMap<String, ClassInfo>,
+    interfaces: HashMap<String, InterfaceInfo>,
+    // classes and interfaces hoisted in a block whose declaration the check
+    // has not reached yet
+    unreached_types: HashSet<String>,
+    // (interface, type) pairs currently being compared, recursive interfaces
+    // are assumed to conform while their members are checked
+    conformance_in_progress: RefCell<Vec<(String, Type)>>,
+    // exports of imported modules, keyed by the path used in the import
+    modules: HashMap<String, ModuleExports>,
+    exported_names: Vec<String>,
+    // declares the symbols introduced while it is checked
+    current_statement: Option<*const Stmt>,
+    class_symbols: HashMap<String, SymbolId>,
+    // fix for the error the check stopped at
+    suggestion: Option<Suggestion>,
+    // strict mode reports every type that is only Any because it is unknown
+    strict: bool,
+    implicit_any: Vec<String>,
+    // set where an expression is evaluated before the function body, which
+    // catches what `?` throws
+    propagation_barrier: Option<&'static str>,
     js_stdlib: JsStdLib,
+    // where the parser found the names of the checked program
+    source_map: SourceMap,
 }
 
 #[derive(Debug)]
 pub struct TypeError {
-    message: String,
+    pub message: String,
+}
+
+fn mentions_any(checked_type: &Type) -> bool {
+    match checked_type {
+        Type::Any => true,
+        Type::Array(inner)
+        | Type::Option(inner)
+        | Type::Set(inner)
+        | Type::Iterator(inner)
+        | Type::Promise(inner) => mentions_any(inner),
+        Type::Result(first, second) | Type::Map(first, second) => {
+            mentions_any(first) || mentions_any(second)
+        }
+        Type::Object(fields) => fields.values().any(mentions_any),
+        Type::Union(members) => members.iter().any(mentions_any),
+        _ => false,
+    }
+}
+
+/// Where a misspelled name stands, to find it in the source again.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NameKind {
+    Variable,
+    Property,
+    Type,
+}
+
+/// Replacing the misspelled `name` with `replacement` fixes a type error.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub name: String,
+    pub replacement: String,
+    pub kind: NameKind,
+    // address of the name's text in the checked program
+    address: *const u8,
+}
+
+impl Suggestion {
+    /// Where the misspelled name is, if the parser recorded it.
+    pub fn position(&self, source_map: &SourceMap) -> Option<Position> {
+        source_map.name_position(self.address)
+    }
+}
+
+// Levenshtein distance, swapping two neighbouring characters counts as one
+// edit as well.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in distances[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = substitution
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+// The closest candidate, if it is close enough to be a typo of `name`. A
+// one letter name is not a typo of any other one.
+fn closest_name(name: &str, candidates: &[String]) -> Option<String> {
+    let length = name.chars().count();
+    let max_distance = (length / 3).max(1).min(length.saturating_sub(1));
+    candidates
+        .iter()
+        .filter(|candidate| candidate.as_str() != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate.clone())
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TypeChecker {
     fn enter_scope(&mut self) {
-        self.scope_stack.push(HashMap::new());
+        self.scope_stack.push(Scope::default());
     }
 
     fn exit_scope(&mut self) {
@@ -42,440 +644,2551 @@ impl TypeChecker {
         }
     }
 
-    fn declare_variable(&mut self, name: String, var_info: VarInfo) {
+    // Names may be declared once per scope, inner scopes may shadow them.
+    // `name` has to be the text of the declaration in the checked program.
+    fn declare_name(&mut self, name: &str, description: String) -> Result<(), TypeError> {
+        let line = self
+            .source_map
+            .name_position(name.as_ptr())
+            .map(|position| position.line);
+        let scope = &mut self
+            .scope_stack
+            .last_mut()
+            .expect("global scope")
+            .declarations;
+        if let Some((first, first_line)) = scope.get(name) {
+            let location = match first_line {
+                Some(first_line) => format!(" in Zeile {}", first_line),
+                None => String::new(),
+            };
+            return Err(TypeError {
+                message: format!(
+                    "'{}' ist in diesem Gültigkeitsbereich bereits deklariert. Hinweis: zuerst deklariert als {}{}",
+                    name, first, location
+                ),
+            });
+        }
+        scope.insert(name.to_string(), (description, line));
+        Ok(())
+    }
+
+    fn describe_binding(var_type: &Type, is_const: bool) -> String {
+        let kind = if is_const { "Konstante" } else { "Variable" };
+        format!("{} vom Typ {:?}", kind, var_type)
+    }
+
+    fn declare_variable(&mut self, name: &str, var_info: VarInfo) {
+        let kind = if var_info.is_const {
+            SymbolKind::Constant
+        } else {
+            SymbolKind::Variable
+        };
+        let id = self.new_symbol(name, kind, &var_info.var_type);
         if let Some(current_scope) = self.scope_stack.last_mut() {
-            current_scope.insert(name, var_info);
+            current_scope.variable_symbols.insert(name.to_string(), id);
+            current_scope.variables.insert(name.to_string(), var_info);
+        }
+    }
+
+    fn new_symbol(&mut self, name: &str, kind: SymbolKind, symbol_type: &Type) -> SymbolId {
+        let symbol_type = self.resolve_type(symbol_type);
+        // only names taken from the program have a position
+        let position = self.source_map.name_position(name.as_ptr());
+        self.type_info
+            .add_symbol(name, kind, symbol_type, position, self.current_statement)
+    }
+
+    // Hoisted declarations get their symbols before their statement is checked.
+    fn declaration_symbol(
+        &mut self,
+        stmt: &Stmt,
+        name: &str,
+        kind: SymbolKind,
+        symbol_type: &Type,
+    ) {
+        let outer_statement = self.current_statement.replace(stmt as *const Stmt);
+        self.new_symbol(name, kind, symbol_type);
+        self.current_statement = outer_statement;
+    }
+
+    fn add_use(&mut self, expr: &Expr, id: SymbolId) {
+        let position = self.source_map.expression_position(expr.id);
+        self.type_info.add_use(expr, id, position);
+    }
+
+    // Hoisted functions keep the symbol they got when they were hoisted.
+    fn function_symbol(&mut self, depth: usize, name: &str, return_type: &Type) {
+        if !self.scope_stack[depth].function_symbols.contains_key(name) {
+            let id = self.new_symbol(name, SymbolKind::Function, return_type);
+            self.scope_stack[depth]
+                .function_symbols
+                .insert(name.to_string(), id);
         }
     }
 
     fn lookup_variable(&self, name: &str) -> Option<&VarInfo> {
         for scope in self.scope_stack.iter().rev() {
-            if let Some(var_info) = scope.get(name) {
+            if let Some(var_info) = scope.variables.get(name) {
                 return Some(var_info);
             }
         }
         None
     }
 
-    fn check_if_const(&mut self, expr: &Expr) -> Option<bool> {
-        if let Expr::Identifier(name) = expr {
-            for scope in self.scope_stack.iter().rev() {
-                if let Some(var_info) = scope.get(name) {
-                    return Some(var_info.is_const);
-                }
-            }
-            None
-        } else if let Expr::Member { .. } = expr {
-            return Some(false);
-        } else {
-            panic!("Identifier needed to check for const");
-        }
+    fn lookup_variable_scope(&self, name: &str) -> Option<usize> {
+        self.scope_stack
+            .iter()
+            .rposition(|scope| scope.variables.contains_key(name))
     }
 
-    fn matching_types(&self, target_type: &Type, value_type: &Type) -> bool {
-        if target_type == value_type || *target_type == Type::Any {
-            return true;
+    fn declare_function(&mut self, name: &str, signature: FunctionSignature, return_type: Type) {
+        self.function_symbol(self.scope_stack.len() - 1, name, &return_type);
+        if let Some(current_scope) = self.scope_stack.last_mut() {
+            current_scope.functions.insert(
+                name.to_string(),
+                MethodInfo {
+                    signature,
+                    return_type,
+                },
+            );
         }
+    }
 
-        if let Type::Option(inner_type) = &target_type {
-            if *value_type == **inner_type || *value_type == Type::Null {
-                return true;
+    fn lookup_function(&self, name: &str) -> Option<&MethodInfo> {
+        self.scope_stack
+            .iter()
+            .rev()
+            .find_map(|scope| scope.functions.get(name))
+    }
+
+    fn assignment_states(&self) -> AssignmentStates {
+        let mut states = Vec::new();
+        for (depth, scope) in self.scope_stack.iter().enumerate() {
+            for (name, var_info) in &scope.variables {
+                if var_info.assignment != Assignment::Assigned {
+                    states.push((depth, name.clone(), var_info.assignment));
+                }
             }
         }
+        states
+    }
 
-        false
+    // The current state of the variables tracked in `before`.
+    fn current_states(&self, before: &AssignmentStates) -> AssignmentStates {
+        before
+            .iter()
+            .map(|(depth, name, _)| {
+                let assignment = self.scope_stack[*depth].variables[name].assignment;
+                (*depth, name.clone(), assignment)
+            })
+            .collect()
     }
 
-    fn get_type(&mut self, checked_type: &Type) -> Type {
-        if let Type::AliasedType(name) = checked_type {
-            self.type_alias
-                .get(name)
-                .expect("type does not exist")
-                .clone()
-        } else {
-            checked_type.clone()
+    fn set_states(&mut self, states: &AssignmentStates) {
+        for (depth, name, assignment) in states {
+            if let Some(var_info) = self.scope_stack[*depth].variables.get_mut(name) {
+                var_info.assignment = *assignment;
+            }
         }
     }
 
-    fn get_method_return_type(
-        &mut self,
-        object: &Expr,
-        method_name: &str,
-    ) -> Result<Type, TypeError> {
-        let obj_type = self.infer_type(object)?;
-
-        if let Expr::Identifier(obj_name) = object {
-            if let Some(return_type) = self.js_stdlib.get_method_type(obj_name, method_name) {
-                return Ok(return_type);
+    // Continues with what holds after every path that can reach the next
+    // statement. Without such a path the state does not matter.
+    fn merge_states(&mut self, before: &AssignmentStates, paths: &[AssignmentStates]) {
+        let Some((first, rest)) = paths.split_first() else {
+            self.set_states(before);
+            return;
+        };
+        let mut merged = first.clone();
+        for path in rest {
+            for (merged_state, (_, _, assignment)) in merged.iter_mut().zip(path) {
+                merged_state.2 = merged_state.2.merge(*assignment);
             }
         }
+        self.set_states(&merged);
+    }
 
-        if let Some(return_type) = self
-            .js_stdlib
-            .get_primitive_method_type(&obj_type, method_name)
-        {
-            return Ok(return_type);
+    // Checks a branch starting from `before` and records the state after it,
+    // if its end can be reached.
+    fn check_branch(
+        &mut self,
+        before: &AssignmentStates,
+        body: &[Stmt],
+        paths: &mut Vec<AssignmentStates>,
+    ) -> Result<(), TypeError> {
+        self.set_states(before);
+        self.enter_scope();
+        self.check_block(body)?;
+        self.exit_scope();
+        if self.block_completion(body, None) != Completion::Return {
+            paths.push(self.current_states(before));
         }
+        Ok(())
+    }
 
-        match obj_type {
-            Type::Object(_) => Ok(Type::Any),
-            _ => Err(TypeError {
-                message: format!(
-                    "Cannot call method '{}' on type {:?}",
-                    method_name, obj_type
-                ),
-            }),
-        }
+    fn enter_loop(&mut self) -> AssignmentStates {
+        let before = self.assignment_states();
+        self.loop_exits.push((before.clone(), Vec::new()));
+        before
     }
 
-    pub fn print_current_scope(&mut self) {
-        println!("\nCurrent Scope Variables:");
-        println!("-----------------------");
-        if let Some(current_scope) = self.scope_stack.last() {
-            for (name, var_info) in current_scope {
-                println!("{}: {:?}", name, var_info);
+    // A loop body runs any number of times, so a constant assigned in it could
+    // be assigned twice. The loop is left at a `break`, or when the condition
+    // fails before or after any iteration.
+    fn finish_loop(
+        &mut self,
+        before: &AssignmentStates,
+        runs_forever: bool,
+    ) -> Result<(), TypeError> {
+        let (_, mut exits) = self.loop_exits.pop().expect("loop entered");
+        let after_body = self.current_states(before);
+        for ((depth, name, before_state), (_, _, after_state)) in before.iter().zip(&after_body) {
+            let is_const = self.scope_stack[*depth].variables[name].is_const;
+            if is_const
+                && *before_state == Assignment::Unassigned
+                && *after_state != Assignment::Unassigned
+            {
+                return Err(TypeError {
+                    message: format!(
+                        "Konstante '{}' könnte in einer Schleife mehrfach zugewiesen werden",
+                        name
+                    ),
+                });
             }
         }
+        if !runs_forever {
+            exits.push(before.clone());
+            exits.push(after_body);
+        }
+        self.merge_states(before, &exits);
+        Ok(())
     }
 
-    pub fn new() -> Self {
-        let mut scope_stack = Vec::new();
-        let mut global_scope = HashMap::new();
-
-        let js_stdlib = JsStdLib::new();
-        for (obj_name, methods) in &js_stdlib.objects {
-            let var_info = VarInfo::new(Type::Object(methods.clone()), true);
-            global_scope.insert(obj_name.clone(), var_info);
+    fn check_variable_read(&self, name: &str) -> Result<(), TypeError> {
+        let Some(depth) = self.lookup_variable_scope(name) else {
+            return Ok(());
+        };
+        // enclosing functions may have assigned it by the time this one runs
+        if depth < self.function_scope_depth {
+            return Ok(());
         }
-
-        scope_stack.push(global_scope);
-        Self {
-            scope_stack,
-            type_alias: HashMap::new(),
-            function_signatures: HashMap::new(),
-            function_return_type: HashMap::new(),
-            current_return_type: None,
-            currently_async: false,
-            currently_loop: false,
-            js_stdlib,
+        match self.scope_stack[depth].variables[name].assignment {
+            Assignment::Assigned => Ok(()),
+            Assignment::Unassigned => Err(TypeError {
+                message: format!(
+                    "Variable '{}' wird gelesen, bevor ihr ein Wert zugewiesen wurde",
+                    name
+                ),
+            }),
+            Assignment::Maybe => Err(TypeError {
+                message: format!(
+                    "Variable '{}' ist nicht auf allen Pfaden zugewiesen, bevor sie gelesen wird",
+                    name
+                ),
+            }),
         }
     }
 
-    pub fn check_program(&mut self, programm: &Stmt) -> Result<(), TypeError> {
-        if let Stmt::Program { body } = programm {
-            for stmt in body {
-                self.check_statement(stmt)?;
-            }
-
-            println!("\nVariable Types:");
-            println!("---------------");
-            if let Some(global_scope) = self.scope_stack.first() {
-                for (name, VarInfo) in global_scope {
-                    println!("{}: {:?}", name, VarInfo);
+    // Assigning a variable declared without a value. Constants declared that
+    // way may be assigned exactly once.
+    fn check_variable_assignment(&mut self, name: &str) -> Result<(), TypeError> {
+        let Some(depth) = self.lookup_variable_scope(name) else {
+            return Ok(());
+        };
+        let in_this_function = depth >= self.function_scope_depth;
+        let var_info = self.scope_stack[depth]
+            .variables
+            .get_mut(name)
+            .expect("variable in scope");
+        if var_info.is_const {
+            match var_info.assignment {
+                Assignment::Unassigned if in_this_function => {}
+                Assignment::Unassigned => {
+                    return Err(TypeError {
+                        message: format!(
+                            "Konstante '{}' kann nur in der Funktion zugewiesen werden, in der sie deklariert ist",
+                            name
+                        ),
+                    })
+                }
+                Assignment::Maybe => {
+                    return Err(TypeError {
+                        message: format!("Konstante '{}' ist möglicherweise bereits zugewiesen", name),
+                    })
+                }
+                Assignment::Assigned => {
+                    return Err(TypeError {
+                        message: format!("Const Variable cant be reasigned {:?}", ExprKind::Identifier(name.to_string())),
+                    })
                 }
             }
         }
-
+        var_info.assignment = Assignment::Assigned;
         Ok(())
     }
 
-    fn check_statement(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
-        match stmt {
-            Stmt::VarDeclaration { .. } => self.check_var_declaration(stmt),
-            Stmt::FunctionDeclaration { .. } => self.check_fn_declaration(stmt),
-            Stmt::IfStatement { .. } => self.check_if_stmt(stmt),
-            Stmt::WhileStatement { .. } => self.check_while_declaration(stmt),
-            Stmt::ForLoopStatement { .. } => self.check_for_loop(stmt),
-            Stmt::ForInLoopStatement { .. } => self.check_for_in_loop(stmt),
-            Stmt::ForLoopIterated { .. } => self.check_for_iter_loop(stmt),
-            Stmt::ReturnStatement { .. } => self.check_return_stmt(stmt),
-            Stmt::TryCatchFinally { .. } => self.check_try_catch_stmt(stmt),
-            Stmt::SwitchStatement { .. } => self.check_switch_stmt(stmt),
-            Stmt::ContinueStatement | Stmt::BreakStatement => self.check_loop_control_stmt(stmt),
-            Stmt::TypeAlias { .. } => self.check_type_alias_stmt(stmt),
-            Stmt::Expression(expr) => {
-                self.infer_type(expr)?;
-                Ok(())
+    fn check_if_const(&mut self, expr: &Expr) -> Option<bool> {
+        if let ExprKind::Identifier(name) = &expr.kind {
+            for scope in self.scope_stack.iter().rev() {
+                if let Some(var_info) = scope.variables.get(name) {
+                    return Some(var_info.is_const);
+                }
             }
-
-            _ => Ok(()),
+            None
+        } else if let ExprKind::Member { .. } = &expr.kind {
+            Some(false)
+        } else {
+            panic!("Identifier needed to check for const");
         }
     }
-    fn check_var_declaration(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
-        if let Stmt::VarDeclaration {
-            identifier,
-            value: Some(value),
-            var_type,
-            constant,
-        } = stmt
-        {
-            let expr_type = self.infer_type(value)?;
 
-            let final_type = if *var_type == Type::Any {
-                expr_type.clone()
-            } else if let Type::AliasedType(name) = var_type {
-                self.type_alias
-                    .get(name)
-                    .cloned()
-                    .ok_or_else(|| TypeError {
-                        message: format!("Unknown type alias: {}", name),
-                    })?
-            } else {
-                var_type.clone()
-            };
+    fn matching_types(&self, target_type: &Type, value_type: &Type) -> bool {
+        let target_type = self.resolve_type(target_type);
+        let value_type = self.resolve_type(value_type);
 
-            if self.matching_types(&final_type, &expr_type) {
-                self.declare_variable(identifier.clone(), VarInfo::new(final_type, *constant));
-                Ok(())
-            } else {
-                Err(TypeError {
-                    message: format!("Expected {:?}, got {:?}", final_type, expr_type),
-                })
-            }
-        } else {
-            panic!("Var declaration expected")
+        if target_type == value_type || target_type == Type::Any {
+            return true;
         }
-    }
 
-    fn check_fn_declaration(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
-        if let Stmt::FunctionDeclaration {
-            name,
-            parameters,
-            param_types,
-            body,
-            return_type,
-            is_async,
-        } = stmt
-        {
-            if *is_async {
-                self.currently_async = true;
+        match (&target_type, &value_type) {
+            (_, Type::Union(members)) => members
+                .iter()
+                .all(|member| self.matching_types(&target_type, member)),
+            (Type::Union(members), _) => members
+                .iter()
+                .any(|member| self.matching_types(member, &value_type)),
+            (Type::AliasedType(interface_name), _)
+                if self.interfaces.contains_key(interface_name) =>
+            {
+                self.missing_interface_members(interface_name, &value_type)
+                    .is_some_and(|missing| missing.is_empty())
             }
-            self.function_signatures
-                .insert(name.clone(), param_types.clone());
-
-            self.current_return_type = Some(self.get_type(return_type));
-            self.function_return_type
-                .insert(name.clone(), return_type.clone());
-            self.enter_scope();
-
-            for (param, param_type) in parameters.iter().zip(param_types.iter()) {
-                self.declare_variable(param.clone(), VarInfo::new(param_type.clone(), true));
+            (Type::AliasedType(target_class), Type::AliasedType(value_class)) => {
+                self.is_subclass(value_class, target_class)
             }
-
-            for stmt in body {
-                self.check_statement(stmt)?;
+            (Type::Result(target_value, target_error), Type::Result(value, error)) => {
+                (**value == Type::Any || self.matching_types(target_value, value))
+                    && (**error == Type::Any || self.matching_types(target_error, error))
             }
-
-            self.exit_scope();
-
-            self.current_return_type = None;
-
-            if *is_async {
-                self.currently_async = false;
+            (Type::String, Type::StringLiteral(_))
+            | (Type::Number, Type::NumberLiteral(_))
+            | (Type::Boolean, Type::BooleanLiteral(_)) => true,
+            (Type::Map(target_key, target_value), Type::Map(key, value)) => {
+                (**key == Type::Any || self.matching_types(target_key, key))
+                    && (**value == Type::Any || self.matching_types(target_value, value))
             }
-
-            Ok(())
-        } else {
-            panic!("function declaration expected")
+            (Type::Set(target_element), Type::Set(element))
+            | (Type::Iterator(target_element), Type::Iterator(element))
+            | (Type::Promise(target_element), Type::Promise(element)) => {
+                **element == Type::Any || self.matching_types(target_element, element)
+            }
+            (Type::Option(inner_type), Type::Option(value_inner)) => {
+                self.matching_types(inner_type, value_inner)
+            }
+            (Type::Option(inner_type), _) => {
+                value_type == Type::Null || self.matching_types(inner_type, &value_type)
+            }
+            _ => false,
         }
     }
 
-    fn check_while_declaration(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
-        if let Stmt::WhileStatement { condition, body } = stmt {
-            self.currently_loop = true;
-            let cond_type = self.infer_type(condition)?;
-
-            if cond_type != Type::Boolean {
-                return Err(TypeError {
-                    message: format!("While condition must be boolean, got {:?}", cond_type),
-                });
+    fn resolve_type(&self, checked_type: &Type) -> Type {
+        match checked_type {
+            Type::AliasedType(name) => match self.type_alias.get(name) {
+                Some(aliased_type) => self.resolve_type(aliased_type),
+                None => checked_type.clone(),
+            },
+            Type::Array(element_type) => Type::Array(Box::new(self.resolve_type(element_type))),
+            Type::Option(inner_type) => Type::Option(Box::new(self.resolve_type(inner_type))),
+            Type::Result(value_type, error_type) => Type::Result(
+                Box::new(self.resolve_type(value_type)),
+                Box::new(self.resolve_type(error_type)),
+            ),
+            Type::Map(key_type, value_type) => Type::Map(
+                Box::new(self.resolve_type(key_type)),
+                Box::new(self.resolve_type(value_type)),
+            ),
+            Type::Set(element_type) => Type::Set(Box::new(self.resolve_type(element_type))),
+            Type::Iterator(element_type) => {
+                Type::Iterator(Box::new(self.resolve_type(element_type)))
             }
-
-            self.enter_scope();
-            for stmt in body {
-                self.check_statement(stmt)?;
+            Type::Promise(inner_type) => Type::Promise(Box::new(self.resolve_type(inner_type))),
+            Type::Union(members) => {
+                let mut resolved_members = Vec::new();
+                for member in members {
+                    match self.resolve_type(member) {
+                        Type::Union(inner_members) => resolved_members.extend(inner_members),
+                        resolved => resolved_members.push(resolved),
+                    }
+                }
+                Type::Union(resolved_members)
             }
-            self.exit_scope();
-            self.currently_loop = false;
-            Ok(())
-        } else {
-            Err(TypeError {
-                message: "Expected while statement".to_string(),
-            })
+            _ => checked_type.clone(),
         }
     }
 
-    fn check_if_stmt(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
-        if let Stmt::IfStatement {
-            condition,
-            else_branch,
-            else_if_branches,
-            then_branch,
-        } = stmt
-        {
-            let cond_type = self.infer_type(condition)?;
-
-            if cond_type != Type::Boolean {
-                return Err(TypeError {
-                    message: format!("If condition must be boolean, got {:?}", cond_type),
-                });
+    // Literal types only survive where they were asked for, everywhere else
+    // they behave like their base type.
+    fn widen_type(&self, checked_type: &Type) -> Type {
+        match self.resolve_type(checked_type) {
+            Type::StringLiteral(_) => Type::String,
+            Type::NumberLiteral(_) => Type::Number,
+            Type::BooleanLiteral(_) => Type::Boolean,
+            Type::Union(members) => {
+                let mut widened_members: Vec<Type> = Vec::new();
+                for member in &members {
+                    let widened = self.widen_type(member);
+                    if !widened_members.contains(&widened) {
+                        widened_members.push(widened);
+                    }
+                }
+                if widened_members.len() == 1 {
+                    widened_members.remove(0)
+                } else {
+                    Type::Union(widened_members)
+                }
             }
+            resolved => resolved,
+        }
+    }
 
-            self.enter_scope();
-            for stmt in then_branch {
-                self.check_statement(stmt)?;
-            }
-            self.exit_scope();
+    fn literal_type(expr: &Expr) -> Option<Type> {
+        match &expr.kind {
+            ExprKind::StringLiteral(value) => Some(Type::StringLiteral(value.clone())),
+            ExprKind::NumericLiteral(value) => Some(Type::NumberLiteral(*value)),
+            ExprKind::BooleanLiteral(value) => Some(Type::BooleanLiteral(*value)),
+            _ => None,
+        }
+    }
 
-            if let Some(else_if) = else_if_branches {
-                for branch in else_if {
-                    let branch_cond_type = self.infer_type(&branch.condition)?;
+    fn infer_contextual_type(
+        &mut self,
+        expr: &Expr,
+        expected_type: &Type,
+    ) -> Result<Type, TypeError> {
+        let inferred = self.infer_contextual_expr_type(expr, expected_type)?;
+        self.record_type(expr, &inferred);
+        Ok(inferred)
+    }
 
-                    if branch_cond_type != Type::Boolean {
-                        return Err(TypeError {
-                            message: format!(
-                                "Else-if condition must be boolean, got {:?}",
-                                branch_cond_type
-                            ),
-                        });
-                    }
+    fn infer_contextual_expr_type(
+        &mut self,
+        expr: &Expr,
+        expected_type: &Type,
+    ) -> Result<Type, TypeError> {
+        if let Some(literal_type) = Self::literal_type(expr) {
+            return Ok(literal_type);
+        }
 
-                    self.enter_scope();
-                    for stmt in &branch.body {
-                        self.check_statement(stmt)?;
-                    }
-                    self.exit_scope();
+        if let ExprKind::Call { caller, args } = &expr.kind {
+            if let ExprKind::Identifier(fn_name) = &caller.kind {
+                if let Some(result_type) =
+                    self.check_result_constructor(fn_name, args, Some(expected_type))?
+                {
+                    return Ok(result_type);
+                }
+                if let Some(collection_type) =
+                    self.check_collection_constructor(fn_name, args, Some(expected_type))?
+                {
+                    return Ok(collection_type);
                 }
             }
+        }
 
-            if let Some(else_stmts) = else_branch {
-                self.enter_scope();
-                for stmt in else_stmts {
-                    self.check_statement(stmt)?;
+        if let (ExprKind::ArrayLiteral(values), Type::Array(element_type)) =
+            (&expr.kind, self.resolve_type(expected_type))
+        {
+            for value in values {
+                let value_type = if let ExprKind::Spread { .. } = &value.kind {
+                    self.check_array_element(value)?
+                } else {
+                    self.infer_contextual_type(value, &element_type)?
+                };
+                if !self.matching_types(&element_type, &value_type) {
+                    return Err(TypeError {
+                        message: format!(
+                            "Array elements must be of type {:?}, got {:?}",
+                            element_type, value_type
+                        ),
+                    });
                 }
-                self.exit_scope();
             }
-            Ok(())
-        } else {
-            Err(TypeError {
-                message: "Expected if statement".to_string(),
-            })
+            return Ok(Type::Array(element_type));
+        }
+
+        if let ExprKind::ObjectLiteral(..) = &expr.kind {
+            let expected_fields = self.expected_fields(expected_type);
+            return self.check_object_literal(expr, expected_fields.as_ref());
         }
+
+        self.infer_type(expr)
     }
 
-    fn check_for_loop(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
-        if let Stmt::ForLoopStatement {
-            initializer,
-            condition,
-            update,
-            body,
-        } = stmt
-        {
-            self.currently_loop = true;
-            self.enter_scope();
+    // Property types an object literal is checked against.
+    fn expected_fields(&self, expected_type: &Type) -> Option<HashMap<String, Type>> {
+        match self.resolve_type(expected_type) {
+            Type::Object(fields) => Some(fields),
+            Type::AliasedType(interface_name) => self
+                .interfaces
+                .get(&interface_name)
+                .map(|interface| interface.fields.clone()),
+            Type::Option(inner_type) => self.expected_fields(&inner_type),
+            _ => None,
+        }
+    }
 
-            if let Some(init) = initializer {
-                self.check_statement(init)?;
+    // All values a type can take, if there are finitely many of them.
+    fn finite_members(&self, checked_type: &Type) -> Option<Vec<Type>> {
+        match self.resolve_type(checked_type) {
+            Type::Boolean => Some(vec![
+                Type::BooleanLiteral(true),
+                Type::BooleanLiteral(false),
+            ]),
+            literal @ (Type::StringLiteral(_)
+            | Type::NumberLiteral(_)
+            | Type::BooleanLiteral(_)
+            | Type::Null) => Some(vec![literal]),
+            Type::Option(inner_type) => {
+                let mut members = self.finite_members(&inner_type)?;
+                members.push(Type::Null);
+                Some(members)
             }
-            if let Some(cond) = condition {
-                let cond_type = self.infer_type(cond)?;
-                if cond_type != Type::Boolean {
-                    return Err(TypeError {
-                        message: format!("For loop condition must be boolean, got {:?}", cond),
-                    });
+            Type::Union(union_members) => {
+                let mut members = Vec::new();
+                for member in &union_members {
+                    for inner in self.finite_members(member)? {
+                        if !members.contains(&inner) {
+                            members.push(inner);
+                        }
+                    }
                 }
+                Some(members)
             }
+            _ => None,
+        }
+    }
 
-            if let Some(update_expr) = update {
-                self.infer_type(update_expr)?;
+    fn get_type(&mut self, checked_type: &Type) -> Result<Type, TypeError> {
+        if let Type::AliasedType(name) = checked_type {
+            if !self.is_known_type_name(name) {
+                let note = self.did_you_mean(name, &self.type_names(), NameKind::Type);
+                return Err(TypeError {
+                    message: format!("Unbekannter Typ '{}'{}", name, note),
+                });
             }
+        }
+        Ok(self.resolve_type(checked_type))
+    }
 
-            for stmt in body {
-                self.check_statement(stmt)?;
-            }
+    fn is_known_type_name(&self, name: &str) -> bool {
+        self.type_alias.contains_key(name)
+            || self.classes.contains_key(name)
+            || self.interfaces.contains_key(name)
+    }
 
-            self.exit_scope();
-            self.currently_loop = false;
-            Ok(())
-        } else {
-            Err(TypeError {
-                message: "Expected for loop statement".to_string(),
-            })
+    // Names of the interface members `value_type` lacks or has with an
+    // incompatible type, `None` if the type cannot have members at all.
+    fn missing_interface_members(
+        &self,
+        interface_name: &str,
+        value_type: &Type,
+    ) -> Option<Vec<String>> {
+        let interface = &self.interfaces[interface_name];
+        let value_type = self.resolve_type(value_type);
+
+        let in_progress = (interface_name.to_string(), value_type.clone());
+        if self.conformance_in_progress.borrow().contains(&in_progress) {
+            return Some(Vec::new());
         }
-    }
 
-    fn check_for_in_loop(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
-        if let Stmt::ForInLoopStatement {
-            iterable,
-            iterator,
-            body,
-        } = stmt
-        {
-            self.currently_loop = true;
-            let element_type = if let Some(itera) = iterable {
-                let iter_type = self.infer_type(itera)?;
-                match iter_type {
-                    Type::Array(element_type) => Ok(*element_type),
-                    _ => {
-                        return Err(TypeError {
-                            message: "iterable must be of type array".to_string(),
+        // field type and method (`None` if only known to exist) of the value
+        let (field_of, method_of): (MemberLookup<Type>, MemberLookup<Option<MethodInfo>>) =
+            match &value_type {
+                Type::AliasedType(class_name) if self.classes.contains_key(class_name) => (
+                    Box::new(move |name| {
+                        self.find_class_member(class_name, |class_info| {
+                            class_info.fields.get(name).cloned()
                         })
-                    }
-                }?
-            } else {
-                panic!("iteratable needed")
+                    }),
+                    Box::new(move |name| {
+                        self.find_class_member(class_name, |class_info| {
+                            class_info.methods.get(name).cloned()
+                        })
+                        .map(Some)
+                    }),
+                ),
+                Type::AliasedType(other_interface)
+                    if self.interfaces.contains_key(other_interface) =>
+                {
+                    let other = &self.interfaces[other_interface];
+                    (
+                        Box::new(move |name| other.fields.get(name).cloned()),
+                        Box::new(move |name| other.methods.get(name).cloned().map(Some)),
+                    )
+                }
+                Type::Object(properties) => (
+                    Box::new(move |name| properties.get(name).cloned()),
+                    // functions are no values, only a property of unknown
+                    // type may hold a method
+                    Box::new(move |name| {
+                        properties
+                            .get(name)
+                            .filter(|property_type| **property_type == Type::Any)
+                            .map(|_| None)
+                    }),
+                ),
+                _ => return None,
             };
 
-            if let Some(iter) = iterator {
-                if let Stmt::VarDeclaration { identifier, .. } = iter.as_ref() {
-                    self.declare_variable(identifier.clone(), VarInfo::new(element_type, true));
-                }
-            } else {
-                panic!("iterator needed");
+        self.conformance_in_progress.borrow_mut().push(in_progress);
+        let mut missing = Vec::new();
+        for (field_name, field_type) in &interface.fields {
+            match field_of(field_name) {
+                Some(provided) if self.matching_types(field_type, &provided) => {}
+                None if interface.optional_fields.contains(field_name) => {}
+                _ => missing.push(field_name.clone()),
             }
-
-            self.enter_scope();
-            for stmt in body {
-                self.check_statement(stmt)?;
+        }
+        for (method_name, method) in &interface.methods {
+            match method_of(method_name) {
+                Some(Some(provided)) if self.method_conforms(method, &provided) => {}
+                Some(None) => {}
+                _ => missing.push(format!("{}()", method_name)),
             }
-            self.exit_scope();
-            self.currently_loop = false;
-            Ok(())
-        } else {
-            Err(TypeError {
-                message: "Expected for-in loop statement".to_string(),
-            })
         }
+        self.conformance_in_progress.borrow_mut().pop();
+
+        missing.sort();
+        Some(missing)
     }
 
-    fn check_for_iter_loop(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
-        if let Stmt::ForLoopIterated {
-            body,
-            iterator_name,
-            first_number,
-            ..
-        } = stmt
-        {
-            self.enter_scope();
+    // The provided method has to accept every call the required one allows.
+    fn method_conforms(&self, required: &MethodInfo, provided: &MethodInfo) -> bool {
+        let required_params = &required.signature.param_types;
+        let provided_params = &provided.signature.param_types;
+        if provided_params.len() < required_params.len() {
+            return false;
+        }
+        let params_match =
+            required_params
+                .iter()
+                .zip(provided_params)
+                .all(|(required_type, provided_type)| {
+                    self.matching_types(provided_type, required_type)
+                });
+        let extra_params_optional = provided.signature.optional_params[required_params.len()..]
+            .iter()
+            .all(|optional| *optional);
 
-            if let Some(iter) = iterator_name {
-                self.check_statement(&Stmt::VarDeclaration {
-                    constant: true,
-                    identifier: iter.clone(),
-                    value: first_number.clone(),
-                    var_type: Type::Number,
-                })?;
+        params_match
+            && extra_params_optional
+            && self.matching_types(&required.return_type, &provided.return_type)
+    }
+
+    // Extra detail for type mismatch errors involving interfaces.
+    fn mismatch_note(&self, target_type: &Type, value_type: &Type) -> String {
+        if let Type::AliasedType(interface_name) = self.resolve_type(target_type) {
+            if self.interfaces.contains_key(&interface_name) {
+                if let Some(missing) = self.missing_interface_members(&interface_name, value_type) {
+                    if !missing.is_empty() {
+                        return format!(" (fehlend oder unpassend: {})", missing.join(", "));
+                    }
+                }
             }
+        }
+        String::new()
+    }
 
-            for stmt in body {
-                self.check_statement(stmt)?;
+    fn is_subclass(&self, class_name: &str, ancestor: &str) -> bool {
+        let mut current = Some(class_name.to_string());
+        while let Some(name) = current {
+            if name == ancestor {
+                return true;
             }
+            current = self
+                .classes
+                .get(&name)
+                .and_then(|class_info| class_info.parent.clone());
+        }
+        false
+    }
 
-            self.exit_scope();
+    // Walks up the inheritance chain until `lookup` finds the member.
+    fn find_class_member<T>(
+        &self,
+        class_name: &str,
+        lookup: impl Fn(&ClassInfo) -> Option<T>,
+    ) -> Option<T> {
+        let mut current = self.classes.get(class_name);
+        while let Some(class_info) = current {
+            if let Some(member) = lookup(class_info) {
+                return Some(member);
+            }
+            current = class_info
+                .parent
+                .as_ref()
+                .and_then(|parent| self.classes.get(parent));
+        }
+        None
+    }
 
-            Ok(())
-        } else {
-            panic!("For iter loop expected ")
+    // Fields and methods of a class and its parents.
+    fn class_member_names(&self, class_name: &str, is_static: bool) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut current = self.classes.get(class_name);
+        while let Some(class_info) = current {
+            if is_static {
+                names.extend(class_info.static_fields.keys().cloned());
+                names.extend(class_info.static_methods.keys().cloned());
+            } else {
+                names.extend(class_info.fields.keys().cloned());
+                names.extend(class_info.methods.keys().cloned());
+            }
+            current = class_info
+                .parent
+                .as_ref()
+                .and_then(|parent| self.classes.get(parent));
+        }
+        names
+    }
+
+    // A bare class name refers to the class itself unless a variable shadows it.
+    fn class_reference(&self, expr: &Expr) -> Option<String> {
+        if let ExprKind::Identifier(name) = &expr.kind {
+            if self.classes.contains_key(name) && self.lookup_variable(name).is_none() {
+                return Some(name.clone());
+            }
+        }
+        None
+    }
+
+    fn class_symbol(&mut self, name: &str) {
+        if !self.class_symbols.contains_key(name) {
+            let instance_type = Type::AliasedType(name.to_string());
+            let id = self.new_symbol(name, SymbolKind::Class, &instance_type);
+            self.class_symbols.insert(name.to_string(), id);
+        }
+    }
+
+    fn record_class_reference(&mut self, expr: &Expr) -> Option<String> {
+        let class_name = self.class_reference(expr)?;
+        if let Some(id) = self.class_symbols.get(&class_name).copied() {
+            self.add_use(expr, id);
+        }
+        Some(class_name)
+    }
+
+    // Resolves `Class.method` and `instance.method` calls, `None` means the
+    // object is not a class at all.
+    fn get_class_method(
+        &mut self,
+        object: &Expr,
+        method_name: &str,
+    ) -> Result<Option<(String, MethodInfo)>, TypeError> {
+        let (class_name, is_static) = match self.record_class_reference(object) {
+            Some(class_name) => (class_name, true),
+            None => match self.infer_type(object).map(|t| self.resolve_type(&t))? {
+                Type::AliasedType(class_name) if self.classes.contains_key(&class_name) => {
+                    (class_name, false)
+                }
+                Type::AliasedType(interface_name)
+                    if self.interfaces.contains_key(&interface_name) =>
+                {
+                    return match self.interfaces[&interface_name].methods.get(method_name) {
+                        Some(method) => Ok(Some((
+                            format!(
+                                "{}.{}",
+                                interface_name.trim_start_matches("typeof "),
+                                method_name
+                            ),
+                            method.clone(),
+                        ))),
+                        None => {
+                            let method_names: Vec<String> = self.interfaces[&interface_name]
+                                .methods
+                                .keys()
+                                .cloned()
+                                .collect();
+                            let note =
+                                self.did_you_mean(method_name, &method_names, NameKind::Property);
+                            Err(TypeError {
+                                message: format!(
+                                    "Interface '{}' hat keine Methode '{}'{}",
+                                    interface_name, method_name, note
+                                ),
+                            })
+                        }
+                    };
+                }
+                _ => return Ok(None),
+            },
+        };
+
+        let method = self.find_class_member(&class_name, |class_info| {
+            if is_static {
+                class_info.static_methods.get(method_name).cloned()
+            } else {
+                class_info.methods.get(method_name).cloned()
+            }
+        });
+        match method {
+            Some(method) => Ok(Some((format!("{}.{}", class_name, method_name), method))),
+            None => {
+                let member_names = self.class_member_names(&class_name, is_static);
+                let note = self.did_you_mean(method_name, &member_names, NameKind::Property);
+                Err(TypeError {
+                    message: format!(
+                        "Klasse '{}' hat keine {}Methode '{}'{}",
+                        class_name,
+                        if is_static { "statische " } else { "" },
+                        method_name,
+                        note
+                    ),
+                })
+            }
+        }
+    }
+
+    fn get_method_return_type(
+        &mut self,
+        object: &Expr,
+        method_name: &str,
+        args: &[Expr],
+    ) -> Result<Type, TypeError> {
+        if let ExprKind::Identifier(obj_name) = &object.kind {
+            if let Some(signature) = self.js_stdlib.get_method(obj_name, method_name) {
+                let method_path = format!("{}.{}", obj_name, method_name);
+                return self.check_builtin_call(&method_path, &signature, args);
+            }
+            let is_stdlib_object = self.js_stdlib.objects.contains_key(obj_name)
+                && self.lookup_variable_scope(obj_name) == Some(0);
+            let member_names = self.js_stdlib.member_names(obj_name);
+            if is_stdlib_object && !member_names.iter().any(|name| name == method_name) {
+                let note = self.did_you_mean(method_name, &member_names, NameKind::Property);
+                return Err(TypeError {
+                    message: format!("{} hat keine Methode '{}'{}", obj_name, method_name, note),
+                });
+            }
+        }
+
+        let obj_type = self.infer_type(object)?;
+        let obj_type = self.resolve_type(&obj_type);
+
+        if let Some((method_path, signature)) =
+            self.js_stdlib.get_primitive_method(&obj_type, method_name)
+        {
+            return self.check_builtin_call(&method_path, &signature, args);
+        }
+
+        match obj_type {
+            Type::Object(_) => {
+                self.report_implicit_any(format!(
+                    "Methode '{}' eines Objekts hat keinen bekannten Rückgabetyp",
+                    method_name
+                ));
+                Ok(Type::Any)
+            }
+            _ => {
+                let member_names = self.js_stdlib.primitive_member_names(&obj_type);
+                let note = self.did_you_mean(method_name, &member_names, NameKind::Property);
+                Err(TypeError {
+                    message: format!(
+                        "Cannot call method '{}' on type {:?}{}",
+                        method_name, obj_type, note
+                    ),
+                })
+            }
+        }
+    }
+
+    // Callbacks are passed by function name; the callback result placeholder
+    // in the return type becomes the return type of the callback.
+    fn check_builtin_call(
+        &mut self,
+        method_path: &str,
+        signature: &MethodSignature,
+        args: &[Expr],
+    ) -> Result<Type, TypeError> {
+        let max_args = signature.param_types.len();
+        if args.len() > max_args && signature.rest_type.is_none() {
+            return Err(TypeError {
+                message: format!(
+                    "Funktion '{}' erwartet höchstens {} Argumente, aber {} wurden übergeben",
+                    method_path,
+                    max_args,
+                    args.len()
+                ),
+            });
+        }
+        let has_spread = args
+            .iter()
+            .any(|arg| matches!(&arg.kind, ExprKind::Spread { .. }));
+        if args.len() < signature.required_params && !has_spread {
+            return Err(TypeError {
+                message: format!(
+                    "Funktion '{}' erwartet mindestens {} Argumente, aber {} wurden übergeben",
+                    method_path,
+                    signature.required_params,
+                    args.len()
+                ),
+            });
+        }
+
+        let mut signature = signature.clone();
+        let mut callback_result = Type::Any;
+        for (idx, arg) in args.iter().enumerate() {
+            let mut expected_type = match signature.param_types.get(idx) {
+                Some(param_type) => param_type.clone(),
+                None => signature.rest_type.clone().unwrap_or(Type::Any),
+            };
+
+            // statics like `Promise.all` learn their element type from the arguments
+            if js_stdlib::mentions_placeholder(&expected_type, js_stdlib::ELEMENT) {
+                let arg_type = self.infer_type(arg)?;
+                let arg_type = self.resolve_type(&arg_type);
+                if let Some(bound) =
+                    js_stdlib::bind_placeholder(&expected_type, &arg_type, js_stdlib::ELEMENT)
+                {
+                    let bound = self.widen_type(&bound);
+                    signature = signature.instantiate(&[(js_stdlib::ELEMENT, &bound)]);
+                    expected_type =
+                        js_stdlib::substitute(&expected_type, js_stdlib::ELEMENT, &bound);
+                }
+            }
+
+            if expected_type == js_stdlib::callback() {
+                if let ExprKind::Identifier(fn_name) = &arg.kind {
+                    if self.lookup_variable(fn_name).is_none() {
+                        if let Some(function) = self.lookup_function(fn_name).cloned() {
+                            if idx == 0 {
+                                self.check_callback_params(
+                                    method_path,
+                                    fn_name,
+                                    &function.signature,
+                                    &signature.callback_params,
+                                )?;
+                            }
+                            callback_result = function.return_type;
+                            self.record_function(arg, fn_name);
+                            continue;
+                        }
+                    }
+                }
+                let actual_type = self.infer_type(arg)?;
+                if actual_type != Type::Any {
+                    return Err(TypeError {
+                        message: format!(
+                            "Typfehler bei Funktionsaufruf '{}': Parameter {} sollte eine Funktion sein, ist aber {:?}",
+                            method_path,
+                            idx + 1,
+                            actual_type
+                        ),
+                    });
+                }
+                continue;
+            }
+
+            let (expected_type, actual_type) = if let ExprKind::Spread { value } = &arg.kind {
+                let expected_type = Type::Array(Box::new(expected_type));
+                let actual_type = self.infer_contextual_type(value, &expected_type)?;
+                (expected_type, actual_type)
+            } else {
+                let actual_type = self.infer_contextual_type(arg, &expected_type)?;
+                (expected_type, actual_type)
+            };
+            if !self.matching_types(&expected_type, &actual_type) {
+                return Err(TypeError {
+                    message: format!(
+                        "Typfehler bei Funktionsaufruf '{}': Parameter {} sollte vom Typ {:?} sein, ist aber {:?}",
+                        method_path,
+                        idx + 1,
+                        expected_type,
+                        actual_type
+                    ),
+                });
+            }
+        }
+
+        let return_type = js_stdlib::substitute(
+            &signature.return_type,
+            js_stdlib::CALLBACK_RESULT,
+            &callback_result,
+        );
+        // an Any of the receiver's element type was reported where it came from
+        let declares_any = method_path
+            .split_once('.')
+            .and_then(|(object_name, method_name)| {
+                self.js_stdlib.get_method(object_name, method_name)
+            })
+            .is_some_and(|declared| mentions_any(&declared.return_type));
+        let unbound = js_stdlib::mentions_placeholder(&return_type, js_stdlib::ELEMENT);
+        let return_type = js_stdlib::substitute(&return_type, js_stdlib::ELEMENT, &Type::Any);
+        if declares_any || unbound {
+            self.report_implicit_any(format!(
+                "Rückgabetyp von '{}' ist unbekannt ({:?})",
+                method_path, return_type
+            ));
+        }
+        Ok(return_type)
+    }
+
+    // Each parameter of the callback has to accept what the method passes.
+    fn check_callback_params(
+        &self,
+        method_path: &str,
+        fn_name: &str,
+        callback: &FunctionSignature,
+        passed_types: &[Type],
+    ) -> Result<(), TypeError> {
+        for (idx, (param_type, passed_type)) in
+            callback.param_types.iter().zip(passed_types).enumerate()
+        {
+            // nothing is known about what a method passes as `any`
+            if *passed_type != Type::Any && !self.matching_types(param_type, passed_type) {
+                return Err(TypeError {
+                    message: format!(
+                        "Callback '{}' für '{}': Parameter {} ist vom Typ {:?}, bekommt aber {:?}",
+                        fn_name,
+                        method_path,
+                        idx + 1,
+                        param_type,
+                        passed_type
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn print_current_scope(&mut self) {
+        println!("\nCurrent Scope Variables:");
+        println!("-----------------------");
+        if let Some(current_scope) = self.scope_stack.last() {
+            for (name, var_info) in &current_scope.variables {
+                println!("{}: {:?}", name, var_info);
+            }
+        }
+    }
+
+    pub fn new() -> Self {
+        let js_stdlib = JsStdLib::new();
+        let mut checker = Self {
+            scope_stack: vec![Scope::default()],
+            type_alias: HashMap::new(),
+            current_return_type: None,
+            // top-level await works in ES modules and wrapped scripts alike
+            currently_async: true,
+            function_scope_depth: 0,
+            type_info: TypeInfo::default(),
+            warnings: Vec::new(),
+            currently_loop: false,
+            loop_exits: Vec::new(),
+            currently_constructor: false,
+            classes: HashMap::new(),
+            interfaces: HashMap::new(),
+            conformance_in_progress: RefCell::new(Vec::new()),
+            modules: HashMap::new(),
+            exported_names: Vec::new(),
+            current_statement: None,
+            class_symbols: HashMap::new(),
+            suggestion: None,
+            strict: false,
+            implicit_any: Vec::new(),
+            propagation_barrier: None,
+            js_stdlib,
+            source_map: SourceMap::default(),
+            unreached_types: HashSet::new(),
+        };
+
+        let object_names: Vec<String> = checker.js_stdlib.objects.keys().cloned().collect();
+        for obj_name in object_names {
+            let var_info = VarInfo::new(checker.js_stdlib.object_type(&obj_name), true);
+            checker.declare_variable(&obj_name, var_info);
+        }
+
+        // an explicit `any` opts out of strict mode
+        checker.type_alias.insert("any".to_string(), Type::Any);
+
+        // Error types are classes, so `catch` and `instanceof` can tell them
+        // apart and user errors can extend them. Their constructors are
+        // callable without `new`.
+        for error_type in checker.js_stdlib.error_types.clone() {
+            let parent = (error_type != "Error").then(|| "Error".to_string());
+            checker.class_symbol(&error_type);
+            checker.classes.insert(
+                error_type,
+                ClassInfo {
+                    parent,
+                    constructor: FunctionSignature::new(
+                        vec![Some("message".to_string())],
+                        vec![Type::String],
+                        vec![true],
+                        None,
+                    ),
+                    fields: JsStdLib::error_fields(),
+                    methods: HashMap::new(),
+                    static_fields: HashMap::new(),
+                    static_methods: HashMap::new(),
+                },
+            );
+        }
+        checker
+    }
+
+    pub fn type_info(&self) -> &TypeInfo {
+        &self.type_info
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// In strict mode every implicit Any is an error, a file can ask for it
+    /// with `#![strict]` as well.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    fn without_propagation<T>(
+        &mut self,
+        position: &'static str,
+        check: impl FnOnce(&mut Self) -> Result<T, TypeError>,
+    ) -> Result<T, TypeError> {
+        let outer_barrier = self.propagation_barrier.replace(position);
+        let result = check(self);
+        self.propagation_barrier = outer_barrier;
+        result
+    }
+
+    fn report_implicit_any(&mut self, reason: String) {
+        if self.strict && !self.implicit_any.contains(&reason) {
+            self.implicit_any.push(reason);
+        }
+    }
+
+    // What flows into something annotated with `any` may be unknown, the
+    // reports since `reports_before` are dropped then.
+    fn allow_implicit_any(&mut self, reports_before: usize, target_type: &Type) {
+        if self.resolve_type(target_type) == Type::Any {
+            self.implicit_any.truncate(reports_before);
+        }
+    }
+
+    /// A fix for the error `check_program` returned, if a name looked
+    /// misspelled.
+    pub fn suggestion(&self) -> Option<&Suggestion> {
+        self.suggestion.as_ref()
+    }
+
+    // Note for an error about `name`, remembers the fix if a candidate is
+    // close enough.
+    fn did_you_mean(&mut self, name: &str, candidates: &[String], kind: NameKind) -> String {
+        match closest_name(name, candidates) {
+            Some(replacement) => {
+                let note = format!(". Meintest du '{}'?", replacement);
+                self.suggestion = Some(Suggestion {
+                    name: name.to_string(),
+                    replacement,
+                    kind,
+                    address: name.as_ptr(),
+                });
+                note
+            }
+            None => String::new(),
+        }
+    }
+
+    fn variable_names(&self) -> Vec<String> {
+        self.scope_stack
+            .iter()
+            .flat_map(|scope| scope.variables.keys().chain(scope.functions.keys()))
+            .cloned()
+            .collect()
+    }
+
+    fn function_names(&self) -> Vec<String> {
+        self.scope_stack
+            .iter()
+            .flat_map(|scope| scope.functions.keys())
+            .chain(self.classes.keys())
+            .cloned()
+            .collect()
+    }
+
+    fn type_names(&self) -> Vec<String> {
+        self.type_alias
+            .keys()
+            .chain(self.classes.keys())
+            .chain(self.interfaces.keys())
+            // interfaces of declared objects are named `typeof X`, types that
+            // came along with an import `import("m").X`
+            .filter(|type_name| {
+                type_name
+                    .chars()
+                    .all(|ch| ch.is_alphanumeric() || ch == '_')
+            })
+            .cloned()
+            .collect()
+    }
+
+    // `promise.catch(...)` deals with the promise on purpose
+    fn handles_rejection(expr: &Expr) -> bool {
+        matches!(&expr.kind, ExprKind::Call { caller, .. }
+            if matches!(&caller.kind, ExprKind::Member { property, .. }
+                if matches!(&property.kind, ExprKind::Identifier(method) if method == "catch")))
+    }
+
+    fn describe_expr(expr: &Expr) -> String {
+        match &expr.kind {
+            ExprKind::Identifier(name) => name.clone(),
+            ExprKind::Call { caller, .. } => format!("{}(...)", Self::describe_expr(caller)),
+            ExprKind::Member {
+                object,
+                property,
+                computed: false,
+            } => format!(
+                "{}.{}",
+                Self::describe_expr(object),
+                Self::describe_expr(property)
+            ),
+            _ => "Ausdruck".to_string(),
+        }
+    }
+
+    /// Positions of the program's names, as recorded by its parser. Errors
+    /// only point into the source if this is set before checking.
+    pub fn set_source_map(&mut self, source_map: SourceMap) {
+        self.source_map = source_map;
+    }
+
+    pub fn register_module(&mut self, source: &str, exports: ModuleExports) {
+        self.modules.insert(source.to_string(), exports);
+    }
+
+    pub fn check_program(&mut self, programm: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::Program { body } = programm {
+            let strict_attribute = body.iter().any(|stmt| {
+                matches!(stmt, Stmt::Attribute { name, applies_to_file: true, .. } if name == "strict")
+            });
+            if strict_attribute {
+                self.strict = true;
+            }
+            // imports come first, like in an ES module
+            for stmt in body {
+                if let Stmt::Import { .. } = stmt {
+                    self.current_statement = Some(stmt as *const Stmt);
+                    self.check_import(stmt)?;
+                    self.current_statement = None;
+                }
+            }
+            self.hoist_declarations(body)?;
+            for stmt in body {
+                match stmt {
+                    Stmt::Import { .. } => {}
+                    Stmt::Export { declaration } => {
+                        self.check_statement(declaration)?;
+                        self.exported_names
+                            .extend(Self::declared_names(declaration));
+                    }
+                    _ => self.check_statement(stmt)?,
+                }
+            }
+            self.check_control_flow(body, "auf oberster Ebene");
+
+            if !self.implicit_any.is_empty() {
+                return Err(TypeError {
+                    message: format!(
+                        "Implizites Any im strikten Modus: {}. Hinweis: mit der Typangabe 'any' ist Any gewollt",
+                        self.implicit_any.join("; ")
+                    ),
+                });
+            }
+
+            let type_aliases: Vec<String> = self.type_alias.keys().cloned().collect();
+            for name in type_aliases {
+                let resolved = self.resolve_type(&Type::AliasedType(name.clone()));
+                self.type_info.type_aliases.insert(name, resolved);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_block(&mut self, stmts: &[Stmt]) -> Result<(), TypeError> {
+        self.hoist_declarations(stmts)?;
+        for stmt in stmts {
+            self.check_statement(stmt)?;
+        }
+        Ok(())
+    }
+
+    // Functions and type aliases can be used before their declaration, the
+    // emitted JS hoists `function` declarations as well.
+    fn hoist_declarations(&mut self, stmts: &[Stmt]) -> Result<(), TypeError> {
+        let declarations: Vec<&Stmt> = stmts
+            .iter()
+            .map(|stmt| match stmt {
+                Stmt::Export { declaration } => declaration.as_ref(),
+                _ => stmt,
+            })
+            .collect();
+
+        for stmt in &declarations {
+            if let Stmt::TypeAlias { .. } = stmt {
+                self.check_type_alias_stmt(stmt)?;
+            }
+        }
+        // after all of them, an alias may mention the ones declared after it
+        for stmt in &declarations {
+            if let Stmt::TypeAlias { name, aliased_type } = stmt {
+                self.declaration_symbol(stmt, name, SymbolKind::TypeAlias, aliased_type);
+            }
+        }
+
+        // classes and interfaces are known by name before their members are
+        // read, so they and the function signatures may mention each other
+        let mut classes = HashMap::new();
+        for stmt in &declarations {
+            let (Stmt::ClassDeclaration { name, .. } | Stmt::InterfaceDeclaration { name, .. }) =
+                stmt
+            else {
+                continue;
+            };
+            if self.is_known_type_name(name) {
+                return Err(TypeError {
+                    message: format!("Typ '{}' ist bereits definiert", name),
+                });
+            }
+            self.unreached_types.insert(name.clone());
+            if let Stmt::ClassDeclaration { parent, .. } = stmt {
+                let outer_statement = self.current_statement.replace(*stmt as *const Stmt);
+                self.class_symbol(name);
+                self.current_statement = outer_statement;
+                self.classes.insert(
+                    name.clone(),
+                    ClassInfo {
+                        parent: parent.clone(),
+                        constructor: FunctionSignature::new(
+                            Vec::new(),
+                            Vec::new(),
+                            Vec::new(),
+                            None,
+                        ),
+                        fields: HashMap::new(),
+                        methods: HashMap::new(),
+                        static_fields: HashMap::new(),
+                        static_methods: HashMap::new(),
+                    },
+                );
+                classes.insert(name.as_str(), *stmt);
+            } else {
+                let instance_type = Type::AliasedType(name.clone());
+                self.declaration_symbol(stmt, name, SymbolKind::Interface, &instance_type);
+                self.interfaces.insert(
+                    name.clone(),
+                    InterfaceInfo {
+                        fields: HashMap::new(),
+                        methods: HashMap::new(),
+                        optional_fields: HashSet::new(),
+                    },
+                );
+            }
+        }
+        for stmt in &declarations {
+            if let Stmt::InterfaceDeclaration { name, members } = stmt {
+                self.register_interface(name, members)?;
+            }
+        }
+        let mut hoisted_classes = HashSet::new();
+        for stmt in &declarations {
+            self.hoist_class(stmt, &classes, &mut hoisted_classes)?;
+        }
+
+        for stmt in &declarations {
+            if let Stmt::FunctionDeclaration { name, .. } = stmt {
+                self.declare_name(name, format!("Funktion '{}'", name))?;
+                let outer_statement = self.current_statement.replace(*stmt as *const Stmt);
+                let (signature, return_type) = self.declared_signature(stmt)?;
+                self.declare_function(name, signature, return_type);
+                self.current_statement = outer_statement;
+            }
+        }
+        Ok(())
+    }
+
+    // Parents are hoisted first, a class without constructor inherits theirs.
+    fn hoist_class<'a>(
+        &mut self,
+        stmt: &'a Stmt,
+        classes: &HashMap<&str, &'a Stmt>,
+        hoisted: &mut HashSet<&'a str>,
+    ) -> Result<(), TypeError> {
+        let Stmt::ClassDeclaration { name, parent, .. } = stmt else {
+            return Ok(());
+        };
+        if !hoisted.insert(name) {
+            return Ok(());
+        }
+        if let Some(parent_stmt) = parent.as_deref().and_then(|parent| classes.get(parent)) {
+            self.hoist_class(parent_stmt, classes, hoisted)?;
+        }
+        let class_info = self.class_shape(stmt, true)?;
+        self.classes.insert(name.clone(), class_info);
+        Ok(())
+    }
+
+    fn check_statement(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        let outer_statement = self.current_statement.replace(stmt as *const Stmt);
+        let result = self.check_statement_kind(stmt);
+        self.current_statement = outer_statement;
+        result
+    }
+
+    fn check_statement_kind(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        match stmt {
+            Stmt::VarDeclaration { .. } => self.check_var_declaration(stmt),
+            Stmt::FunctionDeclaration { .. } => self.check_fn_declaration(stmt),
+            Stmt::IfStatement { .. } => self.check_if_stmt(stmt),
+            Stmt::WhileStatement { .. } => self.check_while_declaration(stmt),
+            Stmt::ForLoopStatement { .. } => self.check_for_loop(stmt),
+            Stmt::ForInLoopStatement { .. } => self.check_for_in_loop(stmt),
+            Stmt::ForLoopIterated { .. } => self.check_for_iter_loop(stmt),
+            Stmt::ReturnStatement { .. } => self.check_return_stmt(stmt),
+            Stmt::TryCatchFinally { .. } => self.check_try_catch_stmt(stmt),
+            Stmt::ThrowStatement { .. } => self.check_throw_stmt(stmt),
+            Stmt::SwitchStatement { .. } => self.check_switch_stmt(stmt),
+            Stmt::ContinueStatement | Stmt::BreakStatement => self.check_loop_control_stmt(stmt),
+            // registered by `hoist_declarations`
+            Stmt::TypeAlias { .. } => Ok(()),
+            Stmt::ClassDeclaration { .. } => self.check_class_declaration(stmt),
+            Stmt::InterfaceDeclaration { .. } => self.check_interface_declaration(stmt),
+            Stmt::ImplDeclaration { .. } => self.check_impl_declaration(stmt),
+            Stmt::Declare { .. } => self.check_declare_stmt(stmt),
+            Stmt::Expression(expr) => {
+                let expr_type = self.infer_type(expr)?;
+                if matches!(self.resolve_type(&expr_type), Type::Promise(_))
+                    && !Self::handles_rejection(expr)
+                {
+                    self.warnings.push(format!(
+                        "Promise wird weder mit await erwartet noch weiterverwendet: {}",
+                        Self::describe_expr(expr)
+                    ));
+                }
+                Ok(())
+            }
+            Stmt::Import { .. } | Stmt::Export { .. } => Err(TypeError {
+                message: "import und export sind nur auf oberster Ebene erlaubt".to_string(),
+            }),
+
+            _ => Ok(()),
+        }
+    }
+    fn check_import(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::Import { names, source } = stmt {
+            let exports = self
+                .modules
+                .get(source)
+                .ok_or_else(|| TypeError {
+                    message: format!("Modul '{}' wurde nicht geladen", source),
+                })?
+                .qualified(source, names);
+
+            for name in names {
+                if self.is_known_type_name(name)
+                    || self.lookup_function(name).is_some()
+                    || self.lookup_variable(name).is_some()
+                {
+                    return Err(TypeError {
+                        message: format!("Import '{}' ist bereits definiert", name),
+                    });
+                }
+            }
+
+            // types referenced by an import may pull in later names of the list
+            for name in names {
+                self.declare_name(name, format!("Import aus '{}'", source))?;
+                let found = self.import_export(name, &exports);
+                if !found {
+                    return Err(TypeError {
+                        message: format!("Modul '{}' exportiert '{}' nicht", source, name),
+                    });
+                }
+            }
+
+            Ok(())
+        } else {
+            panic!("import expected")
+        }
+    }
+
+    // Registers one export, returns false if the module has no such export.
+    fn import_export(&mut self, name: &str, exports: &ModuleExports) -> bool {
+        let mut found = false;
+        let mut referenced_types = Vec::new();
+        if let Some(var_info) = exports.variables.get(name) {
+            // imported bindings are read-only
+            self.declare_variable(name, VarInfo::new(var_info.var_type.clone(), true));
+            referenced_types.push(var_info.var_type.clone());
+            found = true;
+        }
+        if let Some(signature) = exports.function_signatures.get(name) {
+            let return_type = exports.function_return_types[name].clone();
+            self.declare_function(name, signature.clone(), return_type.clone());
+            referenced_types.extend(Self::signature_types(signature));
+            referenced_types.push(return_type);
+            found = true;
+        }
+        if let Some(aliased_type) = exports.type_aliases.get(name) {
+            self.type_alias
+                .insert(name.to_string(), aliased_type.clone());
+            referenced_types.push(aliased_type.clone());
+            found = true;
+        }
+        if let Some(class_info) = exports.classes.get(name) {
+            self.classes.insert(name.to_string(), class_info.clone());
+            self.class_symbol(name);
+            referenced_types.extend(Self::signature_types(&class_info.constructor));
+            for fields in [&class_info.fields, &class_info.static_fields] {
+                referenced_types.extend(fields.values().cloned());
+            }
+            for methods in [&class_info.methods, &class_info.static_methods] {
+                referenced_types.extend(Self::method_types(methods));
+            }
+            found = true;
+        }
+        if let Some(interface) = exports.interfaces.get(name) {
+            self.interfaces.insert(name.to_string(), interface.clone());
+            referenced_types.extend(interface.fields.values().cloned());
+            referenced_types.extend(Self::method_types(&interface.methods));
+            found = true;
+        }
+
+        for referenced_type in &referenced_types {
+            self.import_referenced_types(referenced_type, exports);
+        }
+        found
+    }
+
+    // Types used by an import come along, so the importer can work with the
+    // values it gets without importing every interface by hand.
+    fn import_referenced_types(&mut self, checked_type: &Type, exports: &ModuleExports) {
+        match checked_type {
+            Type::AliasedType(name) if !self.is_known_type_name(name) && exports.has_type(name) => {
+                self.import_export(name, exports);
+            }
+            Type::Array(inner_type)
+            | Type::Option(inner_type)
+            | Type::Set(inner_type)
+            | Type::Iterator(inner_type)
+            | Type::Promise(inner_type) => self.import_referenced_types(inner_type, exports),
+            Type::Result(value_type, error_type) | Type::Map(value_type, error_type) => {
+                self.import_referenced_types(value_type, exports);
+                self.import_referenced_types(error_type, exports);
+            }
+            Type::Union(members) => {
+                for member in members {
+                    self.import_referenced_types(member, exports);
+                }
+            }
+            Type::Object(fields) => {
+                for field_type in fields.values() {
+                    self.import_referenced_types(field_type, exports);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn signature_types(signature: &FunctionSignature) -> Vec<Type> {
+        signature
+            .param_types
+            .iter()
+            .chain(signature.rest_type.iter())
+            .cloned()
+            .collect()
+    }
+
+    fn method_types(methods: &HashMap<String, MethodInfo>) -> Vec<Type> {
+        methods
+            .values()
+            .flat_map(|method| {
+                let mut types = Self::signature_types(&method.signature);
+                types.push(method.return_type.clone());
+                types
+            })
+            .collect()
+    }
+
+    fn declared_names(declaration: &Stmt) -> Vec<String> {
+        match declaration {
+            Stmt::FunctionDeclaration { name, .. }
+            | Stmt::ClassDeclaration { name, .. }
+            | Stmt::InterfaceDeclaration { name, .. }
+            | Stmt::TypeAlias { name, .. } => vec![name.clone()],
+            Stmt::VarDeclaration { pattern, .. } => Self::pattern_names(pattern),
+            _ => Vec::new(),
+        }
+    }
+
+    fn pattern_names(pattern: &Pattern) -> Vec<String> {
+        match pattern {
+            Pattern::Identifier(name) => vec![name.clone()],
+            Pattern::Array { elements, rest } => {
+                let mut names: Vec<String> = elements
+                    .iter()
+                    .flat_map(|element| Self::pattern_names(&element.pattern))
+                    .collect();
+                if let Some(rest) = rest {
+                    names.extend(Self::pattern_names(rest));
+                }
+                names
+            }
+            Pattern::Object { properties, rest } => {
+                let mut names: Vec<String> = properties
+                    .iter()
+                    .flat_map(|property| Self::pattern_names(&property.pattern))
+                    .collect();
+                names.extend(rest.iter().cloned());
+                names
+            }
+        }
+    }
+
+    /// Collects the exports of the last checked program.
+    pub fn exports(&self) -> ModuleExports {
+        let mut exports = ModuleExports::default();
+        for name in &self.exported_names {
+            let global_scope = &self.scope_stack[0];
+            if let Some(var_info) = global_scope.variables.get(name) {
+                exports.variables.insert(
+                    name.clone(),
+                    VarInfo::new(self.resolve_type(&var_info.var_type), var_info.is_const),
+                );
+            }
+            if let Some(function) = global_scope.functions.get(name) {
+                exports
+                    .function_signatures
+                    .insert(name.clone(), self.resolve_signature(&function.signature));
+                exports
+                    .function_return_types
+                    .insert(name.clone(), self.resolve_type(&function.return_type));
+            }
+            if let Some(aliased_type) = self.type_alias.get(name) {
+                exports
+                    .type_aliases
+                    .insert(name.clone(), self.resolve_type(aliased_type));
+            }
+            if self.classes.contains_key(name) {
+                exports
+                    .classes
+                    .insert(name.clone(), self.flattened_class(name));
+            }
+            if let Some(interface) = self.interfaces.get(name) {
+                exports.interfaces.insert(
+                    name.clone(),
+                    InterfaceInfo {
+                        fields: self.resolve_fields(&interface.fields),
+                        methods: self.resolve_methods(&interface.methods),
+                        optional_fields: interface.optional_fields.clone(),
+                    },
+                );
+            }
+        }
+        exports
+    }
+
+    fn resolve_signature(&self, signature: &FunctionSignature) -> FunctionSignature {
+        FunctionSignature::new(
+            signature.param_names.clone(),
+            signature
+                .param_types
+                .iter()
+                .map(|param_type| self.resolve_type(param_type))
+                .collect(),
+            signature.optional_params.clone(),
+            signature
+                .rest_type
+                .as_ref()
+                .map(|rest_type| self.resolve_type(rest_type)),
+        )
+    }
+
+    fn resolve_fields(&self, fields: &HashMap<String, Type>) -> HashMap<String, Type> {
+        fields
+            .iter()
+            .map(|(name, field_type)| (name.clone(), self.resolve_type(field_type)))
+            .collect()
+    }
+
+    fn resolve_methods(
+        &self,
+        methods: &HashMap<String, MethodInfo>,
+    ) -> HashMap<String, MethodInfo> {
+        methods
+            .iter()
+            .map(|(name, method)| {
+                (
+                    name.clone(),
+                    MethodInfo {
+                        signature: self.resolve_signature(&method.signature),
+                        return_type: self.resolve_type(&method.return_type),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    // Inherited members are copied into the class, its parent may not be exported.
+    fn flattened_class(&self, class_name: &str) -> ClassInfo {
+        let mut flattened = self.classes[class_name].clone();
+        let mut ancestor = flattened.parent.clone();
+        while let Some(ancestor_name) = ancestor {
+            let ancestor_info = &self.classes[&ancestor_name];
+            for (name, field_type) in &ancestor_info.fields {
+                flattened
+                    .fields
+                    .entry(name.clone())
+                    .or_insert_with(|| field_type.clone());
+            }
+            for (name, method) in &ancestor_info.methods {
+                flattened
+                    .methods
+                    .entry(name.clone())
+                    .or_insert_with(|| method.clone());
+            }
+            for (name, field_type) in &ancestor_info.static_fields {
+                flattened
+                    .static_fields
+                    .entry(name.clone())
+                    .or_insert_with(|| field_type.clone());
+            }
+            for (name, method) in &ancestor_info.static_methods {
+                flattened
+                    .static_methods
+                    .entry(name.clone())
+                    .or_insert_with(|| method.clone());
+            }
+            ancestor = ancestor_info.parent.clone();
+        }
+
+        flattened.constructor = self.resolve_signature(&flattened.constructor);
+        flattened.fields = self.resolve_fields(&flattened.fields);
+        flattened.methods = self.resolve_methods(&flattened.methods);
+        flattened.static_fields = self.resolve_fields(&flattened.static_fields);
+        flattened.static_methods = self.resolve_methods(&flattened.static_methods);
+        flattened
+    }
+
+    fn check_var_declaration(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::VarDeclaration {
+            pattern,
+            value: Some(value),
+            var_type,
+            constant,
+        } = stmt
+        {
+            let reports_before = self.implicit_any.len();
+            let final_type = if *var_type == Type::Any {
+                Type::Any
+            } else if let Type::AliasedType(name) = var_type {
+                if !self.is_known_type_name(name) {
+                    let note = self.did_you_mean(name, &self.type_names(), NameKind::Type);
+                    return Err(TypeError {
+                        message: format!("Unknown type alias: {}{}", name, note),
+                    });
+                }
+                self.resolve_type(var_type)
+            } else {
+                self.resolve_type(var_type)
+            };
+
+            // Constants keep the literal type of their initializer, `let` widens it
+            let expr_type = if final_type != Type::Any {
+                self.infer_contextual_type(value, &final_type)?
+            } else if *constant {
+                match Self::literal_type(value) {
+                    Some(literal_type) => literal_type,
+                    None => self.infer_type(value)?,
+                }
+            } else {
+                let inferred_type = self.infer_type(value)?;
+                self.widen_type(&inferred_type)
+            };
+            let final_type = if *var_type != Type::Any && final_type == Type::Any {
+                // annotated `any` on purpose
+                self.allow_implicit_any(reports_before, &final_type);
+                final_type
+            } else if final_type == Type::Any {
+                expr_type.clone()
+            } else {
+                final_type
+            };
+
+            if self.matching_types(&final_type, &expr_type) {
+                self.bind_pattern(pattern, &final_type, *constant)
+            } else {
+                Err(TypeError {
+                    message: format!(
+                        "Expected {:?}, got {:?}{}",
+                        final_type,
+                        expr_type,
+                        self.mismatch_note(&final_type, &expr_type)
+                    ),
+                })
+            }
+        } else if let Stmt::VarDeclaration {
+            pattern: Pattern::Identifier(name),
+            value: None,
+            var_type,
+            constant,
+        } = stmt
+        {
+            if let Type::AliasedType(alias) = var_type {
+                if !self.is_known_type_name(alias) {
+                    let note = self.did_you_mean(alias, &self.type_names(), NameKind::Type);
+                    return Err(TypeError {
+                        message: format!("Unknown type alias: {}{}", alias, note),
+                    });
+                }
+            }
+            if name == "self" || name == "super" {
+                return Err(TypeError {
+                    message: format!("'{}' kann nicht als Variablenname verwendet werden", name),
+                });
+            }
+            if *var_type == Type::Any {
+                self.report_implicit_any(format!(
+                    "Variable '{}' hat weder Typangabe noch Wert",
+                    name
+                ));
+            }
+            let var_type = self.resolve_type(var_type);
+            self.declare_name(name, Self::describe_binding(&var_type, *constant))?;
+            self.declare_variable(name, VarInfo::unassigned(var_type, *constant));
+            Ok(())
+        } else {
+            panic!("Var declaration expected")
+        }
+    }
+
+    fn bind_pattern(
+        &mut self,
+        pattern: &Pattern,
+        value_type: &Type,
+        is_const: bool,
+    ) -> Result<(), TypeError> {
+        let value_type = self.resolve_type(value_type);
+        match pattern {
+            Pattern::Identifier(name) => {
+                if name == "self" || name == "super" {
+                    return Err(TypeError {
+                        message: format!(
+                            "'{}' kann nicht als Variablenname verwendet werden",
+                            name
+                        ),
+                    });
+                }
+                self.declare_name(name, Self::describe_binding(&value_type, is_const))?;
+                self.declare_variable(name, VarInfo::new(value_type, is_const));
+                Ok(())
+            }
+            Pattern::Array { elements, rest } => {
+                let element_type = match &value_type {
+                    Type::Array(element_type) => (**element_type).clone(),
+                    Type::Any => Type::Any,
+                    _ => {
+                        return Err(TypeError {
+                            message: format!(
+                                "Array-Destructuring erwartet ein Array, bekam {:?}",
+                                value_type
+                            ),
+                        })
+                    }
+                };
+
+                for element in elements {
+                    let binding_type =
+                        self.binding_type(Some(element_type.clone()), &element.default_value)?;
+                    self.bind_pattern(&element.pattern, &binding_type, is_const)?;
+                }
+                if let Some(rest) = rest {
+                    self.bind_pattern(rest, &Type::Array(Box::new(element_type)), is_const)?;
+                }
+                Ok(())
+            }
+            Pattern::Object { properties, rest } => {
+                let fields = match &value_type {
+                    Type::Object(fields) => Some(fields.clone()),
+                    Type::Any => None,
+                    _ => {
+                        return Err(TypeError {
+                            message: format!(
+                                "Objekt-Destructuring erwartet ein Objekt, bekam {:?}",
+                                value_type
+                            ),
+                        })
+                    }
+                };
+
+                for property in properties {
+                    let property_type = match &fields {
+                        Some(fields) => fields.get(&property.key).cloned(),
+                        None => Some(Type::Any),
+                    };
+                    if property_type.is_none() && property.default_value.is_none() {
+                        let field_names: Vec<String> = fields
+                            .iter()
+                            .flat_map(|fields| fields.keys())
+                            .cloned()
+                            .collect();
+                        let note =
+                            self.did_you_mean(&property.key, &field_names, NameKind::Property);
+                        return Err(TypeError {
+                            message: format!(
+                                "Property '{}' does not exist on this object{}",
+                                property.key, note
+                            ),
+                        });
+                    }
+                    let binding_type = self.binding_type(property_type, &property.default_value)?;
+                    self.bind_pattern(&property.pattern, &binding_type, is_const)?;
+                }
+                if let Some(rest) = rest {
+                    let rest_type = match fields {
+                        Some(mut fields) => {
+                            for property in properties {
+                                fields.remove(&property.key);
+                            }
+                            Type::Object(fields)
+                        }
+                        None => Type::Any,
+                    };
+                    self.declare_name(rest, Self::describe_binding(&rest_type, is_const))?;
+                    self.declare_variable(rest, VarInfo::new(rest_type, is_const));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // Type of a destructured binding; `None` means the value is missing and
+    // only the default can provide one.
+    fn binding_type(
+        &mut self,
+        value_type: Option<Type>,
+        default_value: &Option<Expr>,
+    ) -> Result<Type, TypeError> {
+        match (value_type, default_value) {
+            (Some(value_type), None) => Ok(value_type),
+            (Some(value_type), Some(default_value)) => {
+                let expected_type = match self.resolve_type(&value_type) {
+                    Type::Option(inner_type) => *inner_type,
+                    resolved => resolved,
+                };
+                let default_type = self.without_propagation("einem Standardwert", |checker| {
+                    checker.infer_contextual_type(default_value, &expected_type)
+                })?;
+                if !self.matching_types(&expected_type, &default_type) {
+                    return Err(TypeError {
+                        message: format!(
+                            "Default value of type {:?} does not match {:?}",
+                            default_type, expected_type
+                        ),
+                    });
+                }
+                Ok(expected_type)
+            }
+            (None, Some(default_value)) => {
+                let default_type = self.without_propagation("einem Standardwert", |checker| {
+                    checker.infer_type(default_value)
+                })?;
+                Ok(self.widen_type(&default_type))
+            }
+            (None, None) => Ok(Type::Any),
+        }
+    }
+
+    fn check_fn_declaration(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        self.check_function(stmt, true)?;
+        Ok(())
+    }
+
+    // Calling an async function gives a promise, its body returns the value
+    // the promise resolves to. Both `-> T` and `-> Promise<T>` are accepted.
+    fn call_return_type(return_type: &Type, is_async: bool) -> Type {
+        match return_type {
+            Type::Promise(_) => return_type.clone(),
+            _ if is_async => Type::Promise(Box::new(return_type.clone())),
+            _ => return_type.clone(),
+        }
+    }
+
+    fn body_return_type(return_type: &Type, is_async: bool) -> Type {
+        match return_type {
+            Type::Promise(inner) if is_async => (**inner).clone(),
+            _ => return_type.clone(),
+        }
+    }
+
+    // Checks parameters and body of a function or method. Free functions get
+    // registered before their body is checked so they can call themselves.
+    fn check_function(
+        &mut self,
+        stmt: &Stmt,
+        register: bool,
+    ) -> Result<FunctionSignature, TypeError> {
+        if let Stmt::FunctionDeclaration {
+            name,
+            parameters,
+            param_types,
+            param_defaults,
+            has_rest_parameter,
+            body,
+            return_type,
+            is_async,
+        } = stmt
+        {
+            let outer_statement = self.current_statement.replace(stmt as *const Stmt);
+            // nested functions must not leak their context into the outer one
+            let outer_async = std::mem::replace(&mut self.currently_async, *is_async);
+            let outer_states = self.assignment_states();
+            let outer_scope_depth =
+                std::mem::replace(&mut self.function_scope_depth, self.scope_stack.len());
+            let body_return_type = Self::body_return_type(return_type, *is_async);
+            let body_return_type = self.get_type(&body_return_type)?;
+            let outer_return_type = self.current_return_type.replace(body_return_type);
+            // `break` and `continue` cannot leave the function
+            let outer_loop = std::mem::replace(&mut self.currently_loop, false);
+            let declaring_scope = self.scope_stack.len() - 1;
+            self.enter_scope();
+
+            let fixed_count = if *has_rest_parameter {
+                parameters.len() - 1
+            } else {
+                parameters.len()
+            };
+            let mut param_names = Vec::new();
+            let mut fixed_param_types = Vec::new();
+            let mut optional_params = Vec::new();
+            for idx in 0..fixed_count {
+                let mut param_type = param_types[idx].clone();
+                let has_default = !matches!(
+                    param_defaults[idx],
+                    None | Some(Expr {
+                        kind: ExprKind::NullLiteral,
+                        ..
+                    })
+                );
+                if param_type == Type::Any && !has_default {
+                    self.report_implicit_any(format!(
+                        "Parameter {} von Funktion '{}' hat keine Typangabe",
+                        idx + 1,
+                        name
+                    ));
+                }
+                if let Some(default_value) = &param_defaults[idx] {
+                    let default_type = self
+                        .without_propagation("einem Standardwert", |checker| {
+                            checker.infer_contextual_type(default_value, &param_type)
+                        })?;
+                    if param_type == Type::Any && default_type != Type::Null {
+                        param_type = self.widen_type(&default_type);
+                    } else if !self.matching_types(&param_type, &default_type) {
+                        return Err(TypeError {
+                            message: format!(
+                                "Default-Wert von Parameter {} sollte vom Typ {:?} sein, ist aber {:?}",
+                                idx + 1,
+                                param_type,
+                                default_type
+                            ),
+                        });
+                    }
+                }
+                self.bind_pattern(&parameters[idx], &param_type, true)?;
+
+                param_names.push(match &parameters[idx] {
+                    Pattern::Identifier(param_name) => Some(param_name.clone()),
+                    _ => None,
+                });
+                fixed_param_types.push(param_type);
+                optional_params.push(param_defaults[idx].is_some());
+            }
+
+            let rest_type = if *has_rest_parameter {
+                if param_types[fixed_count] == Type::Any {
+                    self.report_implicit_any(format!(
+                        "Rest-Parameter von Funktion '{}' hat keine Typangabe",
+                        name
+                    ));
+                }
+                let rest_type = match self.resolve_type(&param_types[fixed_count]) {
+                    Type::Array(element_type) => *element_type,
+                    Type::Any => Type::Any,
+                    other => {
+                        return Err(TypeError {
+                            message: format!(
+                                "Rest-Parameter muss vom Typ array sein, ist aber {:?}",
+                                other
+                            ),
+                        })
+                    }
+                };
+                self.bind_pattern(
+                    &parameters[fixed_count],
+                    &Type::Array(Box::new(rest_type.clone())),
+                    true,
+                )?;
+                Some(rest_type)
+            } else {
+                None
+            };
+
+            let signature =
+                FunctionSignature::new(param_names, fixed_param_types, optional_params, rest_type);
+            if register {
+                let call_return_type = Self::call_return_type(return_type, *is_async);
+                self.function_symbol(declaring_scope, name, &call_return_type);
+                self.scope_stack[declaring_scope].functions.insert(
+                    name.clone(),
+                    MethodInfo {
+                        signature: signature.clone(),
+                        return_type: Self::call_return_type(return_type, *is_async),
+                    },
+                );
+            }
+
+            self.check_block(body)?;
+
+            let completion = self.check_control_flow(body, &format!("in Funktion '{}'", name));
+            let returns_value = !matches!(
+                self.current_return_type
+                    .as_ref()
+                    .map(|t| self.resolve_type(t)),
+                None | Some(Type::Void) | Some(Type::Any)
+            );
+            if returns_value && completion == Completion::Normal {
+                return Err(TypeError {
+                    message: format!("Not all paths of function '{}' return a value", name),
+                });
+            }
+
+            self.exit_scope();
+
+            self.current_return_type = outer_return_type;
+            self.currently_async = outer_async;
+            self.currently_loop = outer_loop;
+            // the body runs later, its assignments say nothing about this point
+            self.function_scope_depth = outer_scope_depth;
+            self.set_states(&outer_states);
+            self.current_statement = outer_statement;
+
+            Ok(signature)
+        } else {
+            panic!("function declaration expected")
+        }
+    }
+
+    // Warns about statements that can never run and tells whether the end of
+    // the block can be reached. Nested functions are analysed on their own.
+    fn check_control_flow(&mut self, stmts: &[Stmt], context: &str) -> Completion {
+        self.block_completion(stmts, Some(context))
+    }
+
+    // Without a context nothing is reported, for blocks looked at again.
+    fn block_completion(&mut self, stmts: &[Stmt], context: Option<&str>) -> Completion {
+        for (idx, stmt) in stmts.iter().enumerate() {
+            let completion = self.statement_completion(stmt, context);
+            if completion == Completion::Normal {
+                continue;
+            }
+            // hoisted declarations are not code that runs
+            let unreachable_code = stmts[idx + 1..].iter().any(|later| {
+                !matches!(
+                    later,
+                    Stmt::FunctionDeclaration { .. }
+                        | Stmt::TypeAlias { .. }
+                        | Stmt::InterfaceDeclaration { .. }
+                        | Stmt::Attribute { .. }
+                )
+            });
+            if let (Some(context), true) = (context, unreachable_code) {
+                let exit = match stmt {
+                    Stmt::ReturnStatement { .. } => "'return'",
+                    Stmt::ThrowStatement { .. } => "'throw'",
+                    Stmt::BreakStatement => "'break'",
+                    Stmt::ContinueStatement => "'continue'",
+                    _ => "einer Anweisung, die nie normal endet",
+                };
+                self.warnings
+                    .push(format!("Unerreichbarer Code nach {} {}", exit, context));
+            }
+            return completion;
+        }
+        Completion::Normal
+    }
+
+    fn statement_completion(&mut self, stmt: &Stmt, context: Option<&str>) -> Completion {
+        match stmt {
+            Stmt::ReturnStatement { .. } | Stmt::ThrowStatement { .. } => Completion::Return,
+            Stmt::BreakStatement | Stmt::ContinueStatement => Completion::Break,
+            Stmt::IfStatement {
+                then_branch,
+                else_if_branches,
+                else_branch,
+                ..
+            } => {
+                let mut completion = self.block_completion(then_branch, context);
+                for branch in else_if_branches.iter().flatten() {
+                    completion = completion.either(self.block_completion(&branch.body, context));
+                }
+                match else_branch {
+                    Some(else_branch) => {
+                        completion.either(self.block_completion(else_branch, context))
+                    }
+                    None => Completion::Normal,
+                }
+            }
+            Stmt::WhileStatement { condition, body } => {
+                self.block_completion(body, context);
+                let runs_forever = matches!(&condition.kind, ExprKind::BooleanLiteral(true));
+                Self::loop_completion(runs_forever, body)
+            }
+            Stmt::ForLoopStatement {
+                condition, body, ..
+            } => {
+                self.block_completion(body, context);
+                let runs_forever = matches!(
+                    condition,
+                    None | Some(Expr {
+                        kind: ExprKind::BooleanLiteral(true),
+                        ..
+                    })
+                );
+                Self::loop_completion(runs_forever, body)
+            }
+            Stmt::ForLoopIterated { body, .. } | Stmt::ForInLoopStatement { body, .. } => {
+                self.block_completion(body, context);
+                Completion::Normal
+            }
+            Stmt::SwitchStatement {
+                case_branches,
+                default_branch,
+                ..
+            } => {
+                // `break` leaves the switch, not the function. A switch without
+                // default has been checked to be exhaustive.
+                let mut leaves_switch = true;
+                for branch in case_branches {
+                    leaves_switch &=
+                        self.block_completion(&branch.body, context) == Completion::Return;
+                }
+                if let Some(default_branch) = default_branch {
+                    leaves_switch &=
+                        self.block_completion(default_branch, context) == Completion::Return;
+                }
+                if leaves_switch {
+                    Completion::Return
+                } else {
+                    Completion::Normal
+                }
+            }
+            Stmt::TryCatchFinally {
+                try_branch,
+                catch_clauses,
+                finally_branch,
+            } => {
+                let mut completion = self.block_completion(try_branch, context);
+                for clause in catch_clauses {
+                    completion = completion.either(self.block_completion(&clause.body, context));
+                }
+                // a finally block that never ends normally overrides everything else
+                match finally_branch {
+                    Some(finally_branch) => match self.block_completion(finally_branch, context) {
+                        Completion::Normal => completion,
+                        finally_completion => finally_completion,
+                    },
+                    None => completion,
+                }
+            }
+            _ => Completion::Normal,
+        }
+    }
+
+    fn loop_completion(runs_forever: bool, body: &[Stmt]) -> Completion {
+        if runs_forever && !Self::contains_break(body) {
+            Completion::Return
+        } else {
+            Completion::Normal
+        }
+    }
+
+    // `break` of this loop, the ones of nested loops and switches do not count
+    fn contains_break(stmts: &[Stmt]) -> bool {
+        stmts.iter().any(|stmt| match stmt {
+            Stmt::BreakStatement => true,
+            Stmt::IfStatement {
+                then_branch,
+                else_if_branches,
+                else_branch,
+                ..
+            } => {
+                Self::contains_break(then_branch)
+                    || else_if_branches
+                        .iter()
+                        .flatten()
+                        .any(|branch| Self::contains_break(&branch.body))
+                    || else_branch
+                        .as_ref()
+                        .is_some_and(|else_branch| Self::contains_break(else_branch))
+            }
+            Stmt::TryCatchFinally {
+                try_branch,
+                catch_clauses,
+                finally_branch,
+            } => {
+                Self::contains_break(try_branch)
+                    || catch_clauses
+                        .iter()
+                        .any(|clause| Self::contains_break(&clause.body))
+                    || finally_branch
+                        .as_ref()
+                        .is_some_and(|finally_branch| Self::contains_break(finally_branch))
+            }
+            _ => false,
+        })
+    }
+
+    fn check_while_declaration(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::WhileStatement { condition, body } = stmt {
+            self.currently_loop = true;
+            let cond_type = self.infer_type(condition)?;
+
+            if !self.matching_types(&Type::Boolean, &cond_type) {
+                return Err(TypeError {
+                    message: format!("While condition must be boolean, got {:?}", cond_type),
+                });
+            }
+
+            let before = self.enter_loop();
+            self.enter_scope();
+            self.check_block(body)?;
+            self.exit_scope();
+            let runs_forever = matches!(&condition.kind, ExprKind::BooleanLiteral(true));
+            self.finish_loop(&before, runs_forever)?;
+            self.currently_loop = false;
+            Ok(())
+        } else {
+            Err(TypeError {
+                message: "Expected while statement".to_string(),
+            })
+        }
+    }
+
+    fn check_if_stmt(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::IfStatement {
+            condition,
+            else_branch,
+            else_if_branches,
+            then_branch,
+        } = stmt
+        {
+            let cond_type = self.infer_type(condition)?;
+
+            if !self.matching_types(&Type::Boolean, &cond_type) {
+                return Err(TypeError {
+                    message: format!("If condition must be boolean, got {:?}", cond_type),
+                });
+            }
+
+            let before = self.assignment_states();
+            let mut paths = Vec::new();
+            self.check_branch(&before, then_branch, &mut paths)?;
+
+            if let Some(else_if) = else_if_branches {
+                for branch in else_if {
+                    let branch_cond_type = self.infer_type(&branch.condition)?;
+
+                    if !self.matching_types(&Type::Boolean, &branch_cond_type) {
+                        return Err(TypeError {
+                            message: format!(
+                                "Else-if condition must be boolean, got {:?}",
+                                branch_cond_type
+                            ),
+                        });
+                    }
+
+                    self.check_branch(&before, &branch.body, &mut paths)?;
+                }
+            }
+
+            match else_branch {
+                Some(else_stmts) => {
+                    self.check_branch(&before, else_stmts, &mut paths)?;
+                }
+                None => paths.push(before.clone()),
+            }
+            self.merge_states(&before, &paths);
+            Ok(())
+        } else {
+            Err(TypeError {
+                message: "Expected if statement".to_string(),
+            })
+        }
+    }
+
+    fn check_for_loop(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::ForLoopStatement {
+            initializer,
+            condition,
+            update,
+            body,
+        } = stmt
+        {
+            self.currently_loop = true;
+            self.enter_scope();
+
+            if let Some(init) = initializer {
+                self.check_statement(init)?;
+            }
+            if let Some(cond) = condition {
+                let cond_type = self.infer_type(cond)?;
+                if !self.matching_types(&Type::Boolean, &cond_type) {
+                    return Err(TypeError {
+                        message: format!("For loop condition must be boolean, got {:?}", cond),
+                    });
+                }
+            }
+
+            let before = self.enter_loop();
+            self.check_block(body)?;
+            if let Some(update_expr) = update {
+                self.infer_type(update_expr)?;
+            }
+            let runs_forever = matches!(
+                condition,
+                None | Some(Expr {
+                    kind: ExprKind::BooleanLiteral(true),
+                    ..
+                })
+            );
+            self.finish_loop(&before, runs_forever)?;
+
+            self.exit_scope();
+            self.currently_loop = false;
+            Ok(())
+        } else {
+            Err(TypeError {
+                message: "Expected for loop statement".to_string(),
+            })
+        }
+    }
+
+    fn check_for_in_loop(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::ForInLoopStatement {
+            iterable,
+            iterator,
+            body,
+        } = stmt
+        {
+            self.currently_loop = true;
+            let iter_type = if let Some(itera) = iterable {
+                let iter_type = self.infer_type(itera)?;
+                self.resolve_type(&iter_type)
+            } else {
+                panic!("iteratable needed")
+            };
+            let element_type = match &iter_type {
+                Type::Array(element_type)
+                | Type::Set(element_type)
+                | Type::Iterator(element_type) => (**element_type).clone(),
+                Type::Map(key_type, value_type) => js_stdlib::map_entry_type(key_type, value_type),
+                Type::Any => Type::Any,
+                _ => {
+                    return Err(TypeError {
+                        message: "iterable must be of type array, map, set or iterator".to_string(),
+                    })
+                }
+            };
+
+            // the loop variable belongs to the loop, not the enclosing block
+            self.enter_scope();
+            if let Some(iter) = iterator {
+                if let Stmt::VarDeclaration { pattern, .. } = iter.as_ref() {
+                    match (pattern, &iter_type) {
+                        // `for (let [key, value] in map)` keeps both types apart
+                        (
+                            Pattern::Array {
+                                elements,
+                                rest: None,
+                            },
+                            Type::Map(key_type, value_type),
+                        ) if elements.len() == 2 => {
+                            for (element, element_type) in
+                                elements.iter().zip([key_type, value_type])
+                            {
+                                self.bind_pattern(&element.pattern, element_type, true)?;
+                            }
+                        }
+                        _ => self.bind_pattern(pattern, &element_type, true)?,
+                    }
+                }
+            } else {
+                panic!("iterator needed");
+            }
+
+            let before = self.enter_loop();
+            self.enter_scope();
+            self.check_block(body)?;
+            self.exit_scope();
+            self.exit_scope();
+            self.finish_loop(&before, false)?;
+            self.currently_loop = false;
+            Ok(())
+        } else {
+            Err(TypeError {
+                message: "Expected for-in loop statement".to_string(),
+            })
+        }
+    }
+
+    fn check_for_iter_loop(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::ForLoopIterated {
+            body,
+            iterator_name,
+            first_number,
+            ..
+        } = stmt
+        {
+            self.enter_scope();
+
+            if let Some(first_number) = first_number {
+                let first_type = self.infer_contextual_type(first_number, &Type::Number)?;
+                if !self.matching_types(&Type::Number, &first_type) {
+                    return Err(TypeError {
+                        message: format!(
+                            "Expected {:?}, got {:?}{}",
+                            Type::Number,
+                            first_type,
+                            self.mismatch_note(&Type::Number, &first_type)
+                        ),
+                    });
+                }
+            }
+            // declared by the loop statement itself
+            if let Some(iter) = iterator_name {
+                self.declare_name(iter, Self::describe_binding(&Type::Number, true))?;
+                self.declare_variable(iter, VarInfo::new(Type::Number, true));
+            }
+
+            let before = self.enter_loop();
+            self.check_block(body)?;
+            self.finish_loop(&before, false)?;
+
+            self.exit_scope();
+
+            Ok(())
+        } else {
+            panic!("For iter loop expected ")
         }
     }
 
@@ -484,430 +3197,1692 @@ impl TypeChecker {
             if let Some(expected_value_type) = self.current_return_type.clone() {
                 match value {
                     Some(expr) => {
-                        let actual_return_type = self.infer_type(expr)?;
+                        let reports_before = self.implicit_any.len();
+                        let actual_return_type =
+                            self.infer_contextual_type(expr, &expected_value_type)?;
+                        self.allow_implicit_any(reports_before, &expected_value_type);
+
+                        if !self.matching_types(&expected_value_type, &actual_return_type) {
+                            return Err(TypeError {
+                                message: format!(
+                                    "Return type mismatch: expected {:?}, but got {:?}{}",
+                                    expected_value_type,
+                                    actual_return_type,
+                                    self.mismatch_note(&expected_value_type, &actual_return_type)
+                                ),
+                            });
+                        }
+                    }
+                    None => {
+                        if expected_value_type != Type::Void {
+                            return Err(TypeError {
+                                message: format!(
+                                    "Return type mismatch: expected {:?}, but function returns nothing (Void)",
+                                    expected_value_type
+                                ),
+                            });
+                        }
+                    }
+                }
+            } else {
+                return Err(TypeError {
+                    message: "Return statement outside of function".to_string(),
+                });
+            }
+
+            Ok(())
+        } else {
+            panic!("Return statement expected")
+        }
+    }
+
+    fn check_try_catch_stmt(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::TryCatchFinally {
+            try_branch,
+            catch_clauses,
+            finally_branch,
+        } = stmt
+        {
+            let before = self.assignment_states();
+            let mut paths = Vec::new();
+            self.check_branch(&before, try_branch, &mut paths)?;
+            // a catch clause may start anywhere within the try block
+            let after_try = self.current_states(&before);
+            self.merge_states(&before, &[before.clone(), after_try]);
+            let catch_start = self.current_states(&before);
+            let mut all_ends = vec![self.current_states(&before)];
+
+            let mut has_catch_all = false;
+            for catch_clause in catch_clauses {
+                if has_catch_all {
+                    return Err(TypeError {
+                        message: "Catch-Klausel ohne Typ muss die letzte sein".to_string(),
+                    });
+                }
+
+                let binding_type = match &catch_clause.error_type {
+                    Some(error_type) => {
+                        let is_error_type = matches!(
+                            error_type,
+                            Type::AliasedType(name) if self.is_subclass(name, "Error")
+                        );
+                        if !is_error_type {
+                            return Err(TypeError {
+                                message: format!(
+                                    "Catch-Typ muss ein Error-Typ sein, bekam {:?}",
+                                    error_type
+                                ),
+                            });
+                        }
+                        error_type.clone()
+                    }
+                    None => {
+                        has_catch_all = true;
+                        Type::AliasedType("Error".to_string())
+                    }
+                };
+
+                self.enter_scope();
+                if let Some(binding) = &catch_clause.binding {
+                    self.declare_variable(binding, VarInfo::new(binding_type, false));
+                }
+                self.check_branch(&catch_start, &catch_clause.body, &mut paths)?;
+                all_ends.push(self.current_states(&before));
+                self.exit_scope();
+            }
+
+            if let Some(finally_branch) = finally_branch {
+                // finally runs after whichever of the blocks ran
+                self.merge_states(&before, &all_ends);
+                let finally_start = self.current_states(&before);
+                self.enter_scope();
+                self.check_block(finally_branch)?;
+                self.exit_scope();
+                let after_finally = self.current_states(&before);
+                for path in paths.iter_mut() {
+                    for (state, ((_, _, start), (_, _, end))) in path
+                        .iter_mut()
+                        .zip(finally_start.iter().zip(&after_finally))
+                    {
+                        if start != end {
+                            state.2 = *end;
+                        }
+                    }
+                }
+            }
+            self.merge_states(&before, &paths);
+
+            Ok(())
+        } else {
+            panic!("Try catch stmt expected")
+        }
+    }
+
+    fn check_throw_stmt(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::ThrowStatement { value } = stmt {
+            let thrown_type = self.infer_type(value)?;
+            if !self.matching_types(&Type::AliasedType("Error".to_string()), &thrown_type) {
+                return Err(TypeError {
+                    message: format!("throw erwartet einen Error, bekam {:?}", thrown_type),
+                });
+            }
+            Ok(())
+        } else {
+            panic!("Throw statement expected")
+        }
+    }
+
+    fn check_switch_stmt(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::SwitchStatement {
+            condition,
+            case_branches,
+            default_branch,
+        } = stmt
+        {
+            let condition_type = self.infer_type(condition)?;
+            let condition_type = self.resolve_type(&condition_type);
 
-                        if !self.matching_types(&expected_value_type, &actual_return_type) {
+            let before = self.assignment_states();
+            self.loop_exits.push((Vec::new(), Vec::new()));
+            let mut paths = Vec::new();
+            let mut covered_cases = Vec::new();
+            for case_branch in case_branches {
+                let case_type =
+                    self.infer_contextual_type(&case_branch.condition, &condition_type)?;
+                if !self.matching_types(&condition_type, &case_type)
+                    && !self.matching_types(&case_type, &condition_type)
+                {
+                    return Err(TypeError {
+                        message: format!(
+                            "Case {:?} passt nicht zum Switch-Typ {:?}",
+                            case_type, condition_type
+                        ),
+                    });
+                }
+                covered_cases.push(case_type);
+
+                self.check_branch(&before, &case_branch.body, &mut paths)?;
+            }
+
+            match default_branch {
+                Some(default_branch) => {
+                    self.check_branch(&before, default_branch, &mut paths)?;
+                }
+                None => match self.finite_members(&condition_type) {
+                    Some(members) => {
+                        let missing_cases: Vec<Type> = members
+                            .into_iter()
+                            .filter(|member| !covered_cases.contains(member))
+                            .collect();
+                        if !missing_cases.is_empty() {
                             return Err(TypeError {
                                 message: format!(
-                                    "Return type mismatch: expected {:?}, but got {:?}",
-                                    expected_value_type, actual_return_type
+                                    "Switch is not exhaustive, missing cases: {:?}",
+                                    missing_cases
                                 ),
                             });
                         }
                     }
                     None => {
-                        if expected_value_type != Type::Void {
-                            return Err(TypeError {
-                                message: format!(
-                                    "Return type mismatch: expected {:?}, but function returns nothing (Void)",
-                                    expected_value_type
-                                ),
-                            });
-                        }
+                        return Err(TypeError {
+                            message: format!(
+                                "Switch over non-literal type {:?} needs a default branch",
+                                condition_type
+                            ),
+                        });
                     }
+                },
+            }
+            self.loop_exits.pop();
+            self.merge_states(&before, &paths);
+
+            Ok(())
+        } else {
+            panic!("Expected swiotcvh stmt");
+        }
+    }
+
+    fn check_loop_control_stmt(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if !self.currently_loop {
+            return Err(TypeError {
+                message: "Loop control cant be used outside loops".to_string(),
+            });
+        }
+        if let Stmt::BreakStatement = stmt {
+            if let Some((loop_before, mut exits)) = self.loop_exits.pop() {
+                exits.push(self.current_states(&loop_before));
+                self.loop_exits.push((loop_before, exits));
+            }
+        }
+        self.currently_loop = false;
+        Ok(())
+    }
+
+    fn check_type_alias_stmt(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::TypeAlias { name, aliased_type } = stmt {
+            if self.is_known_type_name(name) {
+                return Err(TypeError {
+                    message: format!("Typ '{}' ist bereits definiert", name),
+                });
+            }
+            self.type_alias.insert(name.clone(), aliased_type.clone());
+
+            Ok(())
+        } else {
+            panic!("type alias stmt expected");
+        }
+    }
+
+    fn infer_type(&mut self, expr: &Expr) -> Result<Type, TypeError> {
+        let inferred = self.infer_expr_type(expr)?;
+        self.record_type(expr, &inferred);
+        Ok(inferred)
+    }
+
+    fn record_type(&mut self, expr: &Expr, expr_type: &Type) {
+        let resolved = self.resolve_type(expr_type);
+        self.type_info.expression_types.insert(expr.id, resolved);
+    }
+
+    fn record_variable(&mut self, expr: &Expr, name: &str) {
+        let id = self
+            .lookup_variable_scope(name)
+            .and_then(|depth| self.scope_stack[depth].variable_symbols.get(name).copied());
+        if let Some(id) = id {
+            self.add_use(expr, id);
+        }
+    }
+
+    fn record_function(&mut self, expr: &Expr, name: &str) {
+        let id = self
+            .scope_stack
+            .iter()
+            .rev()
+            .find(|scope| scope.functions.contains_key(name))
+            .and_then(|scope| scope.function_symbols.get(name).copied());
+        if let Some(id) = id {
+            self.add_use(expr, id);
+        }
+    }
+
+    fn infer_expr_type(&mut self, expr: &Expr) -> Result<Type, TypeError> {
+        match &expr.kind {
+            ExprKind::NumericLiteral(_) => Ok(Type::Number),
+            ExprKind::StringLiteral(_) => Ok(Type::String),
+            ExprKind::BooleanLiteral(_) => Ok(Type::Boolean),
+            ExprKind::NullLiteral => Ok(Type::Null),
+            ExprKind::Identifier(name) => {
+                self.check_variable_read(name)?;
+                self.record_variable(expr, name);
+                if let Some(var_info) = self.lookup_variable(name) {
+                    Ok(var_info.clone().var_type)
+                } else {
+                    let note = self.did_you_mean(name, &self.variable_names(), NameKind::Variable);
+                    Err(TypeError {
+                        message: format!("Nicht deklarierte Variable: {}{}", name, note),
+                    })
                 }
-            } else {
+            }
+
+            ExprKind::ArrayLiteral(..) => self.check_array_literal(expr),
+            ExprKind::ObjectLiteral(..) => self.check_object_literal(expr, None),
+
+            ExprKind::Member { .. } => self.check_member_expr(expr),
+
+            ExprKind::Call { .. } => self.check_call_expr(expr),
+
+            ExprKind::Assignment { .. } => {
+                self.check_assignment(expr)?;
+                Ok(Type::Any)
+            }
+
+            ExprKind::Unary { .. } => self.check_unary_expr(expr),
+
+            ExprKind::AwaitExpression { .. } => self.check_await_expression(expr),
+
+            ExprKind::Increment { .. } => self.check_increment(expr),
+
+            ExprKind::CompoundAssignment { .. } => self.check_compund_assignment(expr),
+
+            ExprKind::Binary { .. } => self.check_binary_expr(expr),
+
+            ExprKind::Propagate { .. } => self.check_propagate_expr(expr),
+
+            ExprKind::NamedArgument { .. } => Err(TypeError {
+                message: "Benannte Argumente sind nur in Funktionsaufrufen erlaubt".to_string(),
+            }),
+
+            ExprKind::Spread { .. } => Err(TypeError {
+                message:
+                    "Spread-Operator ist nur in Arrays, Objekten und Funktionsaufrufen erlaubt"
+                        .to_string(),
+            }),
+        }
+    }
+
+    fn check_assignment(&mut self, expr: &Expr) -> Result<(), TypeError> {
+        if let ExprKind::Assignment { assignee, value } = &expr.kind {
+            let target_type = self.assignment_target_type(assignee)?;
+            let reports_before = self.implicit_any.len();
+            let value_type = self.infer_contextual_type(value, &target_type)?;
+            self.allow_implicit_any(reports_before, &target_type);
+            self.check_assigned_type(assignee, &target_type, &value_type)
+        } else {
+            panic!("assignment expected");
+        }
+    }
+
+    fn assignment_target_type(&mut self, assignee: &Expr) -> Result<Type, TypeError> {
+        if let ExprKind::Identifier(name) = &assignee.kind {
+            let Some(var_info) = self.lookup_variable(name).cloned() else {
+                let note = self.did_you_mean(name, &self.variable_names(), NameKind::Variable);
                 return Err(TypeError {
-                    message: "Return statement outside of function".to_string(),
+                    message: format!("Nicht deklarierte Variable: {}{}", name, note),
+                });
+            };
+            if var_info.is_const && var_info.assignment == Assignment::Assigned {
+                return Err(TypeError {
+                    message: format!("Const Variable cant be reasigned {:?}", assignee),
+                });
+            }
+            self.record_variable(assignee, name);
+            self.record_type(assignee, &var_info.var_type);
+            Ok(var_info.var_type)
+        } else {
+            if self.check_if_const(assignee).expect("variable not found") {
+                return Err(TypeError {
+                    message: format!("Const Variable cant be reasigned {:?}", assignee),
+                });
+            }
+            self.infer_type(assignee)
+        }
+    }
+
+    fn check_assigned_type(
+        &mut self,
+        assignee: &Expr,
+        target_type: &Type,
+        value_type: &Type,
+    ) -> Result<(), TypeError> {
+        if let ExprKind::Identifier(name) = &assignee.kind {
+            self.check_variable_assignment(name)?;
+        }
+
+        if self.matching_types(target_type, value_type) {
+            Ok(())
+        } else {
+            Err(TypeError {
+                message: format!(
+                    "Expected {:?} got {:?}{}",
+                    target_type,
+                    value_type,
+                    self.mismatch_note(target_type, value_type)
+                ),
+            })
+        }
+    }
+
+    fn check_unary_expr(&mut self, expr: &Expr) -> Result<Type, TypeError> {
+        if let ExprKind::Unary { value, .. } = &expr.kind {
+            let value_type = self.infer_type(value)?;
+            if !self.matching_types(&Type::Boolean, &value_type) {
+                return Err(TypeError {
+                    message: "Unary operator ! can only be applied to booleans".to_string(),
+                });
+            }
+
+            Ok(Type::Boolean)
+        } else {
+            panic!("unary expression expected");
+        }
+    }
+
+    fn check_await_expression(&mut self, expr: &Expr) -> Result<Type, TypeError> {
+        if let ExprKind::AwaitExpression { value } = &expr.kind {
+            let value_type = self.infer_type(value)?;
+            if !self.currently_async {
+                return Err(TypeError {
+                    message: "Await can only be called in async functions".to_string(),
+                });
+            }
+
+            match self.resolve_type(&value_type) {
+                Type::Promise(resolved_type) => Ok(*resolved_type),
+                Type::Any => Ok(Type::Any),
+                other => Err(TypeError {
+                    message: format!("await erwartet ein Promise, bekam {:?}", other),
+                }),
+            }
+        } else {
+            panic!("await expression expected");
+        }
+    }
+
+    fn check_propagate_expr(&mut self, expr: &Expr) -> Result<Type, TypeError> {
+        if let ExprKind::Propagate { value } = &expr.kind {
+            if let Some(position) = self.propagation_barrier {
+                return Err(TypeError {
+                    message: format!("Der ?-Operator ist in {} nicht erlaubt", position),
+                });
+            }
+            let value_type = self.infer_type(value)?;
+            let (ok_type, error_type) = match self.resolve_type(&value_type) {
+                Type::Result(ok_type, error_type) => (*ok_type, *error_type),
+                other => {
+                    return Err(TypeError {
+                        message: format!(
+                            "Der ?-Operator ist nur auf Result anwendbar, bekam {:?}",
+                            other
+                        ),
+                    })
+                }
+            };
+
+            let return_type = match &self.current_return_type {
+                Some(return_type) => self.resolve_type(return_type),
+                None => {
+                    return Err(TypeError {
+                        message:
+                            "Der ?-Operator kann nur innerhalb von Funktionen verwendet werden"
+                                .to_string(),
+                    })
+                }
+            };
+            match return_type {
+                Type::Result(_, return_error_type) => {
+                    if error_type != Type::Any
+                        && !self.matching_types(&return_error_type, &error_type)
+                    {
+                        return Err(TypeError {
+                            message: format!(
+                                "Fehlertyp {:?} passt nicht zum Fehlertyp {:?} der Funktion",
+                                error_type, return_error_type
+                            ),
+                        });
+                    }
+                }
+                other => {
+                    return Err(TypeError {
+                        message: format!(
+                        "Der ?-Operator erfordert eine Funktion, die Result zurückgibt, nicht {:?}",
+                        other
+                    ),
+                    })
+                }
+            }
+
+            Ok(ok_type)
+        } else {
+            panic!("propagate expression expected");
+        }
+    }
+
+    fn check_increment(&mut self, expr: &Expr) -> Result<Type, TypeError> {
+        if let ExprKind::Increment { identifier, .. } = &expr.kind {
+            let identifier_type = self.infer_type(identifier)?;
+            if !self.matching_types(&Type::Number, &identifier_type) {
+                return Err(TypeError {
+                    message: "can call increwment only on type number".to_string(),
+                });
+            }
+            Ok(Type::Number)
+        } else {
+            panic!("Expected Increment expr")
+        }
+    }
+
+    fn check_compund_assignment(&mut self, expr: &Expr) -> Result<Type, TypeError> {
+        if let ExprKind::CompoundAssignment {
+            assignee,
+            value,
+            operator,
+        } = &expr.kind
+        {
+            let assignee_type = self.infer_type(assignee)?;
+            let assignee_type = self.widen_type(&assignee_type);
+            let value_type = self.infer_type(value)?;
+            let value_type = self.widen_type(&value_type);
+
+            if operator != "+=" && (assignee_type == Type::String || value_type == Type::String) {
+                return Err(TypeError {
+                    message: format!(
+                        "Cannot use operator '{}' with a string value/assignee. Only '+=' is allowed for string concatenation.",
+                        operator
+                    ),
                 });
             }
 
-            Ok(())
-        } else {
-            panic!("Return statement expected")
+            // checked like `assignee = assignee <operator> value`
+            let target_type = self.assignment_target_type(assignee)?;
+            let result_type =
+                Self::binary_result_type(&operator.replace("=", ""), assignee_type, value_type)?;
+            self.check_assigned_type(assignee, &target_type, &result_type)?;
+            Ok(Type::Any)
+        } else {
+            panic!("Expected compound assignment")
+        }
+    }
+
+    fn check_binary_expr(&mut self, expr: &Expr) -> Result<Type, TypeError> {
+        if let ExprKind::Binary {
+            left,
+            right,
+            operator,
+        } = &expr.kind
+        {
+            let left_type = self.infer_type(left)?;
+            let left_type = self.widen_type(&left_type);
+            let right_type = self.infer_type(right)?;
+            let right_type = self.widen_type(&right_type);
+
+            Self::binary_result_type(operator, left_type, right_type)
+        } else {
+            panic!("Binary Expression exprected");
+        }
+    }
+
+    fn binary_result_type(
+        operator: &str,
+        left_type: Type,
+        right_type: Type,
+    ) -> Result<Type, TypeError> {
+        let both_numbers = left_type == Type::Number && right_type == Type::Number;
+        let left_is_string = left_type == Type::String;
+        let right_is_string = right_type == Type::String;
+
+        match operator {
+            "+" => {
+                if both_numbers {
+                    Ok(Type::Number)
+                } else if left_is_string || right_is_string {
+                    Ok(Type::String)
+                } else {
+                    Err(TypeError {
+                            message: format!(
+                                "Operator '+' kann nicht auf die Typen {:?} und {:?} angewendet werden. Erlaubt für Zahl + Zahl oder String + (String/Zahl).",
+                                left_type, right_type
+                            ),
+                        })
+                }
+            }
+
+            "-" | "*" | "/" => {
+                if both_numbers {
+                    Ok(Type::Number)
+                } else {
+                    Err(TypeError {
+                            message: format!(
+                                "Arithmetischer Operator '{}' erfordert Zahlentypen, aber {:?} und {:?} wurden empfangen.",
+                                operator, left_type, right_type
+                            ),
+                        })
+                }
+            }
+
+            "==" | "!=" | "<" | ">" | "<=" | ">=" | "||" | "&&" => Ok(Type::Boolean),
+
+            _ => Err(TypeError {
+                message: format!("Unbekannter Operator '{}'", operator),
+            }),
         }
     }
 
-    fn check_try_catch_stmt(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
-        if let Stmt::TryCatchFinally {
-            try_branch,
-            catch_branch,
-            finally_branch,
-        } = stmt
-        {
-            for stmt in try_branch {
-                self.check_statement(stmt)?;
-            }
-            for stmt in catch_branch {
-                self.check_statement(stmt)?;
+    fn check_array_literal(&mut self, expr: &Expr) -> Result<Type, TypeError> {
+        if let ExprKind::ArrayLiteral(values) = &expr.kind {
+            if values.is_empty() {
+                self.report_implicit_any(
+                    "Leeres Array ohne Typangabe, der Elementtyp ist unbekannt".to_string(),
+                );
+                return Ok(Type::Array(Box::new(Type::Any)));
             }
-            if let Some(finally_branch) = finally_branch {
-                for stmt in finally_branch {
-                    self.check_statement(stmt)?;
+
+            let first_type = self.check_array_element(&values[0])?;
+            let first_type = self.widen_type(&first_type);
+
+            for value in values.iter().skip(1) {
+                let value_type = self.check_array_element(value)?;
+                let value_type = self.widen_type(&value_type);
+                if value_type != first_type && first_type != Type::Any && value_type != Type::Any {
+                    return Err(TypeError {
+                        message: format!(
+                            "Array elements must have consistent types. Expected {:?}, got {:?}",
+                            first_type, value_type
+                        ),
+                    });
                 }
             }
 
-            Ok(())
+            Ok(Type::Array(Box::new(first_type)))
         } else {
-            panic!("Try catch stmt expected")
+            panic!("array literal expected");
         }
     }
 
-    fn check_switch_stmt(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
-        if let Stmt::SwitchStatement {
-            case_branches,
-            default_branch,
-            ..
-        } = stmt
-        {
-            for case_branch in case_branches {
-                for stmt in &case_branch.body {
-                    self.check_statement(stmt)?;
+    fn check_array_element(&mut self, value: &Expr) -> Result<Type, TypeError> {
+        if let ExprKind::Spread { value } = &value.kind {
+            let spread_type = self.infer_type(value)?;
+            match self.resolve_type(&spread_type) {
+                Type::Array(element_type)
+                | Type::Set(element_type)
+                | Type::Iterator(element_type) => Ok(*element_type),
+                Type::Map(key_type, value_type) => {
+                    Ok(js_stdlib::map_entry_type(&key_type, &value_type))
                 }
+                Type::Any => Ok(Type::Any),
+                other => Err(TypeError {
+                    message: format!(
+                        "Nur Arrays, Maps, Sets und Iteratoren können in ein Array gespreadet werden, bekam {:?}",
+                        other
+                    ),
+                }),
             }
-            for stmt in default_branch {
-                self.check_statement(stmt)?
-            }
-
-            Ok(())
         } else {
-            panic!("Expected swiotcvh stmt");
+            self.infer_type(value)
         }
     }
 
-    fn check_loop_control_stmt(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
-        if !self.currently_loop {
+    // `Ok(value)` and `Err(error)` build a Result, the side that is not given
+    // stays Any until the context pins it down.
+    // `Map()` and `Set()` take their type from the annotation they are
+    // assigned to, or from the initial entries.
+    fn check_collection_constructor(
+        &mut self,
+        fn_name: &str,
+        args: &[Expr],
+        expected_type: Option<&Type>,
+    ) -> Result<Option<Type>, TypeError> {
+        if (fn_name != "Map" && fn_name != "Set")
+            || self.lookup_function(fn_name).is_some()
+            || self.classes.contains_key(fn_name)
+        {
+            return Ok(None);
+        }
+        if args.len() > 1 {
             return Err(TypeError {
-                message: "Loop control cant be used outside loops".to_string(),
+                message: format!("'{}' erwartet höchstens ein Argument", fn_name),
             });
         }
-        self.currently_loop = false;
-        Ok(())
-    }
-
-    fn check_type_alias_stmt(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
-        if let Stmt::TypeAlias { name, aliased_type } = stmt {
-            self.type_alias.insert(name.clone(), aliased_type.clone());
-
-            Ok(())
-        } else {
-            panic!("type alias stmt expected");
-        }
-    }
+        let has_expected_type = expected_type.is_some();
+        let expected_type = expected_type.map(|t| self.resolve_type(t));
 
-    fn infer_type(&mut self, expr: &Expr) -> Result<Type, TypeError> {
-        match expr {
-            Expr::NumericLiteral(_) => Ok(Type::Number),
-            Expr::StringLiteral(_) => Ok(Type::String),
-            Expr::BooleanLiteral(_) => Ok(Type::Boolean),
-            Expr::NullLiteral => Ok(Type::Null),
-            Expr::Identifier(name) => {
-                if let Some(var_info) = self.lookup_variable(name) {
-                    Ok(var_info.clone().var_type)
+        if fn_name == "Set" {
+            let mut element_type = match expected_type {
+                Some(Type::Set(element_type)) => *element_type,
+                _ => Type::Any,
+            };
+            if let Some(arg) = args.first() {
+                let expected_arg = Type::Array(Box::new(element_type.clone()));
+                let arg_type = if element_type == Type::Any {
+                    self.infer_type(arg)?
                 } else {
-                    Err(TypeError {
-                        message: format!("Nicht deklarierte Variable: {}", name),
-                    })
+                    self.infer_contextual_type(arg, &expected_arg)?
+                };
+                match self.resolve_type(&arg_type) {
+                    Type::Array(arg_element) if element_type == Type::Any => {
+                        element_type = self.widen_type(&arg_element)
+                    }
+                    Type::Any => {}
+                    arg_type if self.matching_types(&expected_arg, &arg_type) => {}
+                    arg_type => {
+                        return Err(TypeError {
+                            message: format!(
+                                "'Set' erwartet {:?}, bekam {:?}",
+                                expected_arg, arg_type
+                            ),
+                        })
+                    }
                 }
             }
+            if !has_expected_type && element_type == Type::Any {
+                self.report_implicit_any(
+                    "'Set' ohne Typangabe, der Elementtyp ist unbekannt".to_string(),
+                );
+            }
+            return Ok(Some(Type::Set(Box::new(element_type))));
+        }
 
-            Expr::ArrayLiteral(..) => self.check_array_literal(expr),
-            Expr::ObjectLiteral(..) => self.check_object_literal(expr),
+        let (mut key_type, mut value_type) = match expected_type {
+            Some(Type::Map(key_type, value_type)) => (*key_type, *value_type),
+            _ => (Type::Any, Type::Any),
+        };
+        match args.first() {
+            None => {}
+            Some(Expr {
+                kind: ExprKind::ArrayLiteral(entries),
+                ..
+            }) => {
+                for entry in entries {
+                    let (key, value) = match &entry.kind {
+                        ExprKind::ArrayLiteral(pair) if pair.len() == 2 => (&pair[0], &pair[1]),
+                        _ => {
+                            return Err(TypeError {
+                                message: "'Map' erwartet Einträge der Form [Schlüssel, Wert]"
+                                    .to_string(),
+                            })
+                        }
+                    };
+                    for (entry_value, entry_type) in
+                        [(key, &mut key_type), (value, &mut value_type)]
+                    {
+                        let actual_type = self.infer_contextual_type(entry_value, entry_type)?;
+                        if *entry_type == Type::Any {
+                            *entry_type = self.widen_type(&actual_type);
+                        } else if !self.matching_types(entry_type, &actual_type) {
+                            return Err(TypeError {
+                                message: format!(
+                                    "'Map' erwartet {:?}, bekam {:?}",
+                                    entry_type, actual_type
+                                ),
+                            });
+                        }
+                    }
+                }
+            }
+            Some(arg) => {
+                let arg_type = self.infer_type(arg)?;
+                let expected_map =
+                    Type::Map(Box::new(key_type.clone()), Box::new(value_type.clone()));
+                match self.resolve_type(&arg_type) {
+                    Type::Any => {}
+                    arg_type if self.matching_types(&expected_map, &arg_type) => {
+                        if let Type::Map(arg_key, arg_value) = arg_type {
+                            if key_type == Type::Any {
+                                key_type = *arg_key;
+                            }
+                            if value_type == Type::Any {
+                                value_type = *arg_value;
+                            }
+                        }
+                    }
+                    arg_type => {
+                        return Err(TypeError {
+                            message: format!(
+                                "'Map' erwartet eine Map oder Einträge der Form [Schlüssel, Wert], bekam {:?}",
+                                arg_type
+                            ),
+                        })
+                    }
+                }
+            }
+        }
+        if !has_expected_type && (key_type == Type::Any || value_type == Type::Any) {
+            self.report_implicit_any(
+                "'Map' ohne Typangabe, Schlüssel- oder Werttyp ist unbekannt".to_string(),
+            );
+        }
+        Ok(Some(Type::Map(Box::new(key_type), Box::new(value_type))))
+    }
 
-            Expr::Member { .. } => self.check_member_expr(expr),
+    fn check_result_constructor(
+        &mut self,
+        fn_name: &str,
+        args: &[Expr],
+        expected_type: Option<&Type>,
+    ) -> Result<Option<Type>, TypeError> {
+        if (fn_name != "Ok" && fn_name != "Err") || self.lookup_function(fn_name).is_some() {
+            return Ok(None);
+        }
+        if args.len() != 1 {
+            return Err(TypeError {
+                message: format!("'{}' erwartet genau ein Argument", fn_name),
+            });
+        }
 
-            Expr::Call { .. } => self.check_call_expr(expr),
+        let (expected_value, expected_error) = match expected_type.map(|t| self.resolve_type(t)) {
+            Some(Type::Result(value_type, error_type)) => (*value_type, *error_type),
+            _ => (Type::Any, Type::Any),
+        };
+        let expected_inner = if fn_name == "Ok" {
+            expected_value.clone()
+        } else {
+            expected_error.clone()
+        };
 
-            Expr::Assignment { .. } => {
-                self.check_assignment(expr)?;
-                Ok(Type::Any)
+        let inner_type = if expected_inner == Type::Any {
+            let arg_type = self.infer_type(&args[0])?;
+            self.widen_type(&arg_type)
+        } else {
+            let arg_type = self.infer_contextual_type(&args[0], &expected_inner)?;
+            if !self.matching_types(&expected_inner, &arg_type) {
+                return Err(TypeError {
+                    message: format!(
+                        "'{}' erwartet {:?}, bekam {:?}",
+                        fn_name, expected_inner, arg_type
+                    ),
+                });
             }
+            expected_inner
+        };
 
-            Expr::Unary { .. } => self.check_unary_expr(expr),
-
-            Expr::AwaitExpression { .. } => self.check_await_expression(expr),
+        if fn_name == "Ok" {
+            Ok(Some(Type::Result(
+                Box::new(inner_type),
+                Box::new(expected_error),
+            )))
+        } else {
+            Ok(Some(Type::Result(
+                Box::new(expected_value),
+                Box::new(inner_type),
+            )))
+        }
+    }
 
-            Expr::Increment { .. } => self.check_increment(expr),
+    fn check_call_args(
+        &mut self,
+        fn_name: &str,
+        signature: &FunctionSignature,
+        args: &[Expr],
+    ) -> Result<(), TypeError> {
+        let param_types = &signature.param_types;
+        let mut provided = vec![false; param_types.len()];
+        let mut seen_named = false;
 
-            Expr::CompoundAssignment { .. } => self.check_compund_assignment(expr),
+        for (idx, arg) in args.iter().enumerate() {
+            let (position, value) = match &arg.kind {
+                ExprKind::NamedArgument { name, value } => {
+                    seen_named = true;
+                    let position = signature
+                        .param_names
+                        .iter()
+                        .position(|param_name| param_name.as_deref() == Some(name.as_str()))
+                        .ok_or_else(|| TypeError {
+                            message: format!(
+                                "Funktion '{}' hat keinen Parameter '{}'",
+                                fn_name, name
+                            ),
+                        })?;
+                    if provided[position] {
+                        return Err(TypeError {
+                            message: format!(
+                                "Parameter '{}' von Funktion '{}' wurde mehrfach übergeben",
+                                name, fn_name
+                            ),
+                        });
+                    }
+                    (position, value.as_ref())
+                }
+                _ if seen_named => {
+                    return Err(TypeError {
+                            message: format!(
+                                "Positionale Argumente müssen bei Funktionsaufruf '{}' vor benannten Argumenten stehen",
+                                fn_name
+                            ),
+                        });
+                }
+                _ => (idx, arg),
+            };
 
-            Expr::Binary { .. } => self.check_binary_expr(expr),
+            let (expected_type, actual_type) = if position < param_types.len() {
+                if let ExprKind::Spread { .. } = &value.kind {
+                    return Err(TypeError {
+                            message: format!(
+                                "Spread-Argument bei Funktionsaufruf '{}' kann nur an einen Rest-Parameter übergeben werden",
+                                fn_name
+                            ),
+                        });
+                }
+                provided[position] = true;
+                let expected_type = param_types[position].clone();
+                let actual_type = self.infer_contextual_type(value, &expected_type)?;
+                (expected_type, actual_type)
+            } else if let Some(rest_type) = signature.rest_type.clone() {
+                if let ExprKind::Spread { value } = &value.kind {
+                    let expected_type = Type::Array(Box::new(rest_type));
+                    let actual_type = self.infer_contextual_type(value, &expected_type)?;
+                    (expected_type, actual_type)
+                } else {
+                    let actual_type = self.infer_contextual_type(value, &rest_type)?;
+                    (rest_type, actual_type)
+                }
+            } else {
+                return Err(TypeError {
+                    message: format!(
+                        "Funktion '{}' erwartet höchstens {} Argumente, aber {} wurden übergeben",
+                        fn_name,
+                        param_types.len(),
+                        args.len()
+                    ),
+                });
+            };
 
-            _ => Ok(Type::Any),
+            if !self.matching_types(&expected_type, &actual_type) {
+                return Err(TypeError {
+                        message: format!(
+                            "Typfehler bei Funktionsaufruf '{}': Parameter {} sollte vom Typ {:?} sein, ist aber {:?}{}",
+                            fn_name,
+                            position + 1,
+                            expected_type,
+                            actual_type,
+                            self.mismatch_note(&expected_type, &actual_type)
+                        ),
+                    });
+            }
         }
-    }
 
-    fn check_assignment(&mut self, expr: &Expr) -> Result<(), TypeError> {
-        if let Expr::Assignment { assignee, value } = expr {
-            if self.check_if_const(assignee).expect("variable not found") {
+        for (position, was_provided) in provided.iter().enumerate() {
+            if !was_provided && !signature.optional_params[position] {
+                let param_name = signature.param_names[position]
+                    .clone()
+                    .unwrap_or_else(|| (position + 1).to_string());
                 return Err(TypeError {
-                    message: format!("Const Variable cant be reasigned {:?}", assignee),
+                    message: format!(
+                        "Funktion '{}' erwartet ein Argument für Parameter '{}'",
+                        fn_name, param_name
+                    ),
                 });
             }
-            let target_type = self.infer_type(assignee)?;
-            let value_type = self.infer_type(value)?;
+        }
+        Ok(())
+    }
 
-            if self.matching_types(&target_type, &value_type) {
-                Ok(())
-            } else {
-                Err(TypeError {
-                    message: format!("Expected {:?} got {:?}", target_type, value_type),
-                })
+    fn check_call_expr(&mut self, expr: &Expr) -> Result<Type, TypeError> {
+        if let ExprKind::Call { caller, args } = &expr.kind {
+            match &caller.kind {
+                ExprKind::Identifier(fn_name) => {
+                    if let Some(result_type) = self.check_result_constructor(fn_name, args, None)? {
+                        return Ok(result_type);
+                    }
+                    if let Some(collection_type) =
+                        self.check_collection_constructor(fn_name, args, None)?
+                    {
+                        return Ok(collection_type);
+                    }
+                    if fn_name == "super" {
+                        return self.check_super_call(args);
+                    }
+                    if let Some(class_name) = self.record_class_reference(caller) {
+                        let constructor = self.classes[&class_name].constructor.clone();
+                        self.check_call_args(&class_name, &constructor, args)?;
+                        return Ok(Type::AliasedType(class_name));
+                    }
+                    let function = self.lookup_function(fn_name).cloned();
+                    match function {
+                        Some(function) => {
+                            self.record_function(caller, fn_name);
+                            self.check_call_args(fn_name, &function.signature, args)?;
+                            Ok(function.return_type)
+                        }
+                        None => {
+                            let note = self.did_you_mean(
+                                fn_name,
+                                &self.function_names(),
+                                NameKind::Variable,
+                            );
+                            Err(TypeError {
+                                message: format!("Undefinierte Funktion: {}{}", fn_name, note),
+                            })
+                        }
+                    }
+                }
+                ExprKind::Member {
+                    object, property, ..
+                } => {
+                    if args
+                        .iter()
+                        .any(|arg| matches!(&arg.kind, ExprKind::NamedArgument { .. }))
+                    {
+                        return Err(TypeError {
+                            message: "Benannte Argumente sind nur bei eigenen Funktionen erlaubt"
+                                .to_string(),
+                        });
+                    }
+                    if let ExprKind::Identifier(prop_name) = &property.kind {
+                        if let Some((method_path, method)) =
+                            self.get_class_method(object, prop_name)?
+                        {
+                            self.check_call_args(&method_path, &method.signature, args)?;
+                            return Ok(method.return_type);
+                        }
+                        self.get_method_return_type(object, prop_name, args)
+                    } else {
+                        Err(TypeError {
+                            message: "Property must be an identifier".to_string(),
+                        })
+                    }
+                }
+                _ => {
+                    self.report_implicit_any(
+                        "Aufruf eines Ausdrucks, dessen Typ unbekannt ist".to_string(),
+                    );
+                    Ok(Type::Any)
+                }
             }
         } else {
-            panic!("assignment expected");
+            Err(TypeError {
+                message: "Expected function call expression".to_string(),
+            })
         }
     }
 
-    fn check_unary_expr(&mut self, expr: &Expr) -> Result<Type, TypeError> {
-        if let Expr::Unary { value, .. } = expr {
-            let value_type = self.infer_type(value)?;
-            if !self.matching_types(&Type::Boolean, &value_type) {
-                return Err(TypeError {
-                    message: "Unary operator ! can only be applied to booleans".to_string(),
-                });
-            }
+    fn check_object_literal(
+        &mut self,
+        expr: &Expr,
+        expected_fields: Option<&HashMap<String, Type>>,
+    ) -> Result<Type, TypeError> {
+        if let ExprKind::ObjectLiteral(properties) = &expr.kind {
+            let mut property_types = HashMap::new();
+            let mut spreads_any = false;
+            for property in properties {
+                if let Some(Expr {
+                    kind: ExprKind::Spread { value },
+                    ..
+                }) = &property.value
+                {
+                    let spread_type = self.infer_type(value)?;
+                    match self.resolve_type(&spread_type) {
+                        Type::Object(fields) => property_types.extend(fields),
+                        Type::Any => spreads_any = true,
+                        other => {
+                            return Err(TypeError {
+                                message: format!(
+                                "Nur Objekte können in ein Objekt gespreadet werden, bekam {:?}",
+                                other
+                            ),
+                            })
+                        }
+                    }
+                } else if let Some(value) = &property.value {
+                    let expected_type =
+                        expected_fields.and_then(|fields| fields.get(&property.key));
+                    let property_type = match expected_type {
+                        Some(expected_type) => self.infer_contextual_type(value, expected_type)?,
+                        None => self.infer_type(value)?,
+                    };
 
-            Ok(Type::Boolean)
+                    property_types.insert(property.key.clone(), property_type);
+                } else {
+                    self.report_implicit_any(format!(
+                        "Eigenschaft '{}' in Kurzschreibweise hat keinen bekannten Typ",
+                        property.key
+                    ));
+                    property_types.insert(property.key.clone(), Type::Any);
+                }
+            }
+            if spreads_any {
+                return Ok(Type::Any);
+            }
+            Ok(Type::Object(property_types))
         } else {
-            panic!("unary expression expected");
+            panic!("Object literal expected");
         }
     }
 
-    fn check_await_expression(&mut self, expr: &Expr) -> Result<Type, TypeError> {
-        if let Expr::AwaitExpression { value } = expr {
-            let value_type = self.infer_type(value)?;
-            if !self.currently_async {
-                return Err(TypeError {
-                    message: "Await can only be called in async functions".to_string(),
-                });
+    fn check_class_property(
+        &mut self,
+        class_name: &str,
+        prop_name: &str,
+        is_static: bool,
+    ) -> Result<Type, TypeError> {
+        let field_type = self.find_class_member(class_name, |class_info| {
+            if is_static {
+                class_info.static_fields.get(prop_name).cloned()
+            } else {
+                class_info.fields.get(prop_name).cloned()
             }
+        });
+        if let Some(field_type) = field_type {
+            return Ok(field_type);
+        }
 
-            Ok(value_type)
-        } else {
-            panic!("await expression expected");
+        // methods used as values are not tracked any further
+        let is_method = self
+            .find_class_member(class_name, |class_info| {
+                if is_static {
+                    class_info.static_methods.get(prop_name).map(|_| ())
+                } else {
+                    class_info.methods.get(prop_name).map(|_| ())
+                }
+            })
+            .is_some();
+        if is_method {
+            self.report_implicit_any(format!(
+                "Methode '{}' von Klasse '{}' als Wert hat keinen bekannten Typ",
+                prop_name, class_name
+            ));
+            return Ok(Type::Any);
         }
+
+        let member_names = self.class_member_names(class_name, is_static);
+        let note = self.did_you_mean(prop_name, &member_names, NameKind::Property);
+        Err(TypeError {
+            message: format!(
+                "Property '{}' does not exist on class '{}'{}",
+                prop_name, class_name, note
+            ),
+        })
     }
 
-    fn check_increment(&mut self, expr: &Expr) -> Result<Type, TypeError> {
-        if let Expr::Increment { identifier, prefix } = expr {
-            let identifier_type = self.infer_type(identifier)?;
-            if identifier_type != Type::Number {
+    fn check_super_call(&mut self, args: &[Expr]) -> Result<Type, TypeError> {
+        let parent = match self
+            .lookup_variable("super")
+            .map(|var| var.var_type.clone())
+        {
+            Some(Type::AliasedType(parent)) if self.currently_constructor => parent,
+            _ => {
                 return Err(TypeError {
-                    message: "can call increwment only on type number".to_string(),
-                });
+                    message: "super(...) ist nur im Konstruktor einer abgeleiteten Klasse erlaubt"
+                        .to_string(),
+                })
             }
-            Ok(Type::Number)
+        };
+        let constructor = self.classes[&parent].constructor.clone();
+        self.check_call_args(&parent, &constructor, args)?;
+        Ok(Type::Void)
+    }
+
+    // Only the declared types are known before the bodies are checked, which
+    // is enough for methods calling each other.
+    fn declared_signature(
+        &mut self,
+        declaration: &Stmt,
+    ) -> Result<(FunctionSignature, Type), TypeError> {
+        if let Stmt::FunctionDeclaration {
+            parameters,
+            param_types,
+            param_defaults,
+            has_rest_parameter,
+            return_type,
+            is_async,
+            ..
+        } = declaration
+        {
+            let fixed_count = if *has_rest_parameter {
+                parameters.len() - 1
+            } else {
+                parameters.len()
+            };
+            let param_names = parameters[..fixed_count]
+                .iter()
+                .map(|param| match param {
+                    Pattern::Identifier(param_name) => Some(param_name.clone()),
+                    _ => None,
+                })
+                .collect();
+            let fixed_param_types = param_types[..fixed_count]
+                .iter()
+                .map(|param_type| self.get_type(param_type))
+                .collect::<Result<_, _>>()?;
+            let optional_params = param_defaults[..fixed_count]
+                .iter()
+                .map(|default| default.is_some())
+                .collect();
+            let rest_type = if *has_rest_parameter {
+                match self.resolve_type(&param_types[fixed_count]) {
+                    Type::Array(element_type) => Some(*element_type),
+                    _ => Some(Type::Any),
+                }
+            } else {
+                None
+            };
+            Ok((
+                FunctionSignature::new(param_names, fixed_param_types, optional_params, rest_type),
+                Self::call_return_type(return_type, *is_async),
+            ))
         } else {
-            panic!("Expected Increment expr")
+            panic!("function declaration expected")
         }
     }
 
-    fn check_compund_assignment(&mut self, expr: &Expr) -> Result<Type, TypeError> {
-        if let Expr::CompoundAssignment {
-            assignee,
-            value,
-            operator,
-        } = expr
+    fn check_class_declaration(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::ClassDeclaration {
+            name,
+            parent,
+            members,
+        } = stmt
         {
-            let assignee_type = self.infer_type(assignee)?;
-            let value_type = self.infer_type(value)?;
-
-            if operator != "+=" && (assignee_type == Type::String || value_type == Type::String) {
+            if !self.unreached_types.remove(name) && self.is_known_type_name(name) {
                 return Err(TypeError {
-                    message: format!(
-                        "Cannot use operator '{}' with a string value/assignee. Only '+=' is allowed for string concatenation.",
-                        operator
-                    ),
+                    message: format!("Typ '{}' ist bereits definiert", name),
                 });
             }
+            if let Some(parent_name) = parent {
+                if !self.classes.contains_key(parent_name) {
+                    return Err(TypeError {
+                        message: format!(
+                            "Klasse '{}' kann nicht von unbekannter Klasse '{}' erben",
+                            name, parent_name
+                        ),
+                    });
+                }
+                // the JS class only exists once its declaration ran
+                if self.unreached_types.contains(parent_name) {
+                    return Err(TypeError {
+                        message: format!(
+                            "Klasse '{}' erbt von Klasse '{}', die erst danach deklariert wird",
+                            name, parent_name
+                        ),
+                    });
+                }
+            }
 
-            let binary_expr = Expr::Binary {
-                left: Box::new((**assignee).clone()),
-                right: Box::new((**value).clone()),
-                operator: operator.clone().replace("=", ""),
-            };
+            self.class_symbol(name);
+            let class_info = self.class_shape(stmt, false)?;
+            self.member_symbols(members, &class_info);
+            self.classes.insert(name.clone(), class_info);
 
-            let new_expr = Expr::Assignment {
-                assignee: Box::new((**assignee).clone()),
-                value: Box::new(binary_expr),
-            };
+            for member in members {
+                if let ClassMember::Method {
+                    declaration,
+                    is_static,
+                } = member
+                {
+                    self.check_method(name, declaration, *is_static)?;
+                }
+            }
 
-            self.check_assignment(&new_expr)?;
-            Ok(Type::Any)
+            Ok(())
         } else {
-            panic!("Expected compound assignment")
+            panic!("class declaration expected")
         }
     }
 
-    fn check_binary_expr(&mut self, expr: &Expr) -> Result<Type, TypeError> {
-        if let Expr::Binary {
-            left,
-            right,
-            operator,
-        } = expr
-        {
-            let left_type = self.infer_type(left)?;
-            let right_type = self.infer_type(right)?;
-
-            let both_numbers = left_type == Type::Number && right_type == Type::Number;
-            let left_is_string = left_type == Type::String;
-            let right_is_string = right_type == Type::String;
-
-            match operator.as_str() {
-                "+" => {
-                    if both_numbers {
-                        Ok(Type::Number)
-                    } else if left_is_string || right_is_string {
-                        Ok(Type::String)
+    fn member_symbols(&mut self, members: &[ClassMember], class_info: &ClassInfo) {
+        for member in members {
+            match member {
+                ClassMember::Field {
+                    name, is_static, ..
+                } => {
+                    let fields = if *is_static {
+                        &class_info.static_fields
                     } else {
-                        Err(TypeError {
-                            message: format!(
-                                "Operator '+' kann nicht auf die Typen {:?} und {:?} angewendet werden. Erlaubt für Zahl + Zahl oder String + (String/Zahl).",
-                                left_type, right_type
-                            ),
-                        })
+                        &class_info.fields
+                    };
+                    let field_type = fields.get(name).cloned().unwrap_or(Type::Any);
+                    self.new_symbol(name, SymbolKind::Field, &field_type);
+                }
+                ClassMember::Method { declaration, .. } => {
+                    if let Stmt::FunctionDeclaration {
+                        name,
+                        return_type,
+                        is_async,
+                        ..
+                    } = declaration
+                    {
+                        let return_type = Self::call_return_type(return_type, *is_async);
+                        self.new_symbol(name, SymbolKind::Method, &return_type);
                     }
                 }
+            }
+        }
+    }
+
+    // Fields and methods of a class. While hoisting, fields whose type only
+    // their value tells are left out until the declaration is reached, unless
+    // the value is a literal.
+    fn class_shape(&mut self, stmt: &Stmt, hoisting: bool) -> Result<ClassInfo, TypeError> {
+        let Stmt::ClassDeclaration {
+            name,
+            parent,
+            members,
+        } = stmt
+        else {
+            panic!("class declaration expected")
+        };
+
+        // without an own constructor the parent one is inherited
+        let inherited_constructor = match parent
+            .as_ref()
+            .and_then(|parent_name| self.classes.get(parent_name))
+        {
+            Some(parent_info) => parent_info.constructor.clone(),
+            None => FunctionSignature::new(Vec::new(), Vec::new(), Vec::new(), None),
+        };
+        let mut class_info = ClassInfo {
+            parent: parent.clone(),
+            constructor: inherited_constructor,
+            fields: HashMap::new(),
+            methods: HashMap::new(),
+            static_fields: HashMap::new(),
+            static_methods: HashMap::new(),
+        };
+        // registered first so members can refer to the class itself
+        if !hoisting {
+            self.classes.insert(name.clone(), class_info.clone());
+        }
 
-                "-" | "*" | "/" => {
-                    if both_numbers {
-                        Ok(Type::Number)
+        for member in members {
+            match member {
+                ClassMember::Field {
+                    name: field_name,
+                    field_type,
+                    value,
+                    is_static,
+                } => {
+                    let declared_type = self.get_type(field_type)?;
+                    let field_type = match value {
+                        Some(value) if hoisting && *field_type == Type::Any => {
+                            match Self::literal_type(value) {
+                                Some(literal_type) => self.widen_type(&literal_type),
+                                None => continue,
+                            }
+                        }
+                        Some(value) => {
+                            let reports_before = self.implicit_any.len();
+                            let value_type = self.infer_contextual_type(value, &declared_type)?;
+                            if *field_type != Type::Any {
+                                self.allow_implicit_any(reports_before, &declared_type);
+                            }
+                            if *field_type == Type::Any {
+                                self.widen_type(&value_type)
+                            } else if self.matching_types(&declared_type, &value_type) {
+                                declared_type
+                            } else {
+                                return Err(TypeError {
+                                    message: format!(
+                                        "Feld '{}' sollte vom Typ {:?} sein, ist aber {:?}",
+                                        field_name, declared_type, value_type
+                                    ),
+                                });
+                            }
+                        }
+                        None => {
+                            if *field_type == Type::Any && !hoisting {
+                                self.report_implicit_any(format!(
+                                    "Feld '{}' hat weder Typangabe noch Wert",
+                                    field_name
+                                ));
+                            }
+                            declared_type
+                        }
+                    };
+                    let fields = if *is_static {
+                        &mut class_info.static_fields
                     } else {
-                        Err(TypeError {
+                        &mut class_info.fields
+                    };
+                    if fields.insert(field_name.clone(), field_type).is_some() {
+                        return Err(TypeError {
                             message: format!(
-                                "Arithmetischer Operator '{}' erfordert Zahlentypen, aber {:?} und {:?} wurden empfangen.",
-                                operator, left_type, right_type
+                                "Feld '{}' ist in Klasse '{}' mehrfach definiert",
+                                field_name, name
                             ),
-                        })
+                        });
+                    }
+                }
+                ClassMember::Method {
+                    declaration,
+                    is_static,
+                } => {
+                    let (signature, return_type) = self.declared_signature(declaration)?;
+                    if let Stmt::FunctionDeclaration {
+                        name: method_name, ..
+                    } = declaration
+                    {
+                        if method_name == "constructor" {
+                            class_info.constructor = signature;
+                            continue;
+                        }
+                        let methods = if *is_static {
+                            &mut class_info.static_methods
+                        } else {
+                            &mut class_info.methods
+                        };
+                        let method = MethodInfo {
+                            signature,
+                            return_type,
+                        };
+                        if methods.insert(method_name.clone(), method).is_some() {
+                            return Err(TypeError {
+                                message: format!(
+                                    "Methode '{}' ist in Klasse '{}' mehrfach definiert",
+                                    method_name, name
+                                ),
+                            });
+                        }
                     }
                 }
-
-                "==" | "!=" | "<" | ">" | "<=" | ">=" | "||" | "&&" => Ok(Type::Boolean),
-
-                _ => Err(TypeError {
-                    message: format!("Unbekannter Operator '{}'", operator),
-                }),
             }
-        } else {
-            panic!("Binary Expression exprected");
         }
+        Ok(class_info)
     }
 
-    fn check_array_literal(&mut self, expr: &Expr) -> Result<Type, TypeError> {
-        if let Expr::ArrayLiteral(values) = expr {
-            if values.is_empty() {
-                return Ok(Type::Array(Box::new(Type::Any)));
+    fn check_method(
+        &mut self,
+        class_name: &str,
+        declaration: &Stmt,
+        is_static: bool,
+    ) -> Result<(), TypeError> {
+        let parent = self.classes[class_name].parent.clone();
+        self.enter_scope();
+        if !is_static {
+            self.declare_variable(
+                "self",
+                VarInfo::new(Type::AliasedType(class_name.to_string()), true),
+            );
+            if let Some(parent_name) = &parent {
+                self.declare_variable(
+                    "super",
+                    VarInfo::new(Type::AliasedType(parent_name.clone()), true),
+                );
             }
+        }
+        let checked = self.check_method_body(class_name, &parent, declaration);
+        self.exit_scope();
+        checked
+    }
 
-            let first_type = self.infer_type(&values[0])?;
-
-            for value in values.iter().skip(1) {
-                let value_type = self.infer_type(value)?;
-                if value_type != first_type && first_type != Type::Any && value_type != Type::Any {
+    fn check_method_body(
+        &mut self,
+        class_name: &str,
+        parent: &Option<String>,
+        declaration: &Stmt,
+    ) -> Result<(), TypeError> {
+        if let Stmt::FunctionDeclaration {
+            name,
+            return_type,
+            body,
+            ..
+        } = declaration
+        {
+            if name == "constructor" {
+                if *return_type != Type::Void {
                     return Err(TypeError {
                         message: format!(
-                            "Array elements must have consistent types. Expected {:?}, got {:?}",
-                            first_type, value_type
+                            "Konstruktor von Klasse '{}' kann keinen Rückgabetyp haben",
+                            class_name
+                        ),
+                    });
+                }
+                let calls_super = body.iter().any(|stmt| {
+                    matches!(stmt, Stmt::Expression(Expr { kind: ExprKind::Call { caller, .. }, .. })
+                        if matches!(&caller.kind, ExprKind::Identifier(callee) if callee == "super"))
+                });
+                if parent.is_some() && !calls_super {
+                    return Err(TypeError {
+                        message: format!(
+                            "Konstruktor von Klasse '{}' muss super(...) aufrufen",
+                            class_name
                         ),
                     });
                 }
             }
+            self.currently_constructor = name == "constructor";
+            let checked = self.check_function(declaration, false);
+            self.currently_constructor = false;
+            checked?;
+            Ok(())
+        } else {
+            panic!("function declaration expected")
+        }
+    }
 
-            Ok(Type::Array(Box::new(first_type)))
+    fn check_interface_declaration(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::InterfaceDeclaration { name, members } = stmt {
+            // registered by `hoist_declarations`
+            if self.unreached_types.remove(name) {
+                return Ok(());
+            }
+            if self.is_known_type_name(name) {
+                return Err(TypeError {
+                    message: format!("Typ '{}' ist bereits definiert", name),
+                });
+            }
+            self.new_symbol(
+                name,
+                SymbolKind::Interface,
+                &Type::AliasedType(name.clone()),
+            );
+            self.register_interface(name, members)
         } else {
-            panic!("array literal expected");
+            panic!("interface declaration expected")
         }
     }
 
-    fn check_call_expr(&mut self, expr: &Expr) -> Result<Type, TypeError> {
-        if let Expr::Call { caller, args } = expr {
-            match &**caller {
-                Expr::Identifier(fn_name) => {
-                    let param_types_opt = self.function_signatures.get(fn_name).cloned();
-                    match param_types_opt {
-                        Some(param_types) => {
-                            if args.len() != param_types.len() {
-                                return Err(TypeError {
-                                    message: format!(
-                                        "Funktion '{}' erwartet {} Argumente, aber {} wurden übergeben",
-                                        fn_name,
-                                        param_types.len(),
-                                        args.len()
-                                    ),
-                                });
-                            }
+    fn register_interface(
+        &mut self,
+        name: &str,
+        members: &[InterfaceMember],
+    ) -> Result<(), TypeError> {
+        // registered first so members can refer to the interface itself
+        self.interfaces.insert(
+            name.to_string(),
+            InterfaceInfo {
+                fields: HashMap::new(),
+                methods: HashMap::new(),
+                optional_fields: HashSet::new(),
+            },
+        );
 
-                            for (idx, (arg, expected_type)) in
-                                args.iter().zip(param_types.iter()).enumerate()
-                            {
-                                let actual_type = self.infer_type(arg)?;
-
-                                if actual_type != *expected_type && *expected_type != Type::Any {
-                                    return Err(TypeError {
-                                        message: format!(
-                                            "Typfehler bei Funktionsaufruf '{}': Parameter {} sollte vom Typ {:?} sein, ist aber {:?}",
-                                            fn_name,
-                                            idx + 1,
-                                            expected_type,
-                                            actual_type
-                                        ),
-                                    });
-                                }
-                            }
-                            if let Some(return_type) = self.function_return_type.get(fn_name) {
-                                Ok(return_type.clone())
-                            } else {
-                                Ok(Type::Any)
-                            }
-                        }
-                        None => Err(TypeError {
-                            message: format!("Undefinierte Funktion: {}", fn_name),
-                        }),
+        let mut interface = self.interfaces[name].clone();
+        for member in members {
+            let (member_name, is_new) = match member {
+                InterfaceMember::Field {
+                    name: field_name,
+                    field_type,
+                } => {
+                    let field_type = self.get_type(field_type)?;
+                    let is_new = !interface.methods.contains_key(field_name)
+                        && interface
+                            .fields
+                            .insert(field_name.clone(), field_type)
+                            .is_none();
+                    (field_name, is_new)
+                }
+                InterfaceMember::Method { declaration } => {
+                    let (signature, return_type) = self.declared_signature(declaration)?;
+                    let method_name = match declaration {
+                        Stmt::FunctionDeclaration { name, .. } => name,
+                        _ => panic!("function declaration expected"),
+                    };
+                    let method = MethodInfo {
+                        signature,
+                        return_type: self.get_type(&return_type)?,
+                    };
+                    let is_new = !interface.fields.contains_key(method_name)
+                        && interface
+                            .methods
+                            .insert(method_name.clone(), method)
+                            .is_none();
+                    (method_name, is_new)
+                }
+            };
+            if !is_new {
+                return Err(TypeError {
+                    message: format!(
+                        "Member '{}' ist in Interface '{}' mehrfach definiert",
+                        member_name, name
+                    ),
+                });
+            }
+        }
+        self.interfaces.insert(name.to_string(), interface);
+
+        Ok(())
+    }
+
+    fn check_declare_stmt(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::Declare { declaration } = stmt {
+            match declaration.as_ref() {
+                Stmt::FunctionDeclaration { name, .. } => {
+                    if self.lookup_function(name).is_some() {
+                        return Err(TypeError {
+                            message: format!("Funktion '{}' ist bereits definiert", name),
+                        });
                     }
+                    let (signature, return_type) = self.declared_signature(declaration)?;
+                    let return_type = self.get_type(&return_type)?;
+                    self.declare_function(name, signature, return_type);
                 }
-                Expr::Member {
-                    object, property, ..
+                Stmt::VarDeclaration {
+                    pattern: Pattern::Identifier(name),
+                    var_type,
+                    ..
                 } => {
-                    if let Expr::Identifier(prop_name) = property.as_ref() {
-                        self.get_method_return_type(object, prop_name)
-                    } else {
-                        Err(TypeError {
-                            message: "Property must be an identifier".to_string(),
-                        })
-                    }
+                    let var_type = self.get_type(var_type)?;
+                    self.bind_pattern(&Pattern::Identifier(name.clone()), &var_type, true)?;
+                }
+                Stmt::InterfaceDeclaration { name, members } => {
+                    // the object's type gets a name no user type can have
+                    let object_type = format!("typeof {}", name);
+                    self.register_interface(&object_type, members)?;
+                    self.declare_variable(name, VarInfo::new(Type::AliasedType(object_type), true));
                 }
-                _ => Ok(Type::Any),
+                _ => panic!("unexpected declaration"),
             }
+            Ok(())
         } else {
-            Err(TypeError {
-                message: "Expected function call expression".to_string(),
-            })
+            panic!("declare stmt expected")
         }
     }
 
-    fn check_object_literal(&mut self, expr: &Expr) -> Result<Type, TypeError> {
-        if let Expr::ObjectLiteral(properties) = expr {
-            let mut property_types = HashMap::new();
-            for property in properties {
-                if let Some(value) = &property.value {
-                    let property_type = self.infer_type(value)?;
+    fn check_impl_declaration(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
+        if let Stmt::ImplDeclaration {
+            interface,
+            target,
+            methods,
+        } = stmt
+        {
+            if !self.interfaces.contains_key(interface) {
+                return Err(TypeError {
+                    message: format!("Unbekanntes Interface: {}", interface),
+                });
+            }
+            if !self.classes.contains_key(target) {
+                return Err(TypeError {
+                    message: format!("impl erwartet eine Klasse, '{}' ist keine", target),
+                });
+            }
 
-                    property_types.insert(property.key.clone(), property_type);
-                } else {
-                    property_types.insert(property.key.clone(), Type::Any);
+            for declaration in methods {
+                let (signature, return_type) = self.declared_signature(declaration)?;
+                if let Stmt::FunctionDeclaration {
+                    name: method_name, ..
+                } = declaration
+                {
+                    let class_info = self.classes.get_mut(target).expect("class exists");
+                    if class_info.methods.contains_key(method_name) {
+                        return Err(TypeError {
+                            message: format!(
+                                "Methode '{}' ist in Klasse '{}' bereits definiert",
+                                method_name, target
+                            ),
+                        });
+                    }
+                    class_info.methods.insert(
+                        method_name.clone(),
+                        MethodInfo {
+                            signature,
+                            return_type,
+                        },
+                    );
                 }
             }
-            Ok(Type::Object(property_types))
+            for declaration in methods {
+                self.check_method(target, declaration, false)?;
+            }
+
+            let missing = self
+                .missing_interface_members(interface, &Type::AliasedType(target.clone()))
+                .unwrap_or_default();
+            if !missing.is_empty() {
+                return Err(TypeError {
+                    message: format!(
+                        "Klasse '{}' implementiert '{}' nicht vollständig, fehlend oder unpassend: {}",
+                        target,
+                        interface,
+                        missing.join(", ")
+                    ),
+                });
+            }
+
+            Ok(())
         } else {
-            panic!("Object literal expected");
+            panic!("impl declaration expected")
         }
     }
 
     fn check_member_expr(&mut self, expr: &Expr) -> Result<Type, TypeError> {
-        if let Expr::Member {
+        if let ExprKind::Member {
             object,
             property,
             computed,
-        } = expr
+        } = &expr.kind
         {
+            if let (Some(class_name), false, ExprKind::Identifier(prop_name)) = (
+                self.record_class_reference(object),
+                *computed,
+                &property.kind,
+            ) {
+                return self.check_class_property(&class_name, prop_name, true);
+            }
+
             let obj_type = self.infer_type(object)?;
+            let obj_type = self.resolve_type(&obj_type);
+
+            if let (false, ExprKind::Identifier(prop_name)) = (*computed, &property.kind) {
+                if let Some(prop_type) = self
+                    .js_stdlib
+                    .get_primitive_property_type(&obj_type, prop_name)
+                {
+                    return Ok(prop_type);
+                }
+            }
 
-            match obj_type {
+            match obj_type.clone() {
                 Type::Array(element_type) => {
                     if *computed {
                         let index_type = self.infer_type(property)?;
-                        if index_type != Type::Number {
+                        if !self.matching_types(&Type::Number, &index_type) {
                             return Err(TypeError {
                                 message: "Array index must be a number".to_string(),
                             });
@@ -915,33 +4890,300 @@ impl TypeChecker {
                         return Ok(*element_type.clone());
                     }
                 }
+                Type::AliasedType(class_name) if self.classes.contains_key(&class_name) => {
+                    if let (false, ExprKind::Identifier(prop_name)) = (*computed, &property.kind) {
+                        return self.check_class_property(&class_name, prop_name, false);
+                    }
+                }
+                Type::AliasedType(interface_name)
+                    if self.interfaces.contains_key(&interface_name) =>
+                {
+                    if let (false, ExprKind::Identifier(prop_name)) = (*computed, &property.kind) {
+                        let interface = &self.interfaces[&interface_name];
+                        if let Some(field_type) = interface.fields.get(prop_name) {
+                            return Ok(field_type.clone());
+                        }
+                        if interface.methods.contains_key(prop_name) {
+                            self.report_implicit_any(format!(
+                                "Methode '{}' von Interface '{}' als Wert hat keinen bekannten Typ",
+                                prop_name, interface_name
+                            ));
+                            return Ok(Type::Any);
+                        }
+                        let member_names: Vec<String> = interface
+                            .fields
+                            .keys()
+                            .chain(interface.methods.keys())
+                            .cloned()
+                            .collect();
+                        let note = self.did_you_mean(prop_name, &member_names, NameKind::Property);
+                        return Err(TypeError {
+                            message: format!(
+                                "Property '{}' does not exist on interface '{}'{}",
+                                prop_name, interface_name, note
+                            ),
+                        });
+                    }
+                }
+                Type::Result(value_type, error_type) => {
+                    if let (false, ExprKind::Identifier(prop_name)) = (*computed, &property.kind) {
+                        return match prop_name.as_str() {
+                            "ok" => Ok(Type::Boolean),
+                            "value" => Ok(Type::Option(value_type)),
+                            "error" => Ok(Type::Option(error_type)),
+                            _ => {
+                                let member_names = ["ok", "value", "error"].map(String::from);
+                                let note =
+                                    self.did_you_mean(prop_name, &member_names, NameKind::Property);
+                                Err(TypeError {
+                                    message: format!(
+                                        "Property '{}' does not exist on Result{}",
+                                        prop_name, note
+                                    ),
+                                })
+                            }
+                        };
+                    }
+                }
                 Type::Object(properties) => {
-                    if let Expr::Identifier(prop_name) = property.as_ref() {
+                    if let ExprKind::Identifier(prop_name) = &property.kind {
                         if let Some(prop_type) = properties.get(prop_name) {
                             return Ok(prop_type.clone());
                         } else {
+                            let field_names: Vec<String> = properties.keys().cloned().collect();
+                            let note =
+                                self.did_you_mean(prop_name, &field_names, NameKind::Property);
                             return Err(TypeError {
                                 message: format!(
-                                    "Property '{}' does not exist on this object",
-                                    prop_name
+                                    "Property '{}' does not exist on this object{}",
+                                    prop_name, note
                                 ),
                             });
                         }
                     } else if *computed {
                         let _prop_type = self.infer_type(property)?;
+                        self.report_implicit_any(
+                            "Berechneter Zugriff auf ein Objekt hat keinen bekannten Typ"
+                                .to_string(),
+                        );
                         return Ok(Type::Any);
                     }
                 }
                 _ => {
+                    let note = match (*computed, &property.kind) {
+                        (false, ExprKind::Identifier(prop_name)) => {
+                            let member_names = self.js_stdlib.primitive_member_names(&obj_type);
+                            self.did_you_mean(prop_name, &member_names, NameKind::Property)
+                        }
+                        _ => String::new(),
+                    };
                     return Err(TypeError {
-                        message: format!("Cannot access property on type {:?}", obj_type),
+                        message: format!("Cannot access property on type {:?}{}", obj_type, note),
                     });
                 }
             }
 
+            self.report_implicit_any(format!(
+                "Zugriff auf {:?} hat keinen bekannten Typ",
+                obj_type
+            ));
             Ok(Type::Any)
         } else {
             panic!("Member expression expected");
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SymbolKind, TypeChecker, TypeError};
+    use crate::ast::{ExprKind, Position, Stmt, Type};
+    use crate::parser::Parser;
+
+    fn check(source: &str) -> Result<(), TypeError> {
+        let program = Parser::new().produce_ast(source);
+        TypeChecker::new().check_program(&program)
+    }
+
+    #[test]
+    fn compound_assignments_reference_their_variable_once() {
+        let program = Parser::new().produce_ast("let x = 1; x += 2; x += 3; console.log(x);");
+        let mut type_checker = TypeChecker::new();
+        type_checker
+            .check_program(&program)
+            .expect("Programm ist korrekt");
+
+        let type_info = type_checker.type_info();
+        let x = type_info
+            .symbols()
+            .iter()
+            .find(|symbol| symbol.name == "x")
+            .expect("x ist deklariert");
+        assert_eq!(type_info.references(x.id).len(), 3);
+    }
+
+    #[test]
+    fn expressions_are_found_by_their_id() {
+        let mut parser = Parser::new();
+        let program = parser.produce_ast("let total = 1 + 2;\nconsole.log(total);");
+        let mut type_checker = TypeChecker::new();
+        type_checker
+            .check_program(&program)
+            .expect("Programm ist korrekt");
+
+        let Stmt::Program { body } = &program else {
+            panic!("Programm erwartet");
+        };
+        let Stmt::Expression(call) = &body[1] else {
+            panic!("Ausdruck erwartet");
+        };
+        let ExprKind::Call { args, .. } = call.clone().kind else {
+            panic!("Aufruf erwartet");
+        };
+        let type_info = type_checker.type_info();
+        assert_eq!(type_info.type_of(&args[0]), Some(&Type::Number));
+        assert_eq!(type_info.symbol_of(&args[0]).unwrap().name, "total");
+
+        let position = parser.into_source_map().expression_position(args[0].id);
+        assert_eq!(
+            position,
+            Some(Position {
+                line: 2,
+                column: 13
+            })
+        );
+    }
+
+    #[test]
+    fn iterated_loops_declare_their_variable() {
+        let program = Parser::new().produce_ast("for (0..3 as i) { console.log(i); }");
+        let mut type_checker = TypeChecker::new();
+        type_checker
+            .check_program(&program)
+            .expect("Programm ist korrekt");
+
+        let Stmt::Program { body } = &program else {
+            panic!("Programm erwartet");
+        };
+        let declared = type_checker.type_info().declared_by(&body[0]);
+        assert_eq!(declared.len(), 1);
+        assert_eq!(declared[0].name, "i");
+    }
+
+    #[test]
+    fn builtin_placeholders_are_no_user_types() {
+        let source = "
+            class U { constructor() {} }
+            fn keep(x: U) -> bool { return true; }
+            let a: array<U> = [U()];
+            let b: array<U> = a.filter(keep);";
+        assert!(check(source).is_ok());
+    }
+
+    #[test]
+    fn callbacks_accept_what_the_method_passes() {
+        let source = "
+            fn positive(x: num) -> bool { return x > 0; }
+            let a: array<num> = [1, 2];
+            let b: array<num> = a.filter(positive);";
+        assert!(check(source).is_ok());
+
+        let source = "
+            fn empty(x: string) -> bool { return x == \"\"; }
+            let a: array<num> = [1, 2];
+            let b = a.filter(empty);";
+        assert!(check(source).is_err());
+    }
+
+    #[test]
+    fn redeclarations_name_the_line_of_the_first_declaration() {
+        let mut parser = Parser::new();
+        let program =
+            parser.produce_ast("let a = 1;\nlet b = 2;\nfn c() { let a = 3; }\nlet a = 4;");
+        let mut type_checker = TypeChecker::new();
+        type_checker.set_source_map(parser.into_source_map());
+        let error = type_checker
+            .check_program(&program)
+            .expect_err("a ist doppelt deklariert");
+        assert!(error.message.ends_with("in Zeile 1"), "{}", error.message);
+    }
+
+    #[test]
+    fn classes_and_interfaces_can_be_used_before_their_declaration() {
+        let source = "
+            fn make() -> K { return K(2); }
+            fn area(s: Shape) -> num { return s.w * s.h; }
+            let k = make();
+            let a: num = area({ w: 2, h: k.n });
+            class K {
+                n: num;
+                constructor(n: num) { self.n = n; }
+            }
+            interface Shape { w: num; h: num; }";
+        assert!(check(source).is_ok());
+
+        // the JS class of the parent does not exist yet
+        let source = "
+            class B extends A { constructor() { super(); } }
+            class A { constructor() {} }";
+        assert!(check(source).is_err());
+    }
+
+    #[test]
+    fn unknown_types_are_type_errors() {
+        assert!(check("fn f(x: Nope) -> num { return 1; }").is_err());
+        assert!(check("fn f() -> Nope { return 1; }").is_err());
+        assert!(check("class C { n: Nope; constructor() {} }").is_err());
+    }
+
+    #[test]
+    fn declarations_of_every_kind_have_positioned_symbols() {
+        let source = "type Id = num;
+interface Named { name: string; }
+class User {
+    id: Id;
+    constructor(id: Id) { self.id = id; }
+    fn label() -> string { return \"user\"; }
+}
+let user = User(1);
+console.log(user.label());";
+        let mut parser = Parser::new();
+        let program = parser.produce_ast(source);
+        let mut type_checker = TypeChecker::new();
+        type_checker.set_source_map(parser.into_source_map());
+        type_checker
+            .check_program(&program)
+            .expect("Programm ist korrekt");
+
+        let type_info = type_checker.type_info();
+        let declared = |name: &str, kind: SymbolKind| {
+            let symbol = type_info
+                .symbols()
+                .iter()
+                .find(|symbol| symbol.name == name && symbol.kind == kind)
+                .unwrap_or_else(|| panic!("{} ist deklariert", name));
+            let position = symbol.position.expect("Position der Deklaration");
+            assert_eq!(
+                type_info.symbol_at(position).map(|found| found.id),
+                Some(symbol.id)
+            );
+            (symbol.id, position.line)
+        };
+        assert_eq!(declared("Id", SymbolKind::TypeAlias).1, 1);
+        assert_eq!(declared("Named", SymbolKind::Interface).1, 2);
+        assert_eq!(declared("id", SymbolKind::Field).1, 4);
+        assert_eq!(declared("label", SymbolKind::Method).1, 6);
+
+        let (user, line) = declared("user", SymbolKind::Variable);
+        assert_eq!(line, 8);
+        let references = type_info.references(user);
+        assert_eq!(references.len(), 1);
+        assert_eq!(
+            references[0].position,
+            Some(Position {
+                line: 9,
+                column: 13
+            })
+        );
+    }
+}